@@ -1,6 +1,16 @@
+use std::{env, path::PathBuf};
+
 fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let out_dir = PathBuf::from(env::var("OUT_DIR")?);
+
     tonic_build::configure()
         .build_server(true)
+        // JSON transcoding (de)serializes the messages with the proto3 JSON field names
+        .message_attribute(
+            ".quotes",
+            "#[derive(serde::Serialize, serde::Deserialize)] #[serde(rename_all = \"camelCase\", default)]",
+        )
+        .file_descriptor_set_path(out_dir.join("quotes_descriptor.bin"))
         .out_dir("src/server/proto")
         .compile_protos(&["proto/quotes.proto"], &["proto"])?;
     Ok(())
@@ -3,6 +3,12 @@ pub use sea_orm_migration::prelude::*;
 mod m1716794372_create_quotes_table;
 mod m1716794403_create_views_table;
 mod m1716796965_alter_quotes_table;
+mod m1792396800_create_tags_and_authors_tables;
+mod m1792483200_add_quotes_fingerprint;
+mod m1792656000_add_quotes_source;
+mod m1792742400_create_daily_quotes_table;
+mod m1792828800_add_quotes_moderation;
+mod m1792915200_add_views_feedback;
 
 pub struct Migrator;
 
@@ -13,6 +19,12 @@ impl MigratorTrait for Migrator {
             Box::new(m1716794372_create_quotes_table::Migration),
             Box::new(m1716794403_create_views_table::Migration),
             Box::new(m1716796965_alter_quotes_table::Migration),
+            Box::new(m1792396800_create_tags_and_authors_tables::Migration),
+            Box::new(m1792483200_add_quotes_fingerprint::Migration),
+            Box::new(m1792656000_add_quotes_source::Migration),
+            Box::new(m1792742400_create_daily_quotes_table::Migration),
+            Box::new(m1792828800_add_quotes_moderation::Migration),
+            Box::new(m1792915200_add_views_feedback::Migration),
         ]
     }
 }
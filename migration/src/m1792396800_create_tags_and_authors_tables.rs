@@ -0,0 +1,188 @@
+use super::m1716794372_create_quotes_table::Quotes;
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+/// Canonical tags that upstream sources are known to spell in different ways.
+const TAG_ALIASES: &[(&str, &str, &[&str])] = &[(
+    "motivational",
+    "Motivational",
+    &["motivation", "inspirational", "inspiration"],
+)];
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Tags::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(Tags::Slug).text().not_null().primary_key())
+                    .col(ColumnDef::new(Tags::Name).text().not_null())
+                    .col(
+                        ColumnDef::new(Tags::Aliases)
+                            .array(ColumnType::Text)
+                            .not_null()
+                            .default(Expr::cust("'{}'")),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(QuoteTags::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(QuoteTags::QuoteId).text().not_null())
+                    .col(ColumnDef::new(QuoteTags::Tag).text().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(QuoteTags::Table, QuoteTags::QuoteId)
+                            .to(Quotes::Table, Quotes::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(QuoteTags::Table, QuoteTags::Tag)
+                            .to(Tags::Table, Tags::Slug)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .primary_key(Index::create().col(QuoteTags::QuoteId).col(QuoteTags::Tag))
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(Authors::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Authors::Slug)
+                            .text()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(Authors::Name).text().not_null())
+                    .col(
+                        ColumnDef::new(Authors::Aliases)
+                            .array(ColumnType::Text)
+                            .not_null()
+                            .default(Expr::cust("'{}'")),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        let db = manager.get_connection();
+        for (slug, name, aliases) in TAG_ALIASES {
+            db.execute_unprepared(&format!(
+                "insert into tags (slug, name, aliases) values ('{slug}', '{name}', array['{}']) \
+                 on conflict (slug) do nothing",
+                aliases.join("', '")
+            ))
+            .await?;
+        }
+
+        let tag_slug = slugify("tag");
+        let canonical = format!(
+            "coalesce((select tags.slug from tags where {tag_slug} = any(tags.aliases)), {tag_slug})"
+        );
+
+        db.execute_unprepared(&format!(
+            "insert into tags (slug, name) \
+             select distinct on (slug) slug, {} from \
+             (select {canonical} as slug, tag from quotes, unnest(quotes.tags) as tag) as raw \
+             where slug <> '' order by slug, tag \
+             on conflict (slug) do nothing",
+            clean_name("tag")
+        ))
+        .await?;
+
+        db.execute_unprepared(&format!(
+            "update quotes set tags = array( \
+             select slug from \
+             (select {canonical} as slug, position from unnest(quotes.tags) with ordinality as raw(tag, position)) as canonical \
+             where slug <> '' group by slug order by min(position))"
+        ))
+        .await?;
+
+        db.execute_unprepared(
+            "insert into quote_tags (quote_id, tag) \
+             select quotes.id, tag from quotes, unnest(quotes.tags) as tag \
+             on conflict do nothing",
+        )
+        .await?;
+
+        let author_slug = slugify("quotes.author");
+        db.execute_unprepared(&format!(
+            "insert into authors (slug, name) \
+             select distinct on (slug) slug, {} from \
+             (select {author_slug} as slug, author from quotes) as raw \
+             where slug <> '' order by slug, author \
+             on conflict (slug) do nothing",
+            clean_name("author")
+        ))
+        .await?;
+
+        db.execute_unprepared(&format!(
+            "update quotes set author = authors.name from authors \
+             where authors.slug = {author_slug}"
+        ))
+        .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(QuoteTags::Table).to_owned())
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(Tags::Table).to_owned())
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(Authors::Table).to_owned())
+            .await
+    }
+}
+
+/// SQL counterpart of `database::canonical::slugify` used for the backfill.
+fn slugify(expr: &str) -> String {
+    format!("trim(both '-' from regexp_replace(lower(trim({expr})), '[^[:alnum:]]+', '-', 'g'))")
+}
+
+/// SQL counterpart of `database::canonical::clean_name` used for the backfill.
+fn clean_name(expr: &str) -> String {
+    format!("regexp_replace(trim({expr}), '\\s+', ' ', 'g')")
+}
+
+#[derive(DeriveIden)]
+enum Tags {
+    Table,
+    Slug,
+    Name,
+    Aliases,
+}
+
+#[derive(DeriveIden)]
+enum QuoteTags {
+    Table,
+    QuoteId,
+    Tag,
+}
+
+#[derive(DeriveIden)]
+enum Authors {
+    Table,
+    Slug,
+    Name,
+    Aliases,
+}
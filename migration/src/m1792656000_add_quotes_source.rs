@@ -0,0 +1,95 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                TableAlterStatement::new()
+                    .table(Quotes::Table)
+                    .add_column_if_not_exists(
+                        ColumnDef::new(Quotes::Source)
+                            .text()
+                            .not_null()
+                            .default("manual"),
+                    )
+                    .add_column_if_not_exists(ColumnDef::new(Quotes::ExternalId).text().null())
+                    .to_owned(),
+            )
+            .await?;
+
+        let db = manager.get_connection();
+
+        // Until now the dummyjson integer id was the primary key, everything else was added by hand.
+        db.execute_unprepared(
+            "update quotes set external_id = id, \
+             source = case when id ~ '^[0-9]+$' then 'dummyjson' else 'manual' end",
+        )
+        .await?;
+
+        db.execute_unprepared(
+            "alter table views drop constraint if exists views_quote_id_fkey, \
+             add constraint views_quote_id_fkey foreign key (quote_id) references quotes (id) \
+             on update cascade on delete cascade",
+        )
+        .await?;
+
+        db.execute_unprepared(&format!(
+            "update quotes set id = gen_random_uuid()::text where id !~ '{UUID_PATTERN}'"
+        ))
+        .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("quotes_source_external_id_key")
+                    .table(Quotes::Table)
+                    .col(Quotes::Source)
+                    .col(Quotes::ExternalId)
+                    .unique()
+                    .if_not_exists()
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("quotes_source_external_id_key")
+                    .table(Quotes::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        let db = manager.get_connection();
+        db.execute_unprepared(
+            "update quotes set id = external_id \
+             where source = 'dummyjson' and external_id is not null",
+        )
+        .await?;
+
+        manager
+            .alter_table(
+                TableAlterStatement::new()
+                    .table(Quotes::Table)
+                    .drop_column(Quotes::Source)
+                    .drop_column(Quotes::ExternalId)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+const UUID_PATTERN: &str = "^[0-9a-f]{8}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{12}$";
+
+#[derive(DeriveIden)]
+enum Quotes {
+    Table,
+    Source,
+    ExternalId,
+}
@@ -0,0 +1,63 @@
+use super::m1716794372_create_quotes_table::Quotes;
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(DailyQuotes::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(DailyQuotes::Day).date().not_null())
+                    .col(ColumnDef::new(DailyQuotes::Timezone).text().not_null())
+                    .col(
+                        ColumnDef::new(DailyQuotes::Tag)
+                            .text()
+                            .not_null()
+                            .default(""),
+                    )
+                    .col(ColumnDef::new(DailyQuotes::QuoteId).text().not_null())
+                    .col(
+                        ColumnDef::new(DailyQuotes::Overridden)
+                            .boolean()
+                            .not_null()
+                            .default(false),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(DailyQuotes::Table, DailyQuotes::QuoteId)
+                            .to(Quotes::Table, Quotes::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .primary_key(
+                        Index::create()
+                            .col(DailyQuotes::Day)
+                            .col(DailyQuotes::Timezone)
+                            .col(DailyQuotes::Tag),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(DailyQuotes::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum DailyQuotes {
+    Table,
+    Day,
+    Timezone,
+    Tag,
+    QuoteId,
+    Overridden,
+}
@@ -0,0 +1,177 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // The quotes stored so far have been shown to everyone, they stay visible.
+        manager
+            .alter_table(
+                TableAlterStatement::new()
+                    .table(Quotes::Table)
+                    .add_column_if_not_exists(
+                        ColumnDef::new(Quotes::Status)
+                            .text()
+                            .not_null()
+                            .default("approved"),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("quotes_status_idx")
+                    .table(Quotes::Table)
+                    .col(Quotes::Status)
+                    .if_not_exists()
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(QuoteReports::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(QuoteReports::QuoteId).text().not_null())
+                    .col(ColumnDef::new(QuoteReports::UserId).text().not_null())
+                    .col(
+                        ColumnDef::new(QuoteReports::Reason)
+                            .text()
+                            .not_null()
+                            .default(""),
+                    )
+                    .col(
+                        ColumnDef::new(QuoteReports::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(
+                        ColumnDef::new(QuoteReports::ResolvedAt)
+                            .timestamp_with_time_zone()
+                            .null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(QuoteReports::Table, QuoteReports::QuoteId)
+                            .to(Quotes::Table, Quotes::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .primary_key(
+                        Index::create()
+                            .col(QuoteReports::QuoteId)
+                            .col(QuoteReports::UserId),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(ModerationAudit::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(ModerationAudit::Id)
+                            .text()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(ModerationAudit::QuoteId).text().not_null())
+                    .col(
+                        ColumnDef::new(ModerationAudit::PreviousStatus)
+                            .text()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(ModerationAudit::Status).text().not_null())
+                    .col(ColumnDef::new(ModerationAudit::Moderator).text().not_null())
+                    .col(
+                        ColumnDef::new(ModerationAudit::Note)
+                            .text()
+                            .not_null()
+                            .default(""),
+                    )
+                    .col(
+                        ColumnDef::new(ModerationAudit::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    // the trail is kept, merges move it to the kept quote before deleting one
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(ModerationAudit::Table, ModerationAudit::QuoteId)
+                            .to(Quotes::Table, Quotes::Id)
+                            .on_delete(ForeignKeyAction::Restrict)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("moderation_audit_quote_id_idx")
+                    .table(ModerationAudit::Table)
+                    .col(ModerationAudit::QuoteId)
+                    .if_not_exists()
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(ModerationAudit::Table).to_owned())
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(QuoteReports::Table).to_owned())
+            .await?;
+
+        manager
+            .alter_table(
+                TableAlterStatement::new()
+                    .table(Quotes::Table)
+                    .drop_column(Quotes::Status)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Quotes {
+    Table,
+    Id,
+    Status,
+}
+
+#[derive(DeriveIden)]
+enum QuoteReports {
+    Table,
+    QuoteId,
+    UserId,
+    Reason,
+    CreatedAt,
+    ResolvedAt,
+}
+
+#[derive(DeriveIden)]
+enum ModerationAudit {
+    Table,
+    Id,
+    QuoteId,
+    PreviousStatus,
+    Status,
+    Moderator,
+    Note,
+    CreatedAt,
+}
@@ -0,0 +1,36 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // `dislike` or `skip`, null until the user gives feedback on the viewed quote.
+        manager
+            .alter_table(
+                TableAlterStatement::new()
+                    .table(Views::Table)
+                    .add_column_if_not_exists(ColumnDef::new(Views::Feedback).text().null())
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                TableAlterStatement::new()
+                    .table(Views::Table)
+                    .drop_column(Views::Feedback)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Views {
+    Table,
+    Feedback,
+}
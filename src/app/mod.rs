@@ -1,52 +1,90 @@
 use std::sync::Arc;
 use tokio::signal;
 
+use crate::catalogue;
 use crate::config::GlobalConfig;
 use crate::database::seaorm::SeaORM;
+use crate::events;
 use crate::heartbeat;
 use crate::quote;
 use crate::quote_api;
 use crate::server;
 
 pub async fn start(cfg: GlobalConfig) {
+    server::init_logger();
+
     let db = SeaORM::new(&cfg.orm_config)
         .await
         .expect("failed to start database");
 
     let db = Arc::new(db);
+    let events = events::Bus::new();
     let heartbeat = heartbeat::Heartbeat::new(db.clone());
-    let quote_api = quote_api::Service::new(db.clone());
-    let quote = quote::Service::new(&cfg.quotes_config, db, Arc::new(quote_api));
+    let quote_api = quote_api::Service::new(&cfg.quote_api_config, db.clone(), events.clone())
+        .expect("failed to start quote api");
+    if cfg.quote_api_config.seed_empty_database {
+        let seeded = quote_api.seed().await.expect("failed to seed quotes");
+        if seeded > 0 {
+            log::info!("seeded the database with {seeded} quotes");
+        }
+    }
+    quote_api.spawn_prefetch();
+    let catalogue = catalogue::Service::new(&cfg.catalogue_config, db.clone());
+    let quote = quote::Service::new(&cfg.quotes_config, db, Arc::new(quote_api), events.clone());
 
     if cfg.server_config.service_type.eq("actix") {
-        server::start_actix(&cfg.server_config, heartbeat, quote)
-            .await
-            .expect("failed to create server")
-            .await
-            .expect("failed to start server");
+        server::start_actix(
+            &cfg.server_config,
+            &cfg.graphql_config,
+            &cfg.cors_config,
+            heartbeat,
+            quote,
+            catalogue,
+            events,
+        )
+        .await
+        .expect("failed to create server")
+        .await
+        .expect("failed to start server");
     } else if cfg.server_config.service_type.eq("rocket") {
-        server::start_rocket(&cfg.server_config, heartbeat, quote)
-            .await
-            .expect("failed to create server");
+        server::start_rocket(
+            &cfg.server_config,
+            &cfg.graphql_config,
+            &cfg.cors_config,
+            heartbeat,
+            quote,
+            catalogue,
+            events,
+        )
+        .await
+        .expect("failed to create server");
     } else if cfg.server_config.service_type.eq("axum") {
-        tracing_subscriber::fmt()
-            .with_max_level(tracing::Level::INFO)
-            .with_target(false)
-            .compact()
-            .init();
-
-        let (listener, app) = server::start_axum(&cfg.server_config, heartbeat, quote)
-            .await
-            .expect("failed to create server");
+        let (listener, app) = server::start_axum(
+            &cfg.server_config,
+            &cfg.graphql_config,
+            &cfg.cors_config,
+            heartbeat,
+            quote,
+            catalogue,
+            events,
+        )
+        .await
+        .expect("failed to create server");
 
         axum::serve(listener, app)
             .with_graceful_shutdown(shutdown_signal())
             .await
             .expect("failed to start server");
     } else if cfg.server_config.service_type.eq("grpc") {
-        server::start_grpc(&cfg.server_config, heartbeat, quote)
-            .await
-            .expect("failed to create server");
+        server::start_grpc(
+            &cfg.server_config,
+            &cfg.cors_config,
+            heartbeat,
+            quote,
+            catalogue,
+        )
+        .await
+        .expect("failed to create server");
     }
 }
 
@@ -0,0 +1,44 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::RwLock;
+
+/// In-process cache whose entries expire after a fixed time to live.
+/// A zero TTL disables the cache: nothing is stored and every lookup misses.
+#[derive(Clone)]
+pub struct TtlCache<K, V> {
+    ttl: Duration,
+    entries: Arc<RwLock<HashMap<K, (Instant, V)>>>,
+}
+
+impl<K: Eq + Hash, V: Clone> TtlCache<K, V> {
+    pub async fn get(&self, key: &K) -> Option<V> {
+        let entries = self.entries.read().await;
+        match entries.get(key) {
+            Some((stored_at, value)) if stored_at.elapsed() < self.ttl => Some(value.clone()),
+            _ => None,
+        }
+    }
+
+    pub async fn insert(&self, key: K, value: V) {
+        if self.ttl.is_zero() {
+            return;
+        }
+
+        let mut entries = self.entries.write().await;
+        entries.retain(|_, (stored_at, _)| stored_at.elapsed() < self.ttl);
+        entries.insert(key, (Instant::now(), value));
+    }
+
+    pub async fn remove(&self, key: &K) {
+        self.entries.write().await.remove(key);
+    }
+
+    pub fn new(ttl: Duration) -> Self {
+        TtlCache {
+            ttl,
+            entries: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
+}
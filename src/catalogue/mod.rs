@@ -0,0 +1,185 @@
+pub mod structs;
+pub mod traits;
+
+use anyhow::{Context, Result};
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::cache::TtlCache;
+use crate::config::CatalogueConfig;
+
+use structs::{Author, AuthorSort, ListParams, Page, Tag, TagSort};
+pub use traits::Database;
+
+#[derive(Clone)]
+pub struct Service {
+    db: Arc<dyn Database + Send + Sync>,
+    tags: TtlCache<ListParams<TagSort>, Page<Tag>>,
+    authors: TtlCache<ListParams<AuthorSort>, Page<Author>>,
+}
+
+impl Service {
+    pub async fn get_tags(&self, params: ListParams<TagSort>) -> Result<Page<Tag>> {
+        if let Some(page) = self.tags.get(&params).await {
+            return Ok(page);
+        }
+
+        let (tags, total) = self
+            .db
+            .get_tags(&params)
+            .await
+            .context("failed to get tags")?;
+
+        let page = Page {
+            items: tags,
+            total,
+            limit: params.limit,
+            offset: params.offset,
+        };
+
+        self.tags.insert(params, page.clone()).await;
+        Ok(page)
+    }
+
+    pub async fn get_authors(&self, params: ListParams<AuthorSort>) -> Result<Page<Author>> {
+        if let Some(page) = self.authors.get(&params).await {
+            return Ok(page);
+        }
+
+        let (authors, total) = self
+            .db
+            .get_authors(&params)
+            .await
+            .context("failed to get authors")?;
+
+        let page = Page {
+            items: authors,
+            total,
+            limit: params.limit,
+            offset: params.offset,
+        };
+
+        self.authors.insert(params, page.clone()).await;
+        Ok(page)
+    }
+
+    pub async fn get_tag(&self, name: &str) -> Result<Tag> {
+        self.db.get_tag(name).await.context("failed to get tag")
+    }
+
+    pub async fn get_author(&self, name: &str) -> Result<Author> {
+        self.db
+            .get_author(name)
+            .await
+            .context("failed to get author")
+    }
+
+    pub fn new(cfg: &CatalogueConfig, db: Arc<dyn Database + Send + Sync>) -> Self {
+        let ttl = Duration::from_secs(cfg.cache_ttl);
+        Service {
+            db,
+            tags: TtlCache::new(ttl),
+            authors: TtlCache::new(ttl),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fake::{faker::name, Fake, Faker};
+    use mockall::predicate::*;
+    use std::sync::LazyLock;
+
+    use super::*;
+    use crate::catalogue::traits::MockDatabase;
+
+    static TAGS: LazyLock<Vec<Tag>> = LazyLock::new(|| {
+        vec![Tag {
+            name: Faker.fake(),
+            quotes: (1..100).fake(),
+        }]
+    });
+    static AUTHORS: LazyLock<Vec<Author>> = LazyLock::new(|| {
+        vec![Author {
+            name: name::en::Name().fake(),
+            quotes: (1..100).fake(),
+            likes: (1..100).fake(),
+        }]
+    });
+
+    #[tokio::test]
+    async fn test_get_tags_success() {
+        let params = ListParams::<TagSort>::parse(None, None, None, None).unwrap();
+
+        let mut db = MockDatabase::new();
+        db.expect_get_tags()
+            .with(eq(params))
+            .times(1)
+            .returning(|_| Ok((TAGS.clone(), 1)));
+
+        let service = Service::new(&CatalogueConfig { cache_ttl: 60 }, Arc::new(db));
+        for _ in 0..2 {
+            let res = service.get_tags(params).await;
+            assert!(res.is_ok());
+            assert_eq!(
+                res.unwrap(),
+                Page {
+                    items: TAGS.clone(),
+                    total: 1,
+                    limit: structs::DEFAULT_LIMIT,
+                    offset: 0,
+                }
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_get_authors_success() {
+        let params =
+            ListParams::<AuthorSort>::parse(Some("likes"), Some("asc"), Some(5), Some(10)).unwrap();
+
+        let mut db = MockDatabase::new();
+        db.expect_get_authors()
+            .with(eq(params))
+            .times(2)
+            .returning(|_| Ok((AUTHORS.clone(), 11)));
+
+        let service = Service::new(&CatalogueConfig::default(), Arc::new(db));
+        for _ in 0..2 {
+            let res = service.get_authors(params).await;
+            assert!(res.is_ok());
+            assert_eq!(
+                res.unwrap(),
+                Page {
+                    items: AUTHORS.clone(),
+                    total: 11,
+                    limit: 5,
+                    offset: 10,
+                }
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_get_author_success() {
+        let author = AUTHORS[0].clone();
+
+        let mut db = MockDatabase::new();
+        db.expect_get_author()
+            .with(eq(author.name.clone()))
+            .times(1)
+            .returning(|_| Ok(AUTHORS[0].clone()));
+
+        let service = Service::new(&CatalogueConfig::default(), Arc::new(db));
+        let res = service.get_author(&author.name).await;
+        assert!(res.is_ok());
+        assert_eq!(res.unwrap(), author);
+    }
+
+    #[test]
+    fn test_list_params_invalid() {
+        assert!(ListParams::<TagSort>::parse(Some("likes"), None, None, None).is_err());
+        assert!(ListParams::<AuthorSort>::parse(None, Some("up"), None, None).is_err());
+        assert!(ListParams::<AuthorSort>::parse(None, None, Some(1000), None).is_err());
+    }
+}
@@ -0,0 +1,158 @@
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+use utoipa::ToSchema;
+
+pub const DEFAULT_LIMIT: u64 = 20;
+pub const MAX_LIMIT: u64 = 100;
+
+#[derive(PartialEq, Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct Tag {
+    pub name: String,
+    /// The number of quotes with this tag
+    pub quotes: i64,
+}
+
+#[derive(PartialEq, Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct Author {
+    pub name: String,
+    /// The number of quotes by this author
+    pub quotes: i64,
+    /// The total number of likes of the author's quotes
+    pub likes: i64,
+}
+
+#[derive(PartialEq, Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    /// The number of all items
+    pub total: u64,
+    pub limit: u64,
+    pub offset: u64,
+}
+
+#[derive(PartialEq, Eq, Hash, Debug, Clone, Copy)]
+pub enum Order {
+    Asc,
+    Desc,
+}
+
+impl FromStr for Order {
+    type Err = anyhow::Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        match value {
+            "asc" => Ok(Order::Asc),
+            "desc" => Ok(Order::Desc),
+            _ => Err(anyhow!("unknown order: {value}")),
+        }
+    }
+}
+
+pub trait Sort: FromStr<Err = anyhow::Error> + Default + Copy {
+    /// Order used when the client does not specify one: alphabetical for names,
+    /// biggest first for counters.
+    fn default_order(&self) -> Order;
+}
+
+#[derive(PartialEq, Eq, Hash, Debug, Clone, Copy, Default)]
+pub enum TagSort {
+    Name,
+    #[default]
+    Quotes,
+}
+
+impl FromStr for TagSort {
+    type Err = anyhow::Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        match value {
+            "name" => Ok(TagSort::Name),
+            "quotes" => Ok(TagSort::Quotes),
+            _ => Err(anyhow!("unknown tag sort: {value}")),
+        }
+    }
+}
+
+impl Sort for TagSort {
+    fn default_order(&self) -> Order {
+        match self {
+            TagSort::Name => Order::Asc,
+            TagSort::Quotes => Order::Desc,
+        }
+    }
+}
+
+#[derive(PartialEq, Eq, Hash, Debug, Clone, Copy, Default)]
+pub enum AuthorSort {
+    Name,
+    #[default]
+    Quotes,
+    Likes,
+}
+
+impl FromStr for AuthorSort {
+    type Err = anyhow::Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        match value {
+            "name" => Ok(AuthorSort::Name),
+            "quotes" => Ok(AuthorSort::Quotes),
+            "likes" => Ok(AuthorSort::Likes),
+            _ => Err(anyhow!("unknown author sort: {value}")),
+        }
+    }
+}
+
+impl Sort for AuthorSort {
+    fn default_order(&self) -> Order {
+        match self {
+            AuthorSort::Name => Order::Asc,
+            AuthorSort::Quotes | AuthorSort::Likes => Order::Desc,
+        }
+    }
+}
+
+#[derive(PartialEq, Eq, Hash, Debug, Clone, Copy)]
+pub struct ListParams<S> {
+    pub sort: S,
+    pub order: Order,
+    pub limit: u64,
+    pub offset: u64,
+}
+
+impl<S: Sort> ListParams<S> {
+    /// Builds list parameters from raw client input. Empty strings are treated as
+    /// missing values so that transports without optional fields (gRPC) can reuse it.
+    pub fn parse(
+        sort: Option<&str>,
+        order: Option<&str>,
+        limit: Option<u64>,
+        offset: Option<u64>,
+    ) -> Result<Self> {
+        let sort = match sort.filter(|sort| !sort.is_empty()) {
+            Some(sort) => sort.parse()?,
+            None => S::default(),
+        };
+
+        let order = match order.filter(|order| !order.is_empty()) {
+            Some(order) => order.parse()?,
+            None => sort.default_order(),
+        };
+
+        let limit = match limit.filter(|limit| *limit != 0) {
+            Some(limit) if limit > MAX_LIMIT => {
+                return Err(anyhow!("limit must not be greater than {MAX_LIMIT}"))
+            }
+            Some(limit) => limit,
+            None => DEFAULT_LIMIT,
+        };
+
+        Ok(ListParams {
+            sort,
+            order,
+            limit,
+            offset: offset.unwrap_or_default(),
+        })
+    }
+}
@@ -0,0 +1,16 @@
+use anyhow::Result;
+use async_trait::async_trait;
+
+use super::structs::{Author, AuthorSort, ListParams, Tag, TagSort};
+
+#[cfg(test)]
+use mockall::automock;
+
+#[cfg_attr(test, automock)]
+#[async_trait]
+pub trait Database {
+    async fn get_tags(&self, params: &ListParams<TagSort>) -> Result<(Vec<Tag>, u64)>;
+    async fn get_authors(&self, params: &ListParams<AuthorSort>) -> Result<(Vec<Author>, u64)>;
+    async fn get_tag(&self, name: &str) -> Result<Tag>;
+    async fn get_author(&self, name: &str) -> Result<Author>;
+}
@@ -12,6 +12,18 @@ pub struct GlobalConfig {
 
     #[envconfig(nested)]
     pub quotes_config: QuotesConfig,
+
+    #[envconfig(nested)]
+    pub quote_api_config: QuoteApiConfig,
+
+    #[envconfig(nested)]
+    pub catalogue_config: CatalogueConfig,
+
+    #[envconfig(nested)]
+    pub graphql_config: GraphQLConfig,
+
+    #[envconfig(nested)]
+    pub cors_config: CorsConfig,
 }
 
 impl GlobalConfig {
@@ -27,16 +39,196 @@ pub struct ServerConfig {
 
     #[envconfig(from = "SERVICE_TYPE")]
     pub service_type: String,
+
+    #[envconfig(from = "ADMIN_TOKEN", default = "")]
+    pub admin_token: String,
+
+    /// Seconds clients may reuse the quotes, tags and authors read over HTTP without revalidating them.
+    #[envconfig(from = "HTTP_CACHE_MAX_AGE", default = "60")]
+    pub http_cache_max_age: u64,
 }
 
 #[derive(Envconfig, Debug, Clone, Deserialize, Default)]
 pub struct ORMConfig {
     #[envconfig(from = "DSN")]
     pub dsn: String,
+
+    /// Seconds the quotes read by id are kept in memory, zero disables the cache.
+    #[envconfig(from = "QUOTE_CACHE_TTL", default = "0")]
+    pub quote_cache_ttl: u64,
 }
 
 #[derive(Envconfig, Debug, Clone, Deserialize, Default)]
 pub struct QuotesConfig {
     #[envconfig(from = "RANDOM_QUOTE_CHANCE")]
     pub random_quote_chance: f64,
+
+    #[envconfig(from = "DAILY_QUOTE_TIMEZONE", default = "UTC")]
+    pub daily_quote_timezone: String,
+
+    #[envconfig(from = "DAILY_QUOTE_WEIGHTED_BY_LIKES", default = "true")]
+    pub daily_quote_weighted_by_likes: bool,
+
+    /// Open reports sending an approved quote back to review, zero leaves the quotes visible
+    /// until an admin hides them.
+    #[envconfig(from = "MODERATION_REPORT_THRESHOLD", default = "3")]
+    pub moderation_report_threshold: u64,
+
+    /// Penalty given to the author and the tags of a disliked quote, a quote's weight for
+    /// the user is divided by one plus the penalties of its author and tags.
+    #[envconfig(from = "FEEDBACK_DISLIKE_PENALTY", default = "1.0")]
+    pub feedback_dislike_penalty: f64,
+
+    /// Penalty of a skipped quote, zero ignores the skips.
+    #[envconfig(from = "FEEDBACK_SKIP_PENALTY", default = "0.25")]
+    pub feedback_skip_penalty: f64,
+}
+
+/// Client of the site the new random quotes are fetched from.
+#[derive(Envconfig, Debug, Clone, Deserialize, Default)]
+pub struct QuoteApiConfig {
+    #[envconfig(
+        from = "QUOTE_API_URL",
+        default = "https://dummyjson.com/quotes/random"
+    )]
+    pub url: String,
+
+    #[envconfig(from = "QUOTE_API_CONNECT_TIMEOUT_MS", default = "2000")]
+    pub connect_timeout_ms: u64,
+
+    /// Longest wait for the next bytes of the response.
+    #[envconfig(from = "QUOTE_API_READ_TIMEOUT_MS", default = "3000")]
+    pub read_timeout_ms: u64,
+
+    /// Retries of a failed fetch, the delay before each one doubles and is jittered.
+    #[envconfig(from = "QUOTE_API_RETRIES", default = "2")]
+    pub retries: u32,
+
+    #[envconfig(from = "QUOTE_API_RETRY_BACKOFF_MS", default = "100")]
+    pub retry_backoff_ms: u64,
+
+    /// Failed fetches in a row opening the circuit, zero disables the circuit breaker.
+    #[envconfig(from = "QUOTE_API_FAILURE_THRESHOLD", default = "5")]
+    pub failure_threshold: u32,
+
+    /// Seconds the site is skipped for once the circuit is open.
+    #[envconfig(from = "QUOTE_API_OPEN_CIRCUIT_SECS", default = "30")]
+    pub open_circuit_secs: u64,
+
+    /// Proxy of the requests, the `HTTPS_PROXY` like variables are used when empty.
+    #[envconfig(from = "QUOTE_API_PROXY", default = "")]
+    pub proxy: String,
+
+    /// `quotes-rs/<version>` when empty.
+    #[envconfig(from = "QUOTE_API_USER_AGENT", default = "")]
+    pub user_agent: String,
+
+    /// Largest response of the site accepted, zero doesn't limit it.
+    #[envconfig(from = "QUOTE_API_MAX_BODY_BYTES", default = "65536")]
+    pub max_body_bytes: usize,
+
+    /// Bounds of the sanitized quote text in characters, a zero maximum doesn't limit it.
+    #[envconfig(from = "QUOTE_MIN_LENGTH", default = "3")]
+    pub min_length: usize,
+
+    #[envconfig(from = "QUOTE_MAX_LENGTH", default = "500")]
+    pub max_length: usize,
+
+    #[envconfig(from = "QUOTE_MAX_AUTHOR_LENGTH", default = "100")]
+    pub max_author_length: usize,
+
+    /// Comma separated words rejecting the quotes containing them, case insensitively.
+    #[envconfig(from = "QUOTE_BLOCKED_WORDS", default = "")]
+    pub blocked_words: String,
+
+    /// Comma separated authors whose quotes are rejected.
+    #[envconfig(from = "QUOTE_BLOCKED_AUTHORS", default = "")]
+    pub blocked_authors: String,
+
+    /// Comma separated ISO 639-3 codes of the accepted languages, e.g. `eng`. Any language is
+    /// accepted when empty.
+    #[envconfig(from = "QUOTE_LANGUAGES", default = "")]
+    pub languages: String,
+
+    /// Quotes fetched from a provider until one passes the validation.
+    #[envconfig(from = "QUOTE_VALIDATION_ATTEMPTS", default = "3")]
+    pub validation_attempts: u32,
+
+    /// Draws the new quotes from the corpus instead of the site.
+    #[envconfig(from = "QUOTE_API_OFFLINE", default = "false")]
+    pub offline: bool,
+
+    /// JSON array or CSV file of quotes with `quote`, `author` and `tags` fields, the CSV tags
+    /// are separated by `;`. The bundled corpus is used when empty.
+    #[envconfig(from = "QUOTE_CORPUS_PATH", default = "")]
+    pub corpus_path: String,
+
+    /// Saves the corpus quotes on start when the database has no quotes.
+    #[envconfig(from = "QUOTE_SEED_EMPTY_DATABASE", default = "true")]
+    pub seed_empty_database: bool,
+
+    /// Quotes fetched ahead of the requests, zero fetches them while the requests wait.
+    #[envconfig(from = "QUOTE_PREFETCH_SIZE", default = "20")]
+    pub prefetch_size: usize,
+
+    /// Milliseconds between the refills of the prefetched quotes.
+    #[envconfig(from = "QUOTE_PREFETCH_INTERVAL_MS", default = "5000")]
+    pub prefetch_interval_ms: u64,
+
+    /// Most quotes fetched from a provider per refill as comma separated `name=count` pairs,
+    /// e.g. `dummyjson=5`. Unlisted providers are not limited.
+    #[envconfig(from = "QUOTE_PREFETCH_QUOTAS", default = "")]
+    pub prefetch_quotas: String,
+}
+
+#[derive(Envconfig, Debug, Clone, Deserialize, Default)]
+pub struct CatalogueConfig {
+    #[envconfig(from = "CATALOGUE_CACHE_TTL", default = "0")]
+    pub cache_ttl: u64,
+}
+
+/// Limits of the `/graphql` and `/subscriptions` endpoints, a zero limit is disabled.
+#[derive(Envconfig, Debug, Clone, Deserialize, Default)]
+pub struct GraphQLConfig {
+    #[envconfig(from = "GRAPHQL_MAX_DEPTH", default = "10")]
+    pub max_depth: usize,
+
+    #[envconfig(from = "GRAPHQL_MAX_COMPLEXITY", default = "1000")]
+    pub max_complexity: u64,
+
+    #[envconfig(from = "GRAPHQL_MAX_QUERY_SIZE", default = "8192")]
+    pub max_query_size: usize,
+
+    /// Operations accepted in a batch request.
+    #[envconfig(from = "GRAPHQL_MAX_BATCH_SIZE", default = "10")]
+    pub max_batch_size: usize,
+
+    /// Path of the JSON file mapping the sha256 hashes of the queries to the queries.
+    #[envconfig(from = "GRAPHQL_PERSISTED_QUERIES", default = "")]
+    pub persisted_queries: String,
+
+    /// Rejects the queries missing from the persisted ones.
+    #[envconfig(from = "GRAPHQL_PERSISTED_QUERIES_ONLY", default = "false")]
+    pub persisted_queries_only: bool,
+}
+
+/// CORS policy of the HTTP servers, the lists are comma separated and `*` allows anything.
+#[derive(Envconfig, Debug, Clone, Deserialize, Default)]
+pub struct CorsConfig {
+    #[envconfig(from = "CORS_ALLOWED_ORIGINS", default = "*")]
+    pub allowed_origins: String,
+
+    #[envconfig(from = "CORS_ALLOWED_METHODS", default = "GET,POST,PUT,PATCH,OPTIONS")]
+    pub allowed_methods: String,
+
+    /// `*` allows the headers requested by the browser, `Authorization` included.
+    #[envconfig(from = "CORS_ALLOWED_HEADERS", default = "*")]
+    pub allowed_headers: String,
+
+    #[envconfig(from = "CORS_ALLOW_CREDENTIALS", default = "false")]
+    pub allow_credentials: bool,
+
+    /// Seconds the browsers may cache a preflight response for.
+    #[envconfig(from = "CORS_MAX_AGE", default = "300")]
+    pub max_age: u64,
 }
@@ -0,0 +1,71 @@
+use sha2::{Digest, Sha256};
+
+/// Reduces a tag or an author name to the form used as its primary key:
+/// lowercase alphanumeric words joined with dashes, so that "Famous Quotes",
+/// "famous-quotes" and " famous_quotes " all end up as "famous-quotes".
+pub fn slugify(value: &str) -> String {
+    let mut slug = String::with_capacity(value.len());
+    for char in value.trim().to_lowercase().chars() {
+        if char.is_alphanumeric() {
+            slug.push(char);
+        } else if !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+
+    slug.trim_matches('-').to_string()
+}
+
+/// Display form of a tag or an author name: trimmed, with single spaces between words.
+pub fn clean_name(value: &str) -> String {
+    value.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Hash of the quote text that ignores case, whitespace, punctuation and typographic
+/// quotes, so the same quote coming from different sources maps to one value.
+pub fn fingerprint(quote: &str) -> String {
+    let mut normalized = String::with_capacity(quote.len());
+    for char in quote.to_lowercase().chars() {
+        if char.is_alphanumeric() {
+            normalized.push(char);
+        } else if !normalized.is_empty() && !normalized.ends_with(' ') {
+            normalized.push(' ');
+        }
+    }
+
+    format!("{:x}", Sha256::digest(normalized.trim_end()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_slugify() {
+        assert_eq!(slugify("Motivational"), "motivational");
+        assert_eq!(slugify(" Famous  Quotes! "), "famous-quotes");
+        assert_eq!(slugify("famous_quotes"), "famous-quotes");
+        assert_eq!(slugify("Marcus Aurelius"), "marcus-aurelius");
+        assert_eq!(slugify("--"), "");
+    }
+
+    #[test]
+    fn test_fingerprint() {
+        let expected = fingerprint("Stay hungry, stay foolish.");
+        assert_eq!(fingerprint("  stay HUNGRY stay foolish!"), expected);
+        assert_eq!(
+            fingerprint("\u{201c}Stay hungry\u{2014}stay foolish\u{201d}"),
+            expected
+        );
+        assert_ne!(fingerprint("Stay hungry, stay curious."), expected);
+        assert_eq!(
+            fingerprint("Don\u{2019}t panic."),
+            fingerprint("don't   panic")
+        );
+    }
+
+    #[test]
+    fn test_clean_name() {
+        assert_eq!(clean_name(" Steve  Jobs "), "Steve Jobs");
+    }
+}
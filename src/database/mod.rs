@@ -1,3 +1,4 @@
+pub mod canonical;
 pub mod errors;
 pub mod seaorm;
 pub mod structs;
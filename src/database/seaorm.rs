@@ -1,31 +1,95 @@
 use anyhow::{anyhow, Context, Result};
 use async_trait::async_trait;
+use chrono::{NaiveDate, Utc};
 use migration::{Migrator, MigratorTrait};
-use sea_orm::sea_query::SimpleExpr;
-use sea_orm::ActiveValue::Set;
-use sea_orm::{sea_query, ConnectOptions, QueryOrder};
+use sea_orm::sea_query::Expr;
+use sea_orm::ActiveValue::{NotSet, Set};
 use sea_orm::{
-    ActiveModelTrait, ColumnTrait, Database, DatabaseConnection, EntityTrait, QueryFilter,
-    QuerySelect, QueryTrait,
+    sea_query, Condition, ConnectOptions, ConnectionTrait, DbBackend, FromQueryResult, QueryOrder,
+    Statement, TransactionTrait,
 };
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, Database, DatabaseConnection, EntityTrait, PaginatorTrait,
+    QueryFilter, QuerySelect, QueryTrait,
+};
+use std::collections::HashMap;
+use std::time::Duration;
+use uuid::Uuid;
 
+use crate::cache::TtlCache;
+use crate::catalogue::structs::{Author, AuthorSort, ListParams, Order, Tag, TagSort};
 use crate::config::ORMConfig;
+use crate::quote::structs::{
+    Feedback, ModerationDecision, ModerationStatus, QuoteFilter, QuoteReport,
+};
 use crate::{
-    heartbeat as heartbeat_service, quote as quote_service, quote_api as quote_api_service,
+    catalogue as catalogue_service, heartbeat as heartbeat_service, quote as quote_service,
+    quote_api as quote_api_service,
 };
 
+use super::canonical::{clean_name, fingerprint, slugify};
 use super::errors::Error::ErrNotFound;
+use super::structs::authors::ActiveModel as authors_active_model;
+use super::structs::authors::Column as authors_columns;
+use super::structs::daily_quotes::ActiveModel as daily_quotes_active_model;
+use super::structs::daily_quotes::Column as daily_quotes_columns;
+use super::structs::daily_quotes::Model as daily_quotes_model;
+use super::structs::moderation_audit::ActiveModel as moderation_audit_active_model;
+use super::structs::moderation_audit::Column as moderation_audit_columns;
+use super::structs::moderation_audit::Model as moderation_audit_model;
+use super::structs::prelude::Authors as authors;
+use super::structs::prelude::DailyQuotes as daily_quotes;
+use super::structs::prelude::ModerationAudit as moderation_audit;
+use super::structs::prelude::QuoteReports as quote_reports;
+use super::structs::prelude::QuoteTags as quote_tags;
 use super::structs::prelude::Quotes as quotes;
+use super::structs::prelude::Tags as tags;
 use super::structs::prelude::Views as views;
+use super::structs::quote_reports::ActiveModel as quote_reports_active_model;
+use super::structs::quote_reports::Column as quote_reports_columns;
+use super::structs::quote_reports::Model as quote_reports_model;
+use super::structs::quote_tags::ActiveModel as quote_tags_active_model;
+use super::structs::quote_tags::Column as quote_tags_columns;
 use super::structs::quotes::ActiveModel as quotes_active_model;
 use super::structs::quotes::Column as quotes_columns;
 use super::structs::quotes::Model as quotes_model;
+use super::structs::tags::ActiveModel as tags_active_model;
+use super::structs::tags::Column as tags_columns;
 use super::structs::views::ActiveModel as views_active_model;
 use super::structs::views::Column as views_columns;
 use super::structs::views::Model as views_model;
 
+/// Attempts at saving a quote racing with concurrent saves of the same one.
+const SAVE_QUOTE_ATTEMPTS: usize = 3;
+
 pub struct SeaORM {
     db: DatabaseConnection,
+    /// Quotes read by id, refreshed when they are liked or saved again.
+    quotes: TtlCache<String, quotes_model>,
+}
+
+#[derive(FromQueryResult)]
+struct TagRow {
+    name: String,
+    quote_count: i64,
+}
+
+#[derive(FromQueryResult)]
+struct AuthorRow {
+    name: String,
+    quote_count: i64,
+    like_count: i64,
+}
+
+#[derive(FromQueryResult)]
+struct ViewCountRow {
+    quote_id: String,
+    view_count: i64,
+}
+
+#[derive(FromQueryResult)]
+struct TotalRow {
+    total: i64,
 }
 
 impl SeaORM {
@@ -34,6 +98,18 @@ impl SeaORM {
     }
 
     pub async fn get_quote(&self, quote_id: &str) -> Result<quotes_model> {
+        let key = quote_id.to_string();
+        if let Some(quote) = self.quotes.get(&key).await {
+            return Ok(quote);
+        }
+
+        let quote = self.find_quote(quote_id).await?;
+        self.quotes.insert(key, quote.clone()).await;
+        Ok(quote)
+    }
+
+    /// Reads the quote bypassing the cache.
+    async fn find_quote(&self, quote_id: &str) -> Result<quotes_model> {
         let quote = quotes::find_by_id(quote_id).one(&self.db).await?;
         match quote {
             Some(quote) => Ok(quote),
@@ -41,6 +117,10 @@ impl SeaORM {
         }
     }
 
+    async fn count_quotes(&self) -> Result<u64> {
+        Ok(quotes::find().count(&self.db).await?)
+    }
+
     async fn get_quotes(&self, user_id: &str) -> Result<Vec<quotes_model>> {
         let viewed = quotes::find()
             .select_only()
@@ -50,6 +130,7 @@ impl SeaORM {
 
         Ok(quotes::find()
             .filter(quotes_columns::Id.not_in_subquery(viewed.as_query().to_owned()))
+            .filter(quotes_columns::Status.eq(ModerationStatus::Approved.as_str()))
             .order_by_desc(quotes_columns::Likes)
             .all(&self.db)
             .await?)
@@ -60,34 +141,77 @@ impl SeaORM {
         user_id: &str,
         viewed_quote: &quotes_model,
     ) -> Result<quotes_model> {
+        match self.get_same_quotes(user_id, viewed_quote, 1).await?.pop() {
+            Some(quote) => Ok(quote),
+            None => Err(anyhow!(ErrNotFound)),
+        }
+    }
+
+    /// Returns unseen quotes ordered by the number of shared tags, then by author and likes.
+    async fn get_same_quotes(
+        &self,
+        user_id: &str,
+        viewed_quote: &quotes_model,
+        limit: u64,
+    ) -> Result<Vec<quotes_model>> {
         let viewed = quotes::find()
             .select_only()
             .column(quotes_columns::Id)
             .inner_join(views)
             .filter(views_columns::UserId.eq(user_id));
 
-        let tags = format!(
-            "cardinality(array(select unnest(quotes.tags) intersect select unnest(array['{}'])))",
-            viewed_quote.tags.clone().join("', '")
+        let same_tags = Expr::cust_with_values(
+            "cardinality(array(select unnest(quotes.tags) intersect select unnest($1::text[])))",
+            [viewed_quote.tags.clone()],
         );
 
-        let author = format!(
-            "(case when quotes.author = '{}' then 1 else 2 end)",
-            viewed_quote.author.clone()
+        let same_author = Expr::cust_with_values(
+            "(case when quotes.author = $1 then 1 else 2 end)",
+            [viewed_quote.author.clone()],
         );
 
-        let quote = quotes::find()
+        Ok(quotes::find()
             .filter(quotes_columns::Id.not_in_subquery(viewed.as_query().to_owned()))
-            .order_by_desc(SimpleExpr::Custom(tags))
-            .order_by_asc(SimpleExpr::Custom(author))
+            .filter(quotes_columns::Status.eq(ModerationStatus::Approved.as_str()))
+            .order_by_desc(same_tags)
+            .order_by_asc(same_author)
             .order_by_desc(quotes_columns::Likes)
-            .one(&self.db)
-            .await?;
+            .limit(limit)
+            .all(&self.db)
+            .await?)
+    }
 
-        match quote {
-            Some(quote) => Ok(quote),
-            None => Err(anyhow!(ErrNotFound)),
+    /// Returns the quotes matching the filter, aliases of the author and of the tags
+    /// are followed, most liked first.
+    async fn find_quotes(
+        &self,
+        filter: &QuoteFilter,
+        limit: u64,
+        offset: u64,
+    ) -> Result<Vec<quotes_model>> {
+        let mut query =
+            quotes::find().filter(quotes_columns::Status.eq(ModerationStatus::Approved.as_str()));
+        if let Some(author) = &filter.author {
+            query = query.filter(Expr::cust_with_values(
+                "quotes.author in (select name from authors where slug = $1 or $1 = any(aliases))",
+                [author.clone()],
+            ));
         }
+
+        if !filter.tags.is_empty() {
+            query = query.filter(Expr::cust_with_values(
+                "quotes.tags && array(select slug from tags where slug = any($1) or aliases && $1)",
+                [filter.tags.clone()],
+            ));
+        }
+
+        Ok(query
+            .order_by_desc(quotes_columns::Likes)
+            .order_by_asc(quotes_columns::Id)
+            .limit(limit)
+            .offset(offset)
+            .all(&self.db)
+            .await?)
     }
 
     async fn get_view(&self, user_id: &str, quote_id: &str) -> Result<views_model> {
@@ -103,20 +227,210 @@ impl SeaORM {
         }
     }
 
-    pub async fn save_quote(&self, quote: quotes_active_model) -> Result<()> {
-        quotes::insert(quote)
+    /// Returns the cached quotes and reads the other ones at once, missing quotes are skipped.
+    async fn get_quotes_by_ids(&self, quote_ids: &[String]) -> Result<Vec<quotes_model>> {
+        let mut found = Vec::with_capacity(quote_ids.len());
+        let mut missing = Vec::new();
+        for quote_id in quote_ids {
+            match self.quotes.get(quote_id).await {
+                Some(quote) => found.push(quote),
+                None => missing.push(quote_id.clone()),
+            }
+        }
+        if missing.is_empty() {
+            return Ok(found);
+        }
+
+        let read = quotes::find()
+            .filter(quotes_columns::Id.is_in(missing))
+            .all(&self.db)
+            .await?;
+        for quote in read {
+            self.quotes.insert(quote.id.clone(), quote.clone()).await;
+            found.push(quote);
+        }
+
+        Ok(found)
+    }
+
+    /// Returns the views of the (user id, quote id) pairs, missing pairs are skipped.
+    async fn get_views_by_keys(&self, keys: &[(String, String)]) -> Result<Vec<views_model>> {
+        let condition = keys
+            .iter()
+            .fold(Condition::any(), |condition, (user_id, quote_id)| {
+                condition.add(
+                    Condition::all()
+                        .add(views_columns::UserId.eq(user_id))
+                        .add(views_columns::QuoteId.eq(quote_id)),
+                )
+            });
+
+        Ok(views::find().filter(condition).all(&self.db).await?)
+    }
+
+    /// Returns the number of views of every quote, unseen quotes are skipped.
+    async fn count_views(&self, quote_ids: &[String]) -> Result<Vec<(String, i64)>> {
+        let rows = views::find()
+            .select_only()
+            .column(views_columns::QuoteId)
+            .column_as(Expr::col(views_columns::UserId).count(), "view_count")
+            .filter(views_columns::QuoteId.is_in(quote_ids.iter().cloned()))
+            .group_by(views_columns::QuoteId)
+            .into_model::<ViewCountRow>()
+            .all(&self.db)
+            .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| (row.quote_id, row.view_count))
+            .collect())
+    }
+
+    /// Returns ids of the quotes seen by the user, ordered by id since views are not timed.
+    async fn get_viewed_quote_ids(
+        &self,
+        user_id: &str,
+        limit: u64,
+        offset: u64,
+    ) -> Result<Vec<String>> {
+        let viewed = views::find()
+            .filter(views_columns::UserId.eq(user_id))
+            .order_by_asc(views_columns::QuoteId)
+            .limit(limit)
+            .offset(offset)
+            .all(&self.db)
+            .await?;
+
+        Ok(viewed.into_iter().map(|view| view.quote_id).collect())
+    }
+
+    /// Saves the quote with its tags and author replaced by their canonical values,
+    /// creating the missing tags and authors on the way. A quote that was already
+    /// received from the same source keeps its id. A quote whose fingerprint
+    /// is already stored under another id is not inserted: the stored quote survives
+    /// and is returned instead. Returns the stored quote.
+    pub async fn save_quote(&self, quote: quotes_model) -> Result<quotes_model> {
+        // A concurrent save of the same text, author or tag can commit between the
+        // lookups and the inserts. The next attempt finds it and merges into it.
+        for _ in 1..SAVE_QUOTE_ATTEMPTS {
+            match self.try_save_quote(quote.clone()).await {
+                Err(err) if is_unique_violation(&err) => continue,
+                saved => return saved,
+            }
+        }
+
+        self.try_save_quote(quote).await
+    }
+
+    async fn try_save_quote(&self, quote: quotes_model) -> Result<quotes_model> {
+        let txn = self.db.begin().await?;
+
+        let known = match &quote.external_id {
+            Some(external_id) => {
+                quotes::find()
+                    .filter(quotes_columns::Source.eq(&quote.source))
+                    .filter(quotes_columns::ExternalId.eq(external_id))
+                    .one(&txn)
+                    .await?
+            }
+            None => None,
+        };
+
+        let quote = quotes_model {
+            id: known.map_or(quote.id, |known| known.id),
+            author: resolve_author(&txn, &quote.author)
+                .await
+                .context("failed to resolve author")?,
+            tags: resolve_tags(&txn, &quote.tags)
+                .await
+                .context("failed to resolve tags")?,
+            fingerprint: Some(fingerprint(&quote.quote)),
+            ..quote
+        };
+
+        let survivor = quotes::find()
+            .filter(quotes_columns::Fingerprint.eq(quote.fingerprint.clone()))
+            .filter(quotes_columns::Id.ne(&quote.id))
+            .one(&txn)
+            .await?;
+
+        if let Some(survivor) = survivor {
+            let survivor = match quotes::find_by_id(&quote.id).one(&txn).await? {
+                Some(stale) => merge_quotes(&txn, survivor, stale)
+                    .await
+                    .context("failed to merge duplicate quote")?,
+                None => survivor,
+            };
+
+            txn.commit().await?;
+            self.quotes.remove(&quote.id).await;
+            self.quotes.remove(&survivor.id).await;
+            return Ok(survivor);
+        }
+
+        let quote = quotes::insert(quotes_active_model::from(quote))
             .on_conflict(
                 sea_query::OnConflict::column(quotes_columns::Id)
                     .update_columns(vec![
                         quotes_columns::Author,
                         quotes_columns::Quote,
                         quotes_columns::Tags,
+                        quotes_columns::Fingerprint,
                     ])
                     .to_owned(),
             )
-            .exec(&self.db)
+            .exec_with_returning(&txn)
+            .await?;
+
+        quote_tags::delete_many()
+            .filter(quote_tags_columns::QuoteId.eq(&quote.id))
+            .exec(&txn)
+            .await?;
+
+        save_quote_tags(&txn, &quote.id, &quote.tags).await?;
+
+        txn.commit().await?;
+        self.quotes.remove(&quote.id).await;
+        Ok(quote)
+    }
+
+    /// Fingerprints the quotes stored before fingerprinting existed and merges
+    /// the ones that turn out to be duplicates. The most liked copy survives.
+    async fn fill_fingerprints(&self) -> Result<()> {
+        let unprocessed = quotes::find()
+            .filter(quotes_columns::Fingerprint.is_null())
+            .order_by_desc(quotes_columns::Likes)
+            .order_by_asc(quotes_columns::Id)
+            .all(&self.db)
             .await?;
 
+        for quote in unprocessed {
+            let txn = self.db.begin().await?;
+            let quote_fingerprint = fingerprint(&quote.quote);
+
+            let survivor = quotes::find()
+                .filter(quotes_columns::Fingerprint.eq(&quote_fingerprint))
+                .one(&txn)
+                .await?;
+
+            match survivor {
+                Some(survivor) => {
+                    merge_quotes(&txn, survivor, quote)
+                        .await
+                        .context("failed to merge duplicate quote")?;
+                }
+                None => {
+                    quotes::update_many()
+                        .col_expr(quotes_columns::Fingerprint, Expr::value(quote_fingerprint))
+                        .filter(quotes_columns::Id.eq(&quote.id))
+                        .exec(&txn)
+                        .await?;
+                }
+            }
+
+            txn.commit().await?;
+        }
+
         Ok(())
     }
 
@@ -125,6 +439,7 @@ impl SeaORM {
             user_id: Set(user_id.to_owned()),
             quote_id: Set(quote_id.to_owned()),
             liked: Set(false),
+            feedback: Set(None),
         };
 
         views::insert(view)
@@ -139,34 +454,452 @@ impl SeaORM {
         Ok(())
     }
 
+    async fn mark_as_viewed_many(&self, user_id: &str, quote_ids: &[String]) -> Result<()> {
+        if quote_ids.is_empty() {
+            return Ok(());
+        }
+
+        let rows = quote_ids.iter().map(|quote_id| views_active_model {
+            user_id: Set(user_id.to_owned()),
+            quote_id: Set(quote_id.to_owned()),
+            liked: Set(false),
+            feedback: Set(None),
+        });
+
+        views::insert_many(rows)
+            .on_conflict(
+                sea_query::OnConflict::columns(vec![views_columns::UserId, views_columns::QuoteId])
+                    .do_nothing()
+                    .to_owned(),
+            )
+            .do_nothing()
+            .exec(&self.db)
+            .await?;
+
+        Ok(())
+    }
+
     async fn mark_as_liked(&self, user_id: &str, quote_id: &str) -> Result<()> {
         let view = views_active_model {
             user_id: Set(user_id.to_owned()),
             quote_id: Set(quote_id.to_owned()),
             liked: Set(true),
+            feedback: NotSet,
         };
 
         view.update(&self.db).await?;
         Ok(())
     }
 
-    async fn like_quote(&self, quote_id: &str) -> Result<()> {
+    async fn set_feedback(&self, user_id: &str, quote_id: &str, feedback: Feedback) -> Result<()> {
+        let result = views::update_many()
+            .col_expr(views_columns::Feedback, Expr::value(feedback.as_str()))
+            .filter(views_columns::UserId.eq(user_id))
+            .filter(views_columns::QuoteId.eq(quote_id))
+            .exec(&self.db)
+            .await?;
+
+        match result.rows_affected {
+            0 => Err(anyhow!(ErrNotFound)),
+            _ => Ok(()),
+        }
+    }
+
+    pub async fn get_feedback(&self, user_id: &str) -> Result<Vec<(String, quotes_model)>> {
+        let feedback = views::find()
+            .filter(views_columns::UserId.eq(user_id))
+            .filter(views_columns::Feedback.is_not_null())
+            .find_also_related(quotes)
+            .all(&self.db)
+            .await?;
+
+        Ok(feedback
+            .into_iter()
+            .filter_map(|(view, quote)| Some((view.feedback?, quote?)))
+            .collect())
+    }
+
+    async fn like_quote(&self, quote_id: &str) -> Result<quotes_model> {
+        // the likes are counted from the stored quote, the cached one may be behind
         let quote = self
-            .get_quote(quote_id)
+            .find_quote(quote_id)
             .await
             .context("failed to get old quote")?;
 
         let mut quote_active: quotes_active_model = quote.into();
         quote_active.likes = Set(quote_active.likes.take().unwrap_or_default() + 1);
 
-        quote_active
+        let quote = quote_active
             .update(&self.db)
             .await
             .context("failed to update quote")?;
 
+        self.quotes.insert(quote.id.clone(), quote.clone()).await;
+        Ok(quote)
+    }
+
+    async fn get_daily_quote(
+        &self,
+        day: NaiveDate,
+        timezone: &str,
+        tag: &str,
+    ) -> Result<quotes_model> {
+        // a pick hidden since is replaced by the next one
+        let quote = quotes::find()
+            .inner_join(daily_quotes)
+            .filter(quotes_columns::Status.eq(ModerationStatus::Approved.as_str()))
+            .filter(daily_quotes_columns::Day.eq(day))
+            .filter(daily_quotes_columns::Timezone.eq(timezone))
+            .filter(daily_quotes_columns::Tag.eq(tag))
+            .one(&self.db)
+            .await?;
+
+        match quote {
+            Some(quote) => Ok(quote),
+            None => Err(anyhow!(ErrNotFound)),
+        }
+    }
+
+    async fn get_daily_candidates(&self, tag: &str) -> Result<Vec<quotes_model>> {
+        let mut query = quotes::find()
+            .filter(quotes_columns::Status.eq(ModerationStatus::Approved.as_str()))
+            .order_by_asc(quotes_columns::Id);
+        if !tag.is_empty() {
+            query = query.filter(Expr::cust_with_values("$1 = any(quotes.tags)", [tag]));
+        }
+
+        Ok(query.all(&self.db).await?)
+    }
+
+    async fn save_daily_quote(
+        &self,
+        daily_quote: daily_quotes_model,
+        overwrite: bool,
+    ) -> Result<()> {
+        let mut on_conflict = sea_query::OnConflict::columns([
+            daily_quotes_columns::Day,
+            daily_quotes_columns::Timezone,
+            daily_quotes_columns::Tag,
+        ]);
+
+        on_conflict.update_columns([
+            daily_quotes_columns::QuoteId,
+            daily_quotes_columns::Overridden,
+        ]);
+        if !overwrite {
+            // the stored pick wins unless it was hidden since
+            on_conflict.action_and_where(Expr::cust_with_values(
+                "(select status from quotes where quotes.id = daily_quotes.quote_id) <> $1",
+                [ModerationStatus::Approved.as_str()],
+            ));
+        }
+
+        daily_quotes::insert(daily_quotes_active_model::from(daily_quote))
+            .on_conflict(on_conflict)
+            .do_nothing()
+            .exec(&self.db)
+            .await?;
+
         Ok(())
     }
 
+    /// Upserts the report of the user, a resolved report is opened again.
+    async fn report_quote(&self, report: &QuoteReport) -> Result<u64> {
+        quote_reports::insert(quote_reports_active_model {
+            quote_id: Set(report.quote_id.clone()),
+            user_id: Set(report.user_id.clone()),
+            reason: Set(report.reason.clone()),
+            created_at: Set(Utc::now().fixed_offset()),
+            resolved_at: Set(None),
+        })
+        .on_conflict(
+            sea_query::OnConflict::columns([
+                quote_reports_columns::QuoteId,
+                quote_reports_columns::UserId,
+            ])
+            .update_columns([
+                quote_reports_columns::Reason,
+                quote_reports_columns::CreatedAt,
+                quote_reports_columns::ResolvedAt,
+            ])
+            .to_owned(),
+        )
+        .exec(&self.db)
+        .await?;
+
+        Ok(quote_reports::find()
+            .filter(quote_reports_columns::QuoteId.eq(&report.quote_id))
+            .filter(quote_reports_columns::ResolvedAt.is_null())
+            .count(&self.db)
+            .await?)
+    }
+
+    /// Updates the status and writes the audit entry in one transaction, the quote row is
+    /// locked so concurrent decisions are recorded with the right previous status.
+    async fn set_quote_status(
+        &self,
+        decision: &ModerationDecision,
+        resolve_reports: bool,
+    ) -> Result<moderation_audit_model> {
+        let txn = self.db.begin().await?;
+
+        let quote = quotes::find_by_id(&decision.quote_id)
+            .lock_exclusive()
+            .one(&txn)
+            .await?
+            .ok_or_else(|| anyhow!(ErrNotFound))?;
+
+        quotes::update_many()
+            .col_expr(
+                quotes_columns::Status,
+                Expr::value(decision.status.as_str()),
+            )
+            .filter(quotes_columns::Id.eq(&quote.id))
+            .exec(&txn)
+            .await?;
+
+        if resolve_reports {
+            quote_reports::update_many()
+                .col_expr(
+                    quote_reports_columns::ResolvedAt,
+                    Expr::current_timestamp().into(),
+                )
+                .filter(quote_reports_columns::QuoteId.eq(&quote.id))
+                .filter(quote_reports_columns::ResolvedAt.is_null())
+                .exec(&txn)
+                .await?;
+        }
+
+        let entry = moderation_audit_active_model {
+            id: Set(Uuid::new_v4().to_string()),
+            quote_id: Set(quote.id.clone()),
+            previous_status: Set(quote.status),
+            status: Set(decision.status.as_str().to_string()),
+            moderator: Set(decision.moderator.clone()),
+            note: Set(decision.note.clone()),
+            created_at: Set(Utc::now().fixed_offset()),
+        }
+        .insert(&txn)
+        .await?;
+
+        txn.commit().await?;
+        self.quotes.remove(&quote.id).await;
+        Ok(entry)
+    }
+
+    async fn get_review_queue(
+        &self,
+        limit: u64,
+        offset: u64,
+    ) -> Result<Vec<(quotes_model, Vec<quote_reports_model>)>> {
+        let reported = quote_reports::find()
+            .select_only()
+            .column(quote_reports_columns::QuoteId)
+            .filter(quote_reports_columns::ResolvedAt.is_null());
+
+        let open_reports = Expr::cust(
+            "(select count(*) from quote_reports \
+             where quote_reports.quote_id = quotes.id and quote_reports.resolved_at is null)",
+        );
+
+        let queue = quotes::find()
+            .filter(
+                Condition::any()
+                    .add(quotes_columns::Status.eq(ModerationStatus::Pending.as_str()))
+                    .add(quotes_columns::Id.in_subquery(reported.as_query().to_owned())),
+            )
+            .order_by_desc(open_reports)
+            .order_by_asc(quotes_columns::Id)
+            .limit(limit)
+            .offset(offset)
+            .all(&self.db)
+            .await?;
+
+        let quote_ids: Vec<String> = queue.iter().map(|quote| quote.id.clone()).collect();
+        let mut reports: HashMap<String, Vec<quote_reports_model>> = HashMap::new();
+        for report in quote_reports::find()
+            .filter(quote_reports_columns::QuoteId.is_in(quote_ids))
+            .filter(quote_reports_columns::ResolvedAt.is_null())
+            .order_by_desc(quote_reports_columns::CreatedAt)
+            .all(&self.db)
+            .await?
+        {
+            reports
+                .entry(report.quote_id.clone())
+                .or_default()
+                .push(report);
+        }
+
+        Ok(queue
+            .into_iter()
+            .map(|quote| {
+                let reports = reports.remove(&quote.id).unwrap_or_default();
+                (quote, reports)
+            })
+            .collect())
+    }
+
+    async fn get_moderation_audit(&self, quote_id: &str) -> Result<Vec<moderation_audit_model>> {
+        Ok(moderation_audit::find()
+            .filter(moderation_audit_columns::QuoteId.eq(quote_id))
+            .order_by_asc(moderation_audit_columns::CreatedAt)
+            .order_by_asc(moderation_audit_columns::Id)
+            .all(&self.db)
+            .await?)
+    }
+
+    async fn get_tags(&self, params: &ListParams<TagSort>) -> Result<(Vec<Tag>, u64)> {
+        let sort = match params.sort {
+            TagSort::Name => "name",
+            TagSort::Quotes => "quote_count",
+        };
+
+        let rows = TagRow::find_by_statement(Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            format!(
+                "select tag as name, count(*) as quote_count \
+                 from quotes, unnest(quotes.tags) as tag where quotes.status = $3 \
+                 group by tag order by {sort} {}, name asc limit $1 offset $2",
+                order_to_sql(params.order)
+            ),
+            [
+                (params.limit as i64).into(),
+                (params.offset as i64).into(),
+                ModerationStatus::Approved.as_str().into(),
+            ],
+        ))
+        .all(&self.db)
+        .await?;
+
+        let total = TotalRow::find_by_statement(Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            "select count(distinct tag) as total from quotes, unnest(quotes.tags) as tag \
+             where quotes.status = $1",
+            [ModerationStatus::Approved.as_str().into()],
+        ))
+        .one(&self.db)
+        .await?
+        .map_or(0, |row| row.total);
+
+        Ok((
+            rows.into_iter()
+                .map(|row| Tag {
+                    name: row.name,
+                    quotes: row.quote_count,
+                })
+                .collect(),
+            total as u64,
+        ))
+    }
+
+    async fn get_authors(&self, params: &ListParams<AuthorSort>) -> Result<(Vec<Author>, u64)> {
+        let sort = match params.sort {
+            AuthorSort::Name => "name",
+            AuthorSort::Quotes => "quote_count",
+            AuthorSort::Likes => "like_count",
+        };
+
+        let rows = quotes::find()
+            .select_only()
+            .column_as(quotes_columns::Author, "name")
+            .column_as(Expr::col(quotes_columns::Id).count(), "quote_count")
+            .column_as(Expr::col(quotes_columns::Likes).sum(), "like_count")
+            .filter(quotes_columns::Status.eq(ModerationStatus::Approved.as_str()))
+            .group_by(quotes_columns::Author)
+            .order_by(Expr::cust(sort), order_to_sea_orm(params.order))
+            .order_by_asc(quotes_columns::Author)
+            .limit(params.limit)
+            .offset(params.offset)
+            .into_model::<AuthorRow>()
+            .all(&self.db)
+            .await?;
+
+        let total = quotes::find()
+            .select_only()
+            .column_as(Expr::col(quotes_columns::Author).count_distinct(), "total")
+            .filter(quotes_columns::Status.eq(ModerationStatus::Approved.as_str()))
+            .into_model::<TotalRow>()
+            .one(&self.db)
+            .await?
+            .map_or(0, |row| row.total);
+
+        Ok((
+            rows.into_iter()
+                .map(|row| Author {
+                    name: row.name,
+                    quotes: row.quote_count,
+                    likes: row.like_count,
+                })
+                .collect(),
+            total as u64,
+        ))
+    }
+
+    async fn get_tag(&self, name: &str) -> Result<Tag> {
+        let row = TagRow::find_by_statement(Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            "select slug as name, count(quotes.id) as quote_count \
+             from tags left join quotes on tags.slug = any(quotes.tags) and quotes.status = $2 \
+             where tags.slug = $1 or $1 = any(tags.aliases) \
+             group by slug order by tags.slug = $1 desc limit 1",
+            [
+                slugify(name).into(),
+                ModerationStatus::Approved.as_str().into(),
+            ],
+        ))
+        .one(&self.db)
+        .await?;
+
+        match row {
+            Some(row) if row.quote_count > 0 => Ok(Tag {
+                name: row.name,
+                quotes: row.quote_count,
+            }),
+            _ => Err(anyhow!(ErrNotFound)),
+        }
+    }
+
+    async fn get_author(&self, name: &str) -> Result<Author> {
+        let row = AuthorRow::find_by_statement(Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            "select authors.name, count(quotes.id) as quote_count, \
+             coalesce(sum(quotes.likes), 0) as like_count \
+             from authors left join quotes on quotes.author = authors.name \
+             and quotes.status = $2 \
+             where authors.slug = $1 or $1 = any(authors.aliases) \
+             group by authors.slug, authors.name order by authors.slug = $1 desc limit 1",
+            [
+                slugify(name).into(),
+                ModerationStatus::Approved.as_str().into(),
+            ],
+        ))
+        .one(&self.db)
+        .await?;
+
+        match row {
+            Some(row) if row.quote_count > 0 => Ok(Author {
+                name: row.name,
+                quotes: row.quote_count,
+                likes: row.like_count,
+            }),
+            _ => Err(anyhow!(ErrNotFound)),
+        }
+    }
+
+    #[cfg(test)]
+    pub fn from_connection(db: DatabaseConnection) -> Self {
+        SeaORM {
+            db,
+            quotes: TtlCache::new(Duration::ZERO),
+        }
+    }
+
+    /// Statements issued so far, only available for mock connections.
+    #[cfg(test)]
+    pub fn into_transaction_log(self) -> Vec<sea_orm::Transaction> {
+        self.db.into_transaction_log()
+    }
+
     pub async fn new(cfg: &ORMConfig) -> Result<Self> {
         let mut opt = ConnectOptions::new(&cfg.dsn);
         opt.sqlx_logging(false);
@@ -179,7 +912,228 @@ impl SeaORM {
             .await
             .context("failed to migrate")?;
 
-        Ok(SeaORM { db: connection })
+        let db = SeaORM {
+            db: connection,
+            quotes: TtlCache::new(Duration::from_secs(cfg.quote_cache_ttl)),
+        };
+        db.fill_fingerprints()
+            .await
+            .context("failed to fingerprint quotes")?;
+
+        Ok(db)
+    }
+}
+
+fn is_unique_violation(err: &anyhow::Error) -> bool {
+    err.downcast_ref::<sea_orm::DbErr>()
+        .and_then(sea_orm::DbErr::sql_err)
+        .is_some_and(|err| matches!(err, sea_orm::SqlErr::UniqueConstraintViolation(_)))
+}
+
+/// Folds the duplicate into the survivor and returns the updated survivor. The views
+/// move over. A like on either copy counts as a like. The feedback on the survivor wins
+/// over the duplicate's. The likes are added up without counting a user twice. The tags
+/// are united. The reports and the audit trail move over and the duplicate is deleted.
+async fn merge_quotes<C: ConnectionTrait>(
+    conn: &C,
+    survivor: quotes_model,
+    duplicate: quotes_model,
+) -> Result<quotes_model> {
+    let liked_both = TotalRow::find_by_statement(Statement::from_sql_and_values(
+        DbBackend::Postgres,
+        "select count(*) as total from views as duplicate \
+         inner join views as survivor on survivor.user_id = duplicate.user_id \
+         where duplicate.quote_id = $1 and survivor.quote_id = $2 \
+         and duplicate.liked and survivor.liked",
+        [duplicate.id.clone().into(), survivor.id.clone().into()],
+    ))
+    .one(conn)
+    .await?
+    .map_or(0, |row| row.total);
+
+    conn.execute(Statement::from_sql_and_values(
+        DbBackend::Postgres,
+        "insert into views (user_id, quote_id, liked, feedback) \
+         select user_id, $2, liked, feedback from views where quote_id = $1 \
+         on conflict (user_id, quote_id) do update set liked = views.liked or excluded.liked, \
+         feedback = coalesce(views.feedback, excluded.feedback)",
+        [duplicate.id.clone().into(), survivor.id.clone().into()],
+    ))
+    .await?;
+
+    views::delete_many()
+        .filter(views_columns::QuoteId.eq(&duplicate.id))
+        .exec(conn)
+        .await?;
+
+    // the days already shown the duplicate keep showing the same quote
+    daily_quotes::update_many()
+        .col_expr(daily_quotes_columns::QuoteId, Expr::value(&survivor.id))
+        .filter(daily_quotes_columns::QuoteId.eq(&duplicate.id))
+        .exec(conn)
+        .await?;
+
+    // a user who reported both copies keeps their latest report
+    conn.execute(Statement::from_sql_and_values(
+        DbBackend::Postgres,
+        "insert into quote_reports (quote_id, user_id, reason, created_at, resolved_at) \
+         select $2, user_id, reason, created_at, resolved_at from quote_reports where quote_id = $1 \
+         on conflict (quote_id, user_id) do update set reason = excluded.reason, \
+         created_at = excluded.created_at, resolved_at = excluded.resolved_at \
+         where excluded.created_at > quote_reports.created_at",
+        [duplicate.id.clone().into(), survivor.id.clone().into()],
+    ))
+    .await?;
+
+    quote_reports::delete_many()
+        .filter(quote_reports_columns::QuoteId.eq(&duplicate.id))
+        .exec(conn)
+        .await?;
+
+    moderation_audit::update_many()
+        .col_expr(moderation_audit_columns::QuoteId, Expr::value(&survivor.id))
+        .filter(moderation_audit_columns::QuoteId.eq(&duplicate.id))
+        .exec(conn)
+        .await?;
+
+    quotes::delete_by_id(&duplicate.id).exec(conn).await?;
+
+    let mut merged_tags = survivor.tags.clone();
+    for tag in duplicate.tags {
+        if !merged_tags.contains(&tag) {
+            merged_tags.push(tag);
+        }
+    }
+
+    let likes = survivor.likes + duplicate.likes - liked_both as i32;
+    let mut survivor: quotes_active_model = survivor.into();
+    survivor.likes = Set(likes);
+    survivor.tags = Set(merged_tags);
+    let survivor = survivor.update(conn).await?;
+
+    save_quote_tags(conn, &survivor.id, &survivor.tags).await?;
+    Ok(survivor)
+}
+
+async fn save_quote_tags<C: ConnectionTrait>(
+    conn: &C,
+    quote_id: &str,
+    slugs: &[String],
+) -> Result<()> {
+    if slugs.is_empty() {
+        return Ok(());
+    }
+
+    quote_tags::insert_many(slugs.iter().map(|tag| quote_tags_active_model {
+        quote_id: Set(quote_id.to_owned()),
+        tag: Set(tag.clone()),
+    }))
+    .on_conflict(
+        sea_query::OnConflict::columns(vec![quote_tags_columns::QuoteId, quote_tags_columns::Tag])
+            .do_nothing()
+            .to_owned(),
+    )
+    .exec_without_returning(conn)
+    .await?;
+
+    Ok(())
+}
+
+/// Maps every tag to the slug of its canonical tag, following aliases.
+/// Unknown tags become new canonical tags; duplicates are dropped.
+async fn resolve_tags<C: ConnectionTrait>(conn: &C, raw_tags: &[String]) -> Result<Vec<String>> {
+    let mut canonical: Vec<String> = Vec::with_capacity(raw_tags.len());
+    for raw_tag in raw_tags {
+        let slug = slugify(raw_tag);
+        if slug.is_empty() {
+            continue;
+        }
+
+        let known =
+            tags::find()
+                .filter(Condition::any().add(tags_columns::Slug.eq(&slug)).add(
+                    Expr::cust_with_values("$1 = any(tags.aliases)", [slug.clone()]),
+                ))
+                .order_by_desc(tags_columns::Slug.eq(&slug))
+                .one(conn)
+                .await?;
+
+        let slug = match known {
+            Some(tag) => tag.slug,
+            None => {
+                tags::insert(tags_active_model {
+                    slug: Set(slug.clone()),
+                    name: Set(clean_name(raw_tag)),
+                    aliases: Set(vec![]),
+                })
+                .on_conflict(
+                    sea_query::OnConflict::column(tags_columns::Slug)
+                        .do_nothing()
+                        .to_owned(),
+                )
+                .exec_without_returning(conn)
+                .await?;
+
+                slug
+            }
+        };
+
+        if !canonical.contains(&slug) {
+            canonical.push(slug);
+        }
+    }
+
+    Ok(canonical)
+}
+
+/// Returns the canonical name of the author, registering it if it is unknown.
+async fn resolve_author<C: ConnectionTrait>(conn: &C, raw_author: &str) -> Result<String> {
+    let slug = slugify(raw_author);
+    if slug.is_empty() {
+        return Ok(clean_name(raw_author));
+    }
+
+    let known =
+        authors::find()
+            .filter(Condition::any().add(authors_columns::Slug.eq(&slug)).add(
+                Expr::cust_with_values("$1 = any(authors.aliases)", [slug.clone()]),
+            ))
+            .order_by_desc(authors_columns::Slug.eq(&slug))
+            .one(conn)
+            .await?;
+
+    match known {
+        Some(author) => Ok(author.name),
+        None => {
+            authors::insert(authors_active_model {
+                slug: Set(slug),
+                name: Set(clean_name(raw_author)),
+                aliases: Set(vec![]),
+            })
+            .on_conflict(
+                sea_query::OnConflict::column(authors_columns::Slug)
+                    .do_nothing()
+                    .to_owned(),
+            )
+            .exec_without_returning(conn)
+            .await?;
+
+            Ok(clean_name(raw_author))
+        }
+    }
+}
+
+fn order_to_sql(order: Order) -> &'static str {
+    match order {
+        Order::Asc => "asc",
+        Order::Desc => "desc",
+    }
+}
+
+fn order_to_sea_orm(order: Order) -> sea_orm::Order {
+    match order {
+        Order::Asc => sea_orm::Order::Asc,
+        Order::Desc => sea_orm::Order::Desc,
     }
 }
 
@@ -208,26 +1162,292 @@ impl quote_service::Database for SeaORM {
         self.get_same_quote(user_id, viewed_quote).await
     }
 
+    async fn get_same_quotes(
+        &self,
+        user_id: &str,
+        viewed_quote: &quotes_model,
+        limit: u64,
+    ) -> Result<Vec<quotes_model>> {
+        self.get_same_quotes(user_id, viewed_quote, limit).await
+    }
+
+    async fn find_quotes(
+        &self,
+        filter: &QuoteFilter,
+        limit: u64,
+        offset: u64,
+    ) -> Result<Vec<quotes_model>> {
+        self.find_quotes(filter, limit, offset).await
+    }
+
     async fn get_view(&self, user_id: &str, quote_id: &str) -> Result<views_model> {
         self.get_view(user_id, quote_id).await
     }
 
+    async fn get_quotes_by_ids(&self, quote_ids: &[String]) -> Result<Vec<quotes_model>> {
+        self.get_quotes_by_ids(quote_ids).await
+    }
+
+    async fn get_views_by_keys(&self, keys: &[(String, String)]) -> Result<Vec<views_model>> {
+        self.get_views_by_keys(keys).await
+    }
+
+    async fn count_views(&self, quote_ids: &[String]) -> Result<Vec<(String, i64)>> {
+        self.count_views(quote_ids).await
+    }
+
+    async fn get_viewed_quote_ids(
+        &self,
+        user_id: &str,
+        limit: u64,
+        offset: u64,
+    ) -> Result<Vec<String>> {
+        self.get_viewed_quote_ids(user_id, limit, offset).await
+    }
+
     async fn mark_as_viewed(&self, user_id: &str, quote_id: &str) -> Result<()> {
         self.mark_as_viewed(user_id, quote_id).await
     }
 
+    async fn mark_as_viewed_many(&self, user_id: &str, quote_ids: &[String]) -> Result<()> {
+        self.mark_as_viewed_many(user_id, quote_ids).await
+    }
+
     async fn mark_as_liked(&self, user_id: &str, quote_id: &str) -> Result<()> {
         self.mark_as_liked(user_id, quote_id).await
     }
 
-    async fn like_quote(&self, quote_id: &str) -> Result<()> {
+    async fn set_feedback(&self, user_id: &str, quote_id: &str, feedback: Feedback) -> Result<()> {
+        self.set_feedback(user_id, quote_id, feedback).await
+    }
+
+    async fn get_feedback(&self, user_id: &str) -> Result<Vec<(String, quotes_model)>> {
+        self.get_feedback(user_id).await
+    }
+
+    async fn like_quote(&self, quote_id: &str) -> Result<quotes_model> {
         self.like_quote(quote_id).await
     }
+
+    async fn get_daily_quote(
+        &self,
+        day: NaiveDate,
+        timezone: &str,
+        tag: &str,
+    ) -> Result<quotes_model> {
+        self.get_daily_quote(day, timezone, tag).await
+    }
+
+    async fn get_daily_candidates(&self, tag: &str) -> Result<Vec<quotes_model>> {
+        self.get_daily_candidates(tag).await
+    }
+
+    async fn save_daily_quote(
+        &self,
+        daily_quote: daily_quotes_model,
+        overwrite: bool,
+    ) -> Result<()> {
+        self.save_daily_quote(daily_quote, overwrite).await
+    }
+
+    async fn report_quote(&self, report: &QuoteReport) -> Result<u64> {
+        self.report_quote(report).await
+    }
+
+    async fn set_quote_status(
+        &self,
+        decision: &ModerationDecision,
+        resolve_reports: bool,
+    ) -> Result<moderation_audit_model> {
+        self.set_quote_status(decision, resolve_reports).await
+    }
+
+    async fn get_review_queue(
+        &self,
+        limit: u64,
+        offset: u64,
+    ) -> Result<Vec<(quotes_model, Vec<quote_reports_model>)>> {
+        self.get_review_queue(limit, offset).await
+    }
+
+    async fn get_moderation_audit(&self, quote_id: &str) -> Result<Vec<moderation_audit_model>> {
+        self.get_moderation_audit(quote_id).await
+    }
 }
 
 #[async_trait]
 impl quote_api_service::Database for SeaORM {
-    async fn save_quote(&self, quote: quotes_model) -> Result<()> {
-        self.save_quote(quote.into()).await
+    async fn save_quote(&self, quote: quotes_model) -> Result<quotes_model> {
+        self.save_quote(quote).await
+    }
+
+    async fn count_quotes(&self) -> Result<u64> {
+        self.count_quotes().await
+    }
+}
+
+#[async_trait]
+impl catalogue_service::Database for SeaORM {
+    async fn get_tags(&self, params: &ListParams<TagSort>) -> Result<(Vec<Tag>, u64)> {
+        self.get_tags(params).await
+    }
+
+    async fn get_authors(&self, params: &ListParams<AuthorSort>) -> Result<(Vec<Author>, u64)> {
+        self.get_authors(params).await
+    }
+
+    async fn get_tag(&self, name: &str) -> Result<Tag> {
+        self.get_tag(name).await
+    }
+
+    async fn get_author(&self, name: &str) -> Result<Author> {
+        self.get_author(name).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sea_orm::{DatabaseBackend, MockDatabase};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_quote_cache() {
+        let quote = quotes_model {
+            id: "quote".to_string(),
+            quote: "Premature optimization is the root of all evil.".to_string(),
+            author: "Donald Knuth".to_string(),
+            likes: 0,
+            tags: vec![],
+            fingerprint: None,
+            source: "manual".to_string(),
+            external_id: None,
+            status: "approved".to_string(),
+        };
+        let liked = quotes_model {
+            likes: 1,
+            ..quote.clone()
+        };
+
+        let db = SeaORM {
+            db: MockDatabase::new(DatabaseBackend::Postgres)
+                .append_query_results([
+                    vec![quote.clone()],
+                    vec![quote.clone()],
+                    vec![liked.clone()],
+                ])
+                .into_connection(),
+            quotes: TtlCache::new(Duration::from_secs(60)),
+        };
+
+        for _ in 0..2 {
+            assert_eq!(db.get_quote(&quote.id).await.unwrap(), quote);
+        }
+        // the like reads the stored quote and replaces the cached one
+        assert_eq!(db.like_quote(&quote.id).await.unwrap(), liked);
+        assert_eq!(db.get_quote(&quote.id).await.unwrap(), liked);
+        assert_eq!(
+            db.get_quotes_by_ids(std::slice::from_ref(&quote.id))
+                .await
+                .unwrap(),
+            vec![liked]
+        );
+
+        assert_eq!(db.into_transaction_log().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_merge_quotes_keeps_references() {
+        let survivor = quotes_model {
+            id: "survivor".to_string(),
+            quote: "Simplicity is prerequisite for reliability.".to_string(),
+            author: "Edsger Dijkstra".to_string(),
+            likes: 1,
+            tags: vec![],
+            fingerprint: None,
+            source: "manual".to_string(),
+            external_id: None,
+            status: "approved".to_string(),
+        };
+        let duplicate = quotes_model {
+            id: "duplicate".to_string(),
+            ..survivor.clone()
+        };
+
+        let conn = MockDatabase::new(DatabaseBackend::Postgres)
+            .append_query_results([[std::collections::BTreeMap::from([(
+                "total",
+                sea_orm::Value::BigInt(Some(0)),
+            )])]])
+            .append_query_results([[survivor.clone()]])
+            .append_exec_results((0..7).map(|_| sea_orm::MockExecResult {
+                last_insert_id: 0,
+                rows_affected: 1,
+            }))
+            .into_connection();
+
+        merge_quotes(&conn, survivor, duplicate).await.unwrap();
+
+        let statements: Vec<String> = conn
+            .into_transaction_log()
+            .iter()
+            .flat_map(|transaction| transaction.statements())
+            .map(|statement| statement.sql.clone())
+            .collect();
+        let position = |prefix: &str| {
+            statements
+                .iter()
+                .position(|sql| sql.starts_with(prefix))
+                .unwrap_or_else(|| panic!("no statement starts with {prefix}: {statements:#?}"))
+        };
+
+        // the references are moved to the kept quote before the duplicate is deleted
+        let deleted = position(r#"DELETE FROM "public"."quotes""#);
+        assert!(position(r#"UPDATE "public"."daily_quotes" SET "quote_id""#) < deleted);
+        assert!(position("insert into views (user_id, quote_id, liked, feedback)") < deleted);
+        assert!(position("insert into quote_reports") < deleted);
+        assert!(position(r#"DELETE FROM "public"."quote_reports""#) < deleted);
+        assert!(position(r#"UPDATE "public"."moderation_audit" SET "quote_id""#) < deleted);
+    }
+
+    #[tokio::test]
+    async fn test_reads_are_approved_only() {
+        let db = SeaORM::from_connection(
+            MockDatabase::new(DatabaseBackend::Postgres)
+                .append_query_results((0..7).map(|_| Vec::<quotes_model>::new()))
+                .into_connection(),
+        );
+
+        let day = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        assert!(db.get_daily_quote(day, "UTC", "").await.is_err());
+        db.get_tags(&ListParams::parse(None, None, None, None).unwrap())
+            .await
+            .unwrap();
+        db.get_authors(&ListParams::parse(None, None, None, None).unwrap())
+            .await
+            .unwrap();
+        assert!(db.get_tag("wisdom").await.is_err());
+        assert!(db.get_author("knuth").await.is_err());
+
+        let approved = sea_orm::Value::from(ModerationStatus::Approved.as_str());
+        let statements: Vec<sea_orm::Statement> = db
+            .into_transaction_log()
+            .iter()
+            .flat_map(|transaction| transaction.statements().to_vec())
+            .collect();
+        assert_eq!(statements.len(), 7);
+        for statement in statements {
+            assert!(
+                statement.sql.contains("status"),
+                "unfiltered statement {}",
+                statement.sql
+            );
+            let values = statement.values.map(|values| values.0).unwrap_or_default();
+            assert!(
+                values.contains(&approved),
+                "unfiltered statement {}",
+                statement.sql
+            );
+        }
     }
 }
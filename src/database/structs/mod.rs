@@ -2,5 +2,11 @@
 
 pub mod prelude;
 
+pub mod authors;
+pub mod daily_quotes;
+pub mod moderation_audit;
+pub mod quote_reports;
+pub mod quote_tags;
 pub mod quotes;
+pub mod tags;
 pub mod views;
@@ -1,4 +1,10 @@
 //! `SeaORM` Entity, @generated by sea-orm-codegen 1.1.3
 
+pub use super::authors::Entity as Authors;
+pub use super::daily_quotes::Entity as DailyQuotes;
+pub use super::moderation_audit::Entity as ModerationAudit;
+pub use super::quote_reports::Entity as QuoteReports;
+pub use super::quote_tags::Entity as QuoteTags;
 pub use super::quotes::Entity as Quotes;
+pub use super::tags::Entity as Tags;
 pub use super::views::Entity as Views;
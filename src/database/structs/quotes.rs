@@ -22,6 +22,10 @@ pub struct Model {
     pub author: String,
     pub likes: i32,
     pub tags: Vec<String>,
+    pub fingerprint: Option<String>,
+    pub source: String,
+    pub external_id: Option<String>,
+    pub status: String,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveColumn)]
@@ -31,6 +35,10 @@ pub enum Column {
     Author,
     Likes,
     Tags,
+    Fingerprint,
+    Source,
+    ExternalId,
+    Status,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DerivePrimaryKey)]
@@ -47,6 +55,10 @@ impl PrimaryKeyTrait for PrimaryKey {
 
 #[derive(Copy, Clone, Debug, EnumIter)]
 pub enum Relation {
+    DailyQuotes,
+    ModerationAudit,
+    QuoteReports,
+    QuoteTags,
     Views,
 }
 
@@ -59,6 +71,10 @@ impl ColumnTrait for Column {
             Self::Author => ColumnType::Text.def(),
             Self::Likes => ColumnType::Integer.def(),
             Self::Tags => ColumnType::Array(RcOrArc::new(ColumnType::Text)).def(),
+            Self::Fingerprint => ColumnType::Text.def().null().unique(),
+            Self::Source => ColumnType::Text.def(),
+            Self::ExternalId => ColumnType::Text.def().null(),
+            Self::Status => ColumnType::Text.def(),
         }
     }
 }
@@ -66,15 +82,52 @@ impl ColumnTrait for Column {
 impl RelationTrait for Relation {
     fn def(&self) -> RelationDef {
         match self {
+            Self::DailyQuotes => Entity::has_many(super::daily_quotes::Entity).into(),
+            Self::ModerationAudit => Entity::has_many(super::moderation_audit::Entity).into(),
+            Self::QuoteReports => Entity::has_many(super::quote_reports::Entity).into(),
+            Self::QuoteTags => Entity::has_many(super::quote_tags::Entity).into(),
             Self::Views => Entity::has_many(super::views::Entity).into(),
         }
     }
 }
 
+impl Related<super::daily_quotes::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::DailyQuotes.def()
+    }
+}
+
+impl Related<super::moderation_audit::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ModerationAudit.def()
+    }
+}
+
+impl Related<super::quote_reports::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::QuoteReports.def()
+    }
+}
+
+impl Related<super::quote_tags::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::QuoteTags.def()
+    }
+}
+
 impl Related<super::views::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::Views.def()
     }
 }
 
+impl Related<super::tags::Entity> for Entity {
+    fn to() -> RelationDef {
+        super::quote_tags::Relation::Tags.def()
+    }
+    fn via() -> Option<RelationDef> {
+        Some(super::quote_tags::Relation::Quotes.def().rev())
+    }
+}
+
 impl ActiveModelBehavior for ActiveModel {}
@@ -20,6 +20,7 @@ pub struct Model {
     pub user_id: String,
     pub quote_id: String,
     pub liked: bool,
+    pub feedback: Option<String>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveColumn)]
@@ -27,6 +28,7 @@ pub enum Column {
     UserId,
     QuoteId,
     Liked,
+    Feedback,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DerivePrimaryKey)]
@@ -54,6 +56,7 @@ impl ColumnTrait for Column {
             Self::UserId => ColumnType::Text.def(),
             Self::QuoteId => ColumnType::Text.def(),
             Self::Liked => ColumnType::Boolean.def(),
+            Self::Feedback => ColumnType::Text.def().null(),
         }
     }
 }
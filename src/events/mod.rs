@@ -0,0 +1,41 @@
+use tokio::sync::broadcast;
+
+use crate::quote::structs::Quote;
+
+/// Number of events kept for a slow subscriber before it starts missing them.
+const CAPACITY: usize = 256;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Event {
+    LikeCountChanged { quote_id: String, likes: i32 },
+    NewQuoteAdded(Quote),
+}
+
+/// In-process broadcast bus, services publish domain events to it and every
+/// subscriber receives the events published after it has subscribed.
+#[derive(Clone)]
+pub struct Bus {
+    sender: broadcast::Sender<Event>,
+}
+
+impl Bus {
+    pub fn publish(&self, event: Event) {
+        // Nobody listening is not an error, the event is just dropped.
+        let _ = self.sender.send(event);
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<Event> {
+        self.sender.subscribe()
+    }
+
+    pub fn new() -> Self {
+        let (sender, _) = broadcast::channel(CAPACITY);
+        Bus { sender }
+    }
+}
+
+impl Default for Bus {
+    fn default() -> Self {
+        Self::new()
+    }
+}
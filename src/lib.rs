@@ -1,6 +1,9 @@
 pub mod app;
+mod cache;
+mod catalogue;
 mod config;
 mod database;
+mod events;
 mod heartbeat;
 mod quote;
 mod quote_api;
@@ -10,15 +13,22 @@ pub mod test_tools {
     use anyhow::{Context, Result};
     use fake::{
         faker::{lorem, name},
-        uuid, Fake, Faker,
+        uuid, Fake,
     };
     use rand::seq::IndexedRandom;
 
-    use crate::config::{GlobalConfig, ORMConfig, QuotesConfig, ServerConfig};
+    use crate::config::{
+        CatalogueConfig, CorsConfig, GlobalConfig, GraphQLConfig, ORMConfig, QuoteApiConfig,
+        QuotesConfig, ServerConfig,
+    };
+    use crate::database::canonical::fingerprint;
     use crate::database::seaorm::SeaORM;
     use crate::database::structs::quotes::Model as quote_model;
     use crate::quote::structs as quote_structs;
 
+    pub const ADMIN_TOKEN: &str = "admin-token";
+    pub const CORS_ORIGIN: &str = "https://quotes.example";
+
     pub struct Tools {
         cfg: GlobalConfig,
         db: SeaORM,
@@ -52,7 +62,11 @@ pub mod test_tools {
         }
 
         pub async fn save_quote(&self, quote: quote_model) -> Result<()> {
-            self.db.save_quote(quote.into()).await
+            self.db.save_quote(quote).await.map(|_| ())
+        }
+
+        pub async fn get_feedback(&self, user_id: &str) -> Result<Vec<(String, quote_model)>> {
+            self.db.get_feedback(user_id).await
         }
 
         pub fn compare_quotes(&self, received_quote: &str, expected_quote: quote_model) {
@@ -73,18 +87,55 @@ pub mod test_tools {
                         .choose(&mut rand::rng())
                         .unwrap()
                         .to_string(),
+                    admin_token: ADMIN_TOKEN.to_string(),
+                    http_cache_max_age: 60,
                 },
                 orm_config: ORMConfig {
                     dsn: connection_string,
+                    quote_cache_ttl: 60,
                 },
                 quotes_config: QuotesConfig {
                     random_quote_chance: 0.0,
+                    daily_quote_timezone: "UTC".to_string(),
+                    daily_quote_weighted_by_likes: true,
+                    moderation_report_threshold: 3,
+                    feedback_dislike_penalty: 1.0,
+                    feedback_skip_penalty: 0.25,
+                },
+                quote_api_config: QuoteApiConfig {
+                    url: "https://dummyjson.com/quotes/random".to_string(),
+                    connect_timeout_ms: 2000,
+                    read_timeout_ms: 3000,
+                    retries: 2,
+                    retry_backoff_ms: 100,
+                    failure_threshold: 5,
+                    open_circuit_secs: 30,
+                    ..QuoteApiConfig::default()
+                },
+                catalogue_config: CatalogueConfig::default(),
+                graphql_config: GraphQLConfig {
+                    max_depth: 10,
+                    max_complexity: 1000,
+                    max_query_size: 8192,
+                    max_batch_size: 10,
+                    ..GraphQLConfig::default()
+                },
+                cors_config: CorsConfig {
+                    allowed_origins: CORS_ORIGIN.to_string(),
+                    allowed_methods: "GET,POST,PUT,PATCH,OPTIONS".to_string(),
+                    allowed_headers: "*".to_string(),
+                    allow_credentials: false,
+                    max_age: 300,
                 },
             };
 
-            let db = SeaORM::new(&cfg.orm_config)
-                .await
-                .context("failed to init database")?;
+            // the tools check what the servers stored, they must not read a cached copy
+            let db = SeaORM::new(&ORMConfig {
+                quote_cache_ttl: 0,
+                ..cfg.orm_config.clone()
+            })
+            .await
+            .context("failed to init database")?;
 
             Ok(Tools {
                 cfg,
@@ -95,12 +146,25 @@ pub mod test_tools {
     }
 
     fn get_random_quote() -> quote_model {
+        let tags: Vec<String> = lorem::en::Words(1..5).fake();
+        let mut canonical_tags: Vec<String> = Vec::with_capacity(tags.len());
+        for tag in tags {
+            if !canonical_tags.contains(&tag) {
+                canonical_tags.push(tag);
+            }
+        }
+
+        let quote: String = lorem::en::Sentence(5..10).fake();
         quote_model {
             id: uuid::UUIDv4.fake(),
-            quote: lorem::en::Sentence(5..10).fake(),
+            fingerprint: Some(fingerprint(&quote)),
+            quote,
             author: name::en::Name().fake(),
             likes: 0i32,
-            tags: Faker.fake(),
+            tags: canonical_tags,
+            source: "manual".to_string(),
+            external_id: None,
+            status: "approved".to_string(),
         }
     }
 }
@@ -1,6 +1,9 @@
 mod app;
+mod cache;
+mod catalogue;
 mod config;
 mod database;
+mod events;
 mod heartbeat;
 mod quote;
 mod quote_api;
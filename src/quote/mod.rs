@@ -1,24 +1,41 @@
 pub mod structs;
 pub mod traits;
 
-use anyhow::{Context, Result};
+use anyhow::{anyhow, Context, Result};
+use chrono::{NaiveDate, Utc};
+use chrono_tz::Tz;
 use rand::Rng;
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 
 use crate::config::QuotesConfig;
 use crate::database::errors::Error as DatabaseErrors;
+use crate::database::structs::daily_quotes::Model as DailyQuotes;
 use crate::database::structs::quotes::Model as Quotes;
+use crate::events::{Bus, Event};
 
-use structs::from_database_quote_to_quote;
+use structs::{
+    from_database_audit, from_database_quote_to_quote, from_database_report, AuditEntry,
+    DailyQuoteOverride, DailyQuoteParams, Feedback, FeedbackProfile, ModerationDecision,
+    ModerationStatus, QuoteFilter, QuoteReport, ReviewItem, ReviewPage, Stats,
+};
 pub use traits::{Api, Database};
 
 const ONE_HUNDRED_PERCENT: f64 = 100.0;
+/// Moderator of the status changes made by the reports.
+const REPORTS_MODERATOR: &str = "reports";
+/// Quotes sharing tags with the viewed one weighed against the feedback of the user.
+const SAME_QUOTE_CANDIDATES: u64 = 20;
+/// Api calls per quote missing from a batch, the api may return a quote picked already.
+const TOP_UP_ATTEMPTS: usize = 3;
 
 #[derive(Clone)]
 pub struct Service {
     cfg: QuotesConfig,
     db: Arc<dyn Database + Send + Sync>,
     api: Arc<dyn Api + Send + Sync>,
+    events: Bus,
 }
 
 impl Service {
@@ -28,9 +45,10 @@ impl Service {
             .get_quotes(user_id)
             .await
             .context("failed to get quotes")?;
+        let profile = self.feedback_profile(user_id).await?;
 
         let quote = self
-            .randomize_quote(&quotes)
+            .randomize_quote(&quotes, &profile)
             .await
             .context("failed to get random quote")?;
 
@@ -42,7 +60,169 @@ impl Service {
         Ok(from_database_quote_to_quote(quote))
     }
 
-    pub async fn like_quote(&self, user_id: &str, quote_id: &str) -> Result<()> {
+    /// Returns an unseen quote matching the filter. Filtered quotes are only picked from
+    /// the stored ones, weighted like `get_quote` does, since the api can not be asked
+    /// for a match.
+    pub async fn get_filtered_quote(
+        &self,
+        user_id: &str,
+        filter: &QuoteFilter,
+    ) -> Result<structs::Quote> {
+        if filter.is_empty() {
+            return self.get_quote(user_id).await;
+        }
+
+        let quotes: Vec<Quotes> = self
+            .db
+            .get_quotes(user_id)
+            .await
+            .context("failed to get quotes")?
+            .into_iter()
+            .filter(|quote| filter.matches(quote))
+            .collect();
+        let profile = self.feedback_profile(user_id).await?;
+
+        let quote =
+            pick_by_likes(&quotes, &profile).ok_or_else(|| anyhow!(DatabaseErrors::ErrNotFound))?;
+
+        self.db
+            .mark_as_viewed(user_id, &quote.id)
+            .await
+            .context("failed to mark as viewed")?;
+
+        Ok(from_database_quote_to_quote(quote.clone()))
+    }
+
+    /// Returns the quote if the users can see it, a quote waiting for review or hidden by
+    /// an admin is not found.
+    pub async fn get_quote_by_id(&self, quote_id: &str) -> Result<structs::Quote> {
+        let quote = self
+            .db
+            .get_quote(quote_id)
+            .await
+            .context("failed to get quote")?;
+
+        if !is_visible(&quote) {
+            return Err(anyhow!(DatabaseErrors::ErrNotFound)).context("failed to get quote");
+        }
+
+        Ok(from_database_quote_to_quote(quote))
+    }
+
+    pub async fn find_quotes(
+        &self,
+        filter: &QuoteFilter,
+        limit: u64,
+        offset: u64,
+    ) -> Result<Vec<structs::Quote>> {
+        let quotes = self
+            .db
+            .find_quotes(filter, limit, offset)
+            .await
+            .context("failed to find quotes")?;
+
+        Ok(quotes
+            .into_iter()
+            .map(from_database_quote_to_quote)
+            .collect())
+    }
+
+    /// Returns the quotes the users can see, like `get_quote_by_id`.
+    pub async fn get_quotes_by_ids(&self, quote_ids: &[String]) -> Result<Vec<structs::Quote>> {
+        let quotes = self
+            .db
+            .get_quotes_by_ids(quote_ids)
+            .await
+            .context("failed to get quotes by ids")?;
+
+        Ok(quotes
+            .into_iter()
+            .filter(is_visible)
+            .map(from_database_quote_to_quote)
+            .collect())
+    }
+
+    /// Returns the (user id, quote id) pairs of the given ones where the user liked the quote.
+    pub async fn get_liked(&self, keys: &[(String, String)]) -> Result<HashSet<(String, String)>> {
+        let views = self
+            .db
+            .get_views_by_keys(keys)
+            .await
+            .context("failed to get views")?;
+
+        Ok(views
+            .into_iter()
+            .filter(|view| view.liked)
+            .map(|view| (view.user_id, view.quote_id))
+            .collect())
+    }
+
+    /// Returns the number of users who have seen each quote, unseen quotes are missing.
+    pub async fn get_view_counts(&self, quote_ids: &[String]) -> Result<HashMap<String, i64>> {
+        let counts = self
+            .db
+            .count_views(quote_ids)
+            .await
+            .context("failed to count views")?;
+
+        Ok(counts.into_iter().collect())
+    }
+
+    pub async fn get_history(&self, user_id: &str, limit: u64, offset: u64) -> Result<Vec<String>> {
+        self.db
+            .get_viewed_quote_ids(user_id, limit, offset)
+            .await
+            .context("failed to get viewed quotes")
+    }
+
+    /// Returns up to `count` distinct unseen quotes picked with the same weighting as
+    /// `get_quote`, quotes missing from the pool are fetched from the api.
+    pub async fn get_quotes(&self, user_id: &str, count: usize) -> Result<Vec<structs::Quote>> {
+        let mut pool = self
+            .db
+            .get_quotes(user_id)
+            .await
+            .context("failed to get quotes")?;
+        let profile = self.feedback_profile(user_id).await?;
+
+        let mut picked: Vec<Quotes> = Vec::with_capacity(count);
+        let mut missing = 0;
+        for _ in 0..count {
+            match self.pick_weighted(&pool, &profile) {
+                Some(index) => picked.push(pool.remove(index)),
+                None => missing += 1,
+            }
+        }
+
+        let mut attempts = missing * TOP_UP_ATTEMPTS;
+        while picked.len() < count && attempts > 0 {
+            attempts -= 1;
+            let quote = match self.get_random_quote().await {
+                Ok(quote) => quote,
+                // The api is only a top up, a shorter batch is better than none.
+                Err(_) if !picked.is_empty() => break,
+                Err(err) => return Err(err.context("failed to get random quote")),
+            };
+
+            if !picked.iter().any(|picked| picked.id == quote.id) {
+                picked.push(quote);
+            }
+        }
+
+        let quote_ids: Vec<String> = picked.iter().map(|quote| quote.id.clone()).collect();
+        self.db
+            .mark_as_viewed_many(user_id, &quote_ids)
+            .await
+            .context("failed to mark as viewed")?;
+
+        Ok(picked
+            .into_iter()
+            .map(from_database_quote_to_quote)
+            .collect())
+    }
+
+    /// Likes the quote once per user and returns it with the updated number of likes.
+    pub async fn like_quote(&self, user_id: &str, quote_id: &str) -> Result<structs::Quote> {
         let view = self
             .db
             .get_view(user_id, quote_id)
@@ -50,10 +230,11 @@ impl Service {
             .context("failed to get view")?;
 
         if view.liked {
-            return Ok(());
+            return self.get_quote_by_id(quote_id).await;
         }
 
-        self.db
+        let quote = self
+            .db
             .like_quote(quote_id)
             .await
             .context("failed to like quote")?;
@@ -63,21 +244,55 @@ impl Service {
             .await
             .context("failed to mark as liked")?;
 
-        Ok(())
+        self.events.publish(Event::LikeCountChanged {
+            quote_id: quote.id.clone(),
+            likes: quote.likes,
+        });
+
+        Ok(from_database_quote_to_quote(quote))
+    }
+
+    /// Records that the user disliked or skipped a quote they viewed, the authors and tags
+    /// of such quotes are picked less often for them afterwards.
+    pub async fn give_feedback(
+        &self,
+        user_id: &str,
+        quote_id: &str,
+        feedback: Feedback,
+    ) -> Result<()> {
+        self.db
+            .set_feedback(user_id, quote_id, feedback)
+            .await
+            .context("failed to save feedback")
     }
 
+    /// Returns an unseen quote sharing the most tags with the given one. When the user gave
+    /// feedback, the closest candidates are weighed against it instead.
     pub async fn get_same_quote(&self, user_id: &str, quote_id: &str) -> Result<structs::Quote> {
         let viewed_quote = self
             .db
             .get_quote(quote_id)
             .await
             .context("failed to get viewed quote")?;
+        let profile = self.feedback_profile(user_id).await?;
+
+        let same_quote = if profile.is_empty() {
+            self.db.get_same_quote(user_id, &viewed_quote).await
+        } else {
+            self.rank_same_quotes(user_id, &viewed_quote, &profile, 1)
+                .await
+                .and_then(|quotes| {
+                    quotes
+                        .into_iter()
+                        .next()
+                        .ok_or_else(|| anyhow!(DatabaseErrors::ErrNotFound))
+                })
+        };
 
-        let quote = match self.db.get_same_quote(user_id, &viewed_quote).await {
+        let quote = match same_quote {
             Ok(quote) => quote,
             Err(err) => match err.downcast_ref::<DatabaseErrors>() {
                 Some(DatabaseErrors::ErrNotFound) => self
-                    .api
                     .get_random_quote()
                     .await
                     .context("failed to get random quote")?,
@@ -93,44 +308,402 @@ impl Service {
         Ok(from_database_quote_to_quote(quote))
     }
 
+    /// Returns up to `count` unseen quotes closest to the given one, without falling
+    /// back to the api: an empty list means there is nothing similar left. When the user
+    /// gave feedback, the closest candidates are weighed against it like in `get_same_quote`.
+    pub async fn get_similar_quotes(
+        &self,
+        user_id: &str,
+        quote_id: &str,
+        count: usize,
+    ) -> Result<Vec<structs::Quote>> {
+        let viewed_quote = self
+            .db
+            .get_quote(quote_id)
+            .await
+            .context("failed to get viewed quote")?;
+        let profile = self.feedback_profile(user_id).await?;
+
+        let quotes = if profile.is_empty() {
+            self.db
+                .get_same_quotes(user_id, &viewed_quote, count as u64)
+                .await
+        } else {
+            self.rank_same_quotes(user_id, &viewed_quote, &profile, count)
+                .await
+        }
+        .context("failed to get same quotes")?;
+
+        let quote_ids: Vec<String> = quotes.iter().map(|quote| quote.id.clone()).collect();
+        self.db
+            .mark_as_viewed_many(user_id, &quote_ids)
+            .await
+            .context("failed to mark as viewed")?;
+
+        Ok(quotes
+            .into_iter()
+            .map(from_database_quote_to_quote)
+            .collect())
+    }
+
+    /// Returns the quote shared by everyone for the current day in the requested timezone.
+    /// The first request of the day picks it deterministically and stores it, so every
+    /// instance and every restart returns the same quote until the day ends.
+    pub async fn get_quote_of_the_day(&self, params: &DailyQuoteParams) -> Result<structs::Quote> {
+        let (day, timezone, tag) = self.daily_key(params, None)?;
+
+        match self.db.get_daily_quote(day, &timezone, &tag).await {
+            Ok(quote) => return Ok(from_database_quote_to_quote(quote)),
+            Err(err) => match err.downcast_ref::<DatabaseErrors>() {
+                Some(DatabaseErrors::ErrNotFound) => {}
+                _ => return Err(err.context("failed to get quote of the day")),
+            },
+        }
+
+        let quotes = self
+            .db
+            .get_daily_candidates(&tag)
+            .await
+            .context("failed to get quotes of the day candidates")?;
+
+        let quote = self
+            .pick_daily_quote(&format!("{day}/{timezone}/{tag}"), &quotes)
+            .ok_or_else(|| anyhow!(DatabaseErrors::ErrNotFound))?;
+
+        self.db
+            .save_daily_quote(
+                DailyQuotes {
+                    day,
+                    timezone: timezone.clone(),
+                    tag: tag.clone(),
+                    quote_id: quote.id.clone(),
+                    overridden: false,
+                },
+                false,
+            )
+            .await
+            .context("failed to save quote of the day")?;
+
+        // Another instance could have stored its pick first, the stored one always wins.
+        let quote = self
+            .db
+            .get_daily_quote(day, &timezone, &tag)
+            .await
+            .context("failed to get quote of the day")?;
+
+        Ok(from_database_quote_to_quote(quote))
+    }
+
+    pub async fn set_quote_of_the_day(
+        &self,
+        daily_override: &DailyQuoteOverride,
+    ) -> Result<structs::Quote> {
+        let quote = self
+            .db
+            .get_quote(&daily_override.quote_id)
+            .await
+            .context("failed to get quote")?;
+
+        let (day, timezone, tag) = self.daily_key(&daily_override.params, daily_override.date)?;
+        self.db
+            .save_daily_quote(
+                DailyQuotes {
+                    day,
+                    timezone,
+                    tag,
+                    quote_id: quote.id.clone(),
+                    overridden: true,
+                },
+                true,
+            )
+            .await
+            .context("failed to save quote of the day")?;
+
+        Ok(from_database_quote_to_quote(quote))
+    }
+
+    /// Records the report of a user, an approved quote reported by enough users is sent
+    /// back to review and hidden from the users until an admin approves it.
+    pub async fn report_quote(&self, report: &QuoteReport) -> Result<()> {
+        let quote = self
+            .db
+            .get_quote(&report.quote_id)
+            .await
+            .context("failed to get quote")?;
+
+        let reports = self
+            .db
+            .report_quote(report)
+            .await
+            .context("failed to report quote")?;
+
+        let threshold = self.cfg.moderation_report_threshold;
+        if threshold == 0 || reports < threshold || !is_visible(&quote) {
+            return Ok(());
+        }
+
+        let decision = ModerationDecision {
+            quote_id: quote.id,
+            status: ModerationStatus::Pending,
+            moderator: REPORTS_MODERATOR.to_string(),
+            note: format!("{reports} open reports"),
+        };
+        self.db
+            .set_quote_status(&decision, false)
+            .await
+            .context("failed to send quote to review")?;
+
+        Ok(())
+    }
+
+    pub async fn get_review_queue(&self, page: &ReviewPage) -> Result<Vec<ReviewItem>> {
+        let queue = self
+            .db
+            .get_review_queue(page.limit, page.offset)
+            .await
+            .context("failed to get review queue")?;
+
+        queue
+            .into_iter()
+            .map(|(quote, reports)| {
+                Ok(ReviewItem {
+                    status: ModerationStatus::parse(&quote.status)?,
+                    quote: from_database_quote_to_quote(quote),
+                    reports: reports.into_iter().map(from_database_report).collect(),
+                })
+            })
+            .collect()
+    }
+
+    /// Approves or hides the quote, its open reports are resolved by the decision.
+    pub async fn moderate_quote(&self, decision: &ModerationDecision) -> Result<AuditEntry> {
+        let entry = self
+            .db
+            .set_quote_status(decision, true)
+            .await
+            .context("failed to moderate quote")?;
+
+        from_database_audit(entry)
+    }
+
+    /// Status changes of the quote, oldest first.
+    pub async fn get_moderation_audit(&self, quote_id: &str) -> Result<Vec<AuditEntry>> {
+        let entries = self
+            .db
+            .get_moderation_audit(quote_id)
+            .await
+            .context("failed to get moderation audit")?;
+
+        entries.into_iter().map(from_database_audit).collect()
+    }
+
+    pub fn get_stats(&self) -> Stats {
+        Stats {
+            rejected_quotes: self.api.rejected_quotes(),
+        }
+    }
+
     pub fn new(
         cfg: &QuotesConfig,
         db: Arc<dyn Database + Send + Sync>,
         api: Arc<dyn Api + Send + Sync>,
+        events: Bus,
     ) -> Self {
         Service {
             cfg: cfg.to_owned(),
             db,
             api,
+            events,
+        }
+    }
+
+    /// Falls back to the stored quotes when the api is failing.
+    async fn randomize_quote(
+        &self,
+        quotes: &[Quotes],
+        profile: &FeedbackProfile,
+    ) -> Result<Quotes> {
+        if let Some(index) = self.pick_weighted(quotes, profile) {
+            return Ok(quotes[index].clone());
+        }
+
+        match self.get_random_quote().await {
+            Ok(quote) => Ok(quote),
+            Err(err) => match pick_by_likes(quotes, profile) {
+                Some(quote) => {
+                    log::warn!("failed to get random quote, using a stored one: {err:#}");
+                    Ok(quote.clone())
+                }
+                None => Err(err),
+            },
         }
     }
 
-    async fn randomize_quote(&self, quotes: &[Quotes]) -> Result<Quotes> {
+    /// New quote of the api, an already stored one may have been hidden since.
+    async fn get_random_quote(&self) -> Result<Quotes> {
+        let quote = self.api.get_random_quote().await?;
+        if !is_visible(&quote) {
+            return Err(anyhow!("random quote {} is {}", quote.id, quote.status));
+        }
+
+        Ok(quote)
+    }
+
+    /// Feedback of the user, not queried when neither feedback is penalized.
+    async fn feedback_profile(&self, user_id: &str) -> Result<FeedbackProfile> {
+        if self.cfg.feedback_dislike_penalty <= 0.0 && self.cfg.feedback_skip_penalty <= 0.0 {
+            return Ok(FeedbackProfile::default());
+        }
+
+        let feedback = self
+            .db
+            .get_feedback(user_id)
+            .await
+            .context("failed to get feedback")?;
+
+        Ok(FeedbackProfile::new(
+            &feedback,
+            self.cfg.feedback_dislike_penalty,
+            self.cfg.feedback_skip_penalty,
+        ))
+    }
+
+    /// Up to `count` closest candidates once the shared tags are weighed against the
+    /// feedback, the candidates come ordered by shared tags so the earliest wins a tie.
+    async fn rank_same_quotes(
+        &self,
+        user_id: &str,
+        viewed_quote: &Quotes,
+        profile: &FeedbackProfile,
+        count: usize,
+    ) -> Result<Vec<Quotes>> {
+        let candidates = self
+            .db
+            .get_same_quotes(
+                user_id,
+                viewed_quote,
+                SAME_QUOTE_CANDIDATES.max(count as u64),
+            )
+            .await?;
+
+        let mut ranked: Vec<(f64, Quotes)> = candidates
+            .into_iter()
+            .map(|quote| {
+                let shared = quote
+                    .tags
+                    .iter()
+                    .filter(|tag| viewed_quote.tags.contains(tag))
+                    .count();
+                ((shared + 1) as f64 * profile.factor(&quote), quote)
+            })
+            .collect();
+        // the sort is stable, equal scores keep the order of the candidates
+        ranked.sort_by(|(a, _), (b, _)| b.total_cmp(a));
+
+        Ok(ranked
+            .into_iter()
+            .take(count)
+            .map(|(_, quote)| quote)
+            .collect())
+    }
+
+    /// Picks the index of a quote weighted by its likes and the feedback of the user,
+    /// `None` means that a new quote should be fetched from the api instead.
+    fn pick_weighted(&self, quotes: &[Quotes], profile: &FeedbackProfile) -> Option<usize> {
+        let weight = |q: &Quotes| {
+            let likes = if q.likes == 0 { 1.0 } else { q.likes as f64 };
+            likes * profile.factor(q)
+        };
+
         let random_percent = rand::rng().random_range(0.0..101.0);
         if (ONE_HUNDRED_PERCENT - self.cfg.random_quote_chance) > random_percent
             && !quotes.is_empty()
         {
-            let likes_count = quotes.iter().fold(0.0, |acc, q| {
-                acc + if q.likes == 0 { 1.0 } else { q.likes as f64 }
-            });
+            let likes_count = quotes.iter().fold(0.0, |acc, q| acc + weight(q));
 
             let mut accumulator = 0.0;
             let del = likes_count * ONE_HUNDRED_PERCENT
                 / (ONE_HUNDRED_PERCENT - self.cfg.random_quote_chance);
 
-            for q in quotes.iter() {
-                let likes = if q.likes == 0 { 1.0 } else { q.likes as f64 };
-                let percent = likes / del * ONE_HUNDRED_PERCENT;
+            for (index, q) in quotes.iter().enumerate() {
+                let percent = weight(q) / del * ONE_HUNDRED_PERCENT;
                 if percent + accumulator >= random_percent {
-                    return Ok(q.clone());
+                    return Some(index);
                 }
 
                 accumulator += percent
             }
         }
 
-        self.api.get_random_quote().await
+        None
+    }
+
+    fn daily_key(
+        &self,
+        params: &DailyQuoteParams,
+        date: Option<NaiveDate>,
+    ) -> Result<(NaiveDate, String, String)> {
+        let timezone = match params.timezone {
+            Some(timezone) => timezone,
+            None if self.cfg.daily_quote_timezone.is_empty() => Tz::UTC,
+            None => self
+                .cfg
+                .daily_quote_timezone
+                .parse::<Tz>()
+                .map_err(|_| anyhow!("unknown default timezone"))?,
+        };
+
+        let day = date.unwrap_or_else(|| Utc::now().with_timezone(&timezone).date_naive());
+        Ok((
+            day,
+            timezone.name().to_string(),
+            params.tag.clone().unwrap_or_default(),
+        ))
+    }
+
+    /// Maps the seed to a point on the quotes laid out one after another, each quote
+    /// taking as much space as its likes when weighting is enabled.
+    fn pick_daily_quote<'a>(&self, seed: &str, quotes: &'a [Quotes]) -> Option<&'a Quotes> {
+        let weight = |quote: &Quotes| match self.cfg.daily_quote_weighted_by_likes {
+            true => quote.likes.max(1) as f64,
+            false => 1.0,
+        };
+
+        let digest = Sha256::digest(seed);
+        let seed = u64::from_be_bytes(digest[..8].try_into().ok()?);
+        let mut point = seed as f64 / u64::MAX as f64 * quotes.iter().map(weight).sum::<f64>();
+
+        for quote in quotes {
+            if point < weight(quote) {
+                return Some(quote);
+            }
+
+            point -= weight(quote);
+        }
+
+        quotes.last()
+    }
+}
+
+fn is_visible(quote: &Quotes) -> bool {
+    quote.status == ModerationStatus::Approved.as_str()
+}
+
+/// Picks a quote with a chance proportional to its likes and the feedback of the user,
+/// unliked quotes count as one like.
+fn pick_by_likes<'a>(quotes: &'a [Quotes], profile: &FeedbackProfile) -> Option<&'a Quotes> {
+    let weight = |quote: &Quotes| quote.likes.max(1) as f64 * profile.factor(quote);
+    let mut point = rand::rng().random_range(0.0..1.0) * quotes.iter().map(weight).sum::<f64>();
+
+    for quote in quotes {
+        if point < weight(quote) {
+            return Some(quote);
+        }
+
+        point -= weight(quote);
     }
+
+    quotes.last()
 }
 
 #[cfg(test)]
@@ -141,9 +714,11 @@ mod tests {
         uuid, Fake, Faker,
     };
     use mockall::predicate::*;
+    use std::collections::HashSet;
     use std::sync::LazyLock;
 
     use super::*;
+    use crate::database::structs::moderation_audit::Model as audit_model;
     use crate::database::structs::quotes::Model as quote_model;
     use crate::database::structs::views::Model as view_model;
     use crate::quote::traits::{MockApi, MockDatabase};
@@ -156,11 +731,16 @@ mod tests {
         author: name::en::Name().fake(),
         likes: Faker.fake(),
         tags: Faker.fake(),
+        fingerprint: None,
+        source: Faker.fake(),
+        external_id: Faker.fake(),
+        status: "approved".to_string(),
     });
     static VIEW: LazyLock<view_model> = LazyLock::new(|| view_model {
         user_id: USER_ID.clone(),
         quote_id: QUOTE_ID.clone(),
         liked: true,
+        feedback: None,
     });
 
     #[tokio::test]
@@ -201,6 +781,7 @@ mod tests {
         let service = new_service(
             QuotesConfig {
                 random_quote_chance: 100.0,
+                ..QuotesConfig::default()
             },
             (db, api),
         );
@@ -211,47 +792,482 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn test_like_quote_success() {
+    async fn test_get_quote_api_failure_uses_stored_quotes() {
         let mut db = MockDatabase::new();
 
-        db.expect_get_view()
-            .with(eq(USER_ID.clone()), eq(QUOTE_ID.clone()))
-            .returning(|_, _| Ok(VIEW.clone()));
-
-        db.expect_like_quote()
-            .with(eq(QUOTE_ID.clone()))
-            .returning(|_| Ok(()));
+        db.expect_get_quotes()
+            .with(eq(USER_ID.clone()))
+            .returning(|_| Ok(vec![QUOTE.clone()]));
 
-        db.expect_mark_as_liked()
+        db.expect_mark_as_viewed()
             .with(eq(USER_ID.clone()), eq(QUOTE_ID.clone()))
             .returning(|_, _| Ok(()));
 
-        let service = new_service(QuotesConfig::default(), (db, MockApi::new()));
-        let res = service.like_quote(&USER_ID, &QUOTE_ID).await;
+        let mut api = MockApi::new();
+
+        api.expect_get_random_quote()
+            .times(1)
+            .returning(|| Err(anyhow!("quote api is failing, circuit is open")));
+
+        let service = new_service(
+            QuotesConfig {
+                random_quote_chance: 100.0,
+                ..QuotesConfig::default()
+            },
+            (db, api),
+        );
+
+        let res = service.get_quote(&USER_ID).await;
         assert!(res.is_ok());
+        assert_eq!(res.unwrap(), from_database_quote_to_quote(QUOTE.clone()));
     }
 
     #[tokio::test]
-    async fn test_like_quote_already_liked() {
+    async fn test_get_quotes_success() {
+        let pool: Vec<quote_model> = (0..3)
+            .map(|_| quote_model {
+                id: uuid::UUIDv4.fake(),
+                ..QUOTE.clone()
+            })
+            .collect();
+
         let mut db = MockDatabase::new();
 
-        db.expect_get_view()
-            .with(eq(USER_ID.clone()), eq(QUOTE_ID.clone()))
-            .returning(|_, _| Ok(VIEW.clone()));
+        let quotes = pool.clone();
+        db.expect_get_quotes()
+            .with(eq(USER_ID.clone()))
+            .times(1)
+            .returning(move |_| Ok(quotes.clone()));
 
-        let service = new_service(QuotesConfig::default(), (db, MockApi::new()));
-        let res = service.like_quote(&USER_ID, &QUOTE_ID).await;
+        let quotes = pool.clone();
+        db.expect_mark_as_viewed_many()
+            .withf(move |user_id, quote_ids| {
+                let unique: HashSet<&String> = quote_ids.iter().collect();
+                user_id == *USER_ID
+                    && quote_ids.len() == 5
+                    && unique.len() == 5
+                    && quotes.iter().all(|quote| quote_ids.contains(&quote.id))
+            })
+            .times(1)
+            .returning(|_, _| Ok(()));
+
+        let mut api = MockApi::new();
+
+        api.expect_get_random_quote().returning(|| {
+            Ok(quote_model {
+                id: uuid::UUIDv4.fake(),
+                ..QUOTE.clone()
+            })
+        });
+
+        let service = new_service(QuotesConfig::default(), (db, api));
+        let res = service.get_quotes(&USER_ID, 5).await;
         assert!(res.is_ok());
+        assert_eq!(res.unwrap().len(), 5);
     }
 
     #[tokio::test]
-    async fn test_get_same_quote_success() {
+    async fn test_get_quotes_skips_duplicates_of_the_api() {
         let mut db = MockDatabase::new();
 
-        db.expect_get_quote()
-            .with(eq(QUOTE_ID.clone()))
-            .returning(|_| Ok(QUOTE.clone()));
-
+        db.expect_get_quotes()
+            .with(eq(USER_ID.clone()))
+            .returning(|_| Ok(vec![]));
+
+        db.expect_mark_as_viewed_many()
+            .withf(|user_id, quote_ids| {
+                let unique: HashSet<&String> = quote_ids.iter().collect();
+                user_id == *USER_ID && quote_ids.len() == 3 && unique.len() == 3
+            })
+            .times(1)
+            .returning(|_, _| Ok(()));
+
+        // every other answer of the api is the same quote
+        let mut api = MockApi::new();
+
+        let mut calls = 0;
+        api.expect_get_random_quote().returning(move || {
+            calls += 1;
+            Ok(match calls % 2 {
+                1 => QUOTE.clone(),
+                _ => quote_model {
+                    id: uuid::UUIDv4.fake(),
+                    ..QUOTE.clone()
+                },
+            })
+        });
+
+        let service = new_service(QuotesConfig::default(), (db, api));
+        let res = service.get_quotes(&USER_ID, 3).await;
+        assert_eq!(res.unwrap().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_get_quotes_stops_topping_up() {
+        let mut db = MockDatabase::new();
+
+        db.expect_get_quotes()
+            .with(eq(USER_ID.clone()))
+            .returning(|_| Ok(vec![]));
+
+        db.expect_mark_as_viewed_many()
+            .withf(|user_id, quote_ids| user_id == *USER_ID && quote_ids == [QUOTE_ID.clone()])
+            .times(1)
+            .returning(|_, _| Ok(()));
+
+        let mut api = MockApi::new();
+
+        api.expect_get_random_quote()
+            .times(3 * TOP_UP_ATTEMPTS)
+            .returning(|| Ok(QUOTE.clone()));
+
+        let service = new_service(QuotesConfig::default(), (db, api));
+        let res = service.get_quotes(&USER_ID, 3).await;
+        assert_eq!(res.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_get_quotes_api_failure() {
+        let mut db = MockDatabase::new();
+
+        db.expect_get_quotes()
+            .with(eq(USER_ID.clone()))
+            .returning(|_| Ok(vec![]));
+
+        db.expect_mark_as_viewed_many().never();
+
+        let mut api = MockApi::new();
+
+        api.expect_get_random_quote()
+            .times(1)
+            .returning(|| Err(anyhow!("api is unavailable")));
+
+        let service = new_service(QuotesConfig::default(), (db, api));
+        let res = service.get_quotes(&USER_ID, 3).await;
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn test_parse_batch_count() {
+        assert_eq!(
+            structs::parse_batch_count(None).unwrap(),
+            structs::DEFAULT_BATCH_COUNT
+        );
+        assert_eq!(structs::parse_batch_count(Some(7)).unwrap(), 7);
+        assert!(structs::parse_batch_count(Some(0)).is_err());
+        assert!(structs::parse_batch_count(Some(1000)).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_like_quote_success() {
+        let mut db = MockDatabase::new();
+
+        db.expect_get_view()
+            .with(eq(USER_ID.clone()), eq(QUOTE_ID.clone()))
+            .returning(|_, _| {
+                Ok(view_model {
+                    liked: false,
+                    ..VIEW.clone()
+                })
+            });
+
+        db.expect_like_quote()
+            .with(eq(QUOTE_ID.clone()))
+            .returning(|_| Ok(QUOTE.clone()));
+
+        db.expect_mark_as_liked()
+            .with(eq(USER_ID.clone()), eq(QUOTE_ID.clone()))
+            .returning(|_, _| Ok(()));
+
+        let service = new_service(QuotesConfig::default(), (db, MockApi::new()));
+        let res = service.like_quote(&USER_ID, &QUOTE_ID).await;
+        assert!(res.is_ok());
+        assert_eq!(res.unwrap(), from_database_quote_to_quote(QUOTE.clone()));
+    }
+
+    #[tokio::test]
+    async fn test_like_quote_publishes_like_count() {
+        let mut db = MockDatabase::new();
+
+        db.expect_get_view()
+            .with(eq(USER_ID.clone()), eq(QUOTE_ID.clone()))
+            .returning(|_, _| {
+                Ok(view_model {
+                    liked: false,
+                    ..VIEW.clone()
+                })
+            });
+
+        db.expect_like_quote()
+            .with(eq(QUOTE_ID.clone()))
+            .times(1)
+            .returning(|_| Ok(QUOTE.clone()));
+
+        db.expect_mark_as_liked()
+            .with(eq(USER_ID.clone()), eq(QUOTE_ID.clone()))
+            .times(1)
+            .returning(|_, _| Ok(()));
+
+        let events = Bus::new();
+        let mut received = events.subscribe();
+        let service = Service::new(
+            &QuotesConfig::default(),
+            Arc::new(db),
+            Arc::new(MockApi::new()),
+            events,
+        );
+
+        let res = service.like_quote(&USER_ID, &QUOTE_ID).await;
+        assert!(res.is_ok());
+        assert_eq!(
+            received.try_recv().unwrap(),
+            Event::LikeCountChanged {
+                quote_id: QUOTE_ID.clone(),
+                likes: QUOTE.likes,
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_like_quote_already_liked() {
+        let mut db = MockDatabase::new();
+
+        db.expect_get_view()
+            .with(eq(USER_ID.clone()), eq(QUOTE_ID.clone()))
+            .returning(|_, _| Ok(VIEW.clone()));
+
+        db.expect_like_quote().never();
+
+        db.expect_get_quote()
+            .with(eq(QUOTE_ID.clone()))
+            .returning(|_| Ok(QUOTE.clone()));
+
+        let service = new_service(QuotesConfig::default(), (db, MockApi::new()));
+        let res = service.like_quote(&USER_ID, &QUOTE_ID).await;
+        assert!(res.is_ok());
+        assert_eq!(res.unwrap(), from_database_quote_to_quote(QUOTE.clone()));
+    }
+
+    #[tokio::test]
+    async fn test_get_filtered_quote_success() {
+        let other = quote_model {
+            id: uuid::UUIDv4.fake(),
+            author: "Someone Else".to_string(),
+            tags: vec!["unrelated".to_string()],
+            ..QUOTE.clone()
+        };
+        let matching = quote_model {
+            author: "Ada Lovelace".to_string(),
+            tags: vec!["science".to_string()],
+            ..QUOTE.clone()
+        };
+
+        let mut db = MockDatabase::new();
+
+        let pool = vec![other, matching.clone()];
+        db.expect_get_quotes()
+            .with(eq(USER_ID.clone()))
+            .returning(move |_| Ok(pool.clone()));
+
+        db.expect_mark_as_viewed()
+            .with(eq(USER_ID.clone()), eq(QUOTE_ID.clone()))
+            .times(1)
+            .returning(|_, _| Ok(()));
+
+        let service = new_service(QuotesConfig::default(), (db, MockApi::new()));
+        let filter = QuoteFilter::new(Some(" ada  lovelace"), &["Science".to_string()]);
+        let res = service.get_filtered_quote(&USER_ID, &filter).await;
+        assert!(res.is_ok());
+        assert_eq!(res.unwrap(), from_database_quote_to_quote(matching));
+    }
+
+    #[tokio::test]
+    async fn test_get_filtered_quote_not_found() {
+        let mut db = MockDatabase::new();
+
+        db.expect_get_quotes()
+            .with(eq(USER_ID.clone()))
+            .returning(|_| Ok(vec![QUOTE.clone()]));
+
+        db.expect_mark_as_viewed().never();
+
+        let mut api = MockApi::new();
+
+        api.expect_get_random_quote().never();
+
+        let service = new_service(QuotesConfig::default(), (db, api));
+        let filter = QuoteFilter::new(
+            None,
+            &[format!("{}-missing", uuid::UUIDv4.fake::<String>())],
+        );
+        let res = service.get_filtered_quote(&USER_ID, &filter).await;
+        assert!(matches!(
+            res.unwrap_err().downcast_ref::<DatabaseErrors>(),
+            Some(DatabaseErrors::ErrNotFound)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_get_filtered_quote_weighs_feedback() {
+        let disliked = quote_model {
+            id: uuid::UUIDv4.fake(),
+            author: format!("{} Disliked", QUOTE.author),
+            tags: vec!["science".to_string()],
+            likes: 1000,
+            ..QUOTE.clone()
+        };
+        let other = quote_model {
+            tags: vec!["science".to_string()],
+            likes: 1,
+            ..QUOTE.clone()
+        };
+
+        let mut db = MockDatabase::new();
+
+        // the filtered tag is shared, only the author is disliked
+        let profile = quote_model {
+            tags: vec![],
+            ..disliked.clone()
+        };
+        let pool = vec![disliked, other.clone()];
+        db.expect_get_quotes()
+            .with(eq(USER_ID.clone()))
+            .returning(move |_| Ok(pool.clone()));
+
+        db.expect_get_feedback()
+            .with(eq(USER_ID.clone()))
+            .times(1)
+            .returning(move |_| Ok(vec![("dislike".to_string(), profile.clone())]));
+
+        db.expect_mark_as_viewed()
+            .with(eq(USER_ID.clone()), eq(QUOTE_ID.clone()))
+            .times(1)
+            .returning(|_, _| Ok(()));
+
+        let mut api = MockApi::new();
+
+        api.expect_get_random_quote().never();
+
+        let service = new_service(
+            QuotesConfig {
+                feedback_dislike_penalty: 1e9,
+                ..QuotesConfig::default()
+            },
+            (db, api),
+        );
+
+        let filter = QuoteFilter::new(None, &["science".to_string()]);
+        let res = service.get_filtered_quote(&USER_ID, &filter).await;
+        assert_eq!(res.unwrap(), from_database_quote_to_quote(other));
+    }
+
+    #[tokio::test]
+    async fn test_get_similar_quotes_success() {
+        let similar: Vec<quote_model> = (0..2)
+            .map(|_| quote_model {
+                id: uuid::UUIDv4.fake(),
+                ..QUOTE.clone()
+            })
+            .collect();
+        let similar_ids: Vec<String> = similar.iter().map(|quote| quote.id.clone()).collect();
+
+        let mut db = MockDatabase::new();
+
+        db.expect_get_quote()
+            .with(eq(QUOTE_ID.clone()))
+            .returning(|_| Ok(QUOTE.clone()));
+
+        let quotes = similar.clone();
+        db.expect_get_same_quotes()
+            .with(eq(USER_ID.clone()), eq(QUOTE.clone()), eq(3))
+            .times(1)
+            .returning(move |_, _, _| Ok(quotes.clone()));
+
+        db.expect_mark_as_viewed_many()
+            .with(eq(USER_ID.clone()), eq(similar_ids))
+            .times(1)
+            .returning(|_, _| Ok(()));
+
+        let service = new_service(QuotesConfig::default(), (db, MockApi::new()));
+        let res = service.get_similar_quotes(&USER_ID, &QUOTE_ID, 3).await;
+        assert!(res.is_ok());
+        assert_eq!(
+            res.unwrap(),
+            similar
+                .into_iter()
+                .map(from_database_quote_to_quote)
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_get_similar_quotes_weighs_feedback() {
+        let disliked = quote_model {
+            id: uuid::UUIDv4.fake(),
+            author: format!("{} Disliked", QUOTE.author),
+            ..QUOTE.clone()
+        };
+        let others: Vec<quote_model> = (0..2)
+            .map(|_| quote_model {
+                id: uuid::UUIDv4.fake(),
+                ..QUOTE.clone()
+            })
+            .collect();
+        let other_ids: Vec<String> = others.iter().map(|quote| quote.id.clone()).collect();
+
+        let mut db = MockDatabase::new();
+
+        db.expect_get_quote()
+            .with(eq(QUOTE_ID.clone()))
+            .returning(|_| Ok(QUOTE.clone()));
+
+        let profile = disliked.clone();
+        db.expect_get_feedback()
+            .with(eq(USER_ID.clone()))
+            .returning(move |_| Ok(vec![("dislike".to_string(), profile.clone())]));
+
+        // the disliked author comes first by shared tags but is ranked last
+        let candidates = vec![disliked, others[0].clone(), others[1].clone()];
+        db.expect_get_same_quotes()
+            .with(
+                eq(USER_ID.clone()),
+                eq(QUOTE.clone()),
+                eq(SAME_QUOTE_CANDIDATES),
+            )
+            .times(1)
+            .returning(move |_, _, _| Ok(candidates.clone()));
+
+        db.expect_mark_as_viewed_many()
+            .with(eq(USER_ID.clone()), eq(other_ids))
+            .times(1)
+            .returning(|_, _| Ok(()));
+
+        let service = new_service(
+            QuotesConfig {
+                feedback_dislike_penalty: 1.0,
+                ..QuotesConfig::default()
+            },
+            (db, MockApi::new()),
+        );
+
+        let res = service.get_similar_quotes(&USER_ID, &QUOTE_ID, 2).await;
+        assert_eq!(
+            res.unwrap(),
+            others
+                .into_iter()
+                .map(from_database_quote_to_quote)
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_get_same_quote_success() {
+        let mut db = MockDatabase::new();
+
+        db.expect_get_quote()
+            .with(eq(QUOTE_ID.clone()))
+            .returning(|_| Ok(QUOTE.clone()));
+
         db.expect_get_same_quote()
             .with(eq(USER_ID.clone()), eq(QUOTE.clone()))
             .returning(|_, _| Ok(QUOTE.clone()));
@@ -293,7 +1309,392 @@ mod tests {
         assert_eq!(res.unwrap(), from_database_quote_to_quote(QUOTE.clone()));
     }
 
+    #[test]
+    fn test_feedback_profile_factor() {
+        let quote = |author: &str, tags: &[&str]| quote_model {
+            author: author.to_string(),
+            tags: tags.iter().map(|tag| tag.to_string()).collect(),
+            ..QUOTE.clone()
+        };
+        let feedback = vec![
+            (
+                "dislike".to_string(),
+                quote("Bad Author", &["Gloom", "life"]),
+            ),
+            ("skip".to_string(), quote("Other Author", &["life"])),
+            ("like".to_string(), quote("Good Author", &["joy"])),
+        ];
+
+        let profile = FeedbackProfile::new(&feedback, 1.0, 0.25);
+        assert_eq!(profile.factor(&quote("bad author", &[])), 0.5);
+        assert_eq!(
+            profile.factor(&quote("Good Author", &["gloom", "Life"])),
+            1.0 / 3.25
+        );
+        assert_eq!(profile.factor(&quote("Good Author", &["joy"])), 1.0);
+        assert!(FeedbackProfile::new(&feedback, 0.0, 0.0).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_get_quote_down_weights_disliked_author() {
+        let disliked = quote_model {
+            id: uuid::UUIDv4.fake(),
+            author: format!("{} Disliked", QUOTE.author),
+            tags: vec![],
+            likes: 1000,
+            ..QUOTE.clone()
+        };
+        let other = quote_model {
+            tags: vec![],
+            likes: 1,
+            ..QUOTE.clone()
+        };
+
+        let mut db = MockDatabase::new();
+
+        let (pool, profile) = (vec![disliked.clone(), other.clone()], disliked.clone());
+        db.expect_get_quotes()
+            .with(eq(USER_ID.clone()))
+            .returning(move |_| Ok(pool.clone()));
+
+        db.expect_get_feedback()
+            .with(eq(USER_ID.clone()))
+            .times(1)
+            .returning(move |_| Ok(vec![("dislike".to_string(), profile.clone())]));
+
+        db.expect_mark_as_viewed()
+            .with(eq(USER_ID.clone()), eq(QUOTE_ID.clone()))
+            .returning(|_, _| Ok(()));
+
+        let mut api = MockApi::new();
+
+        let random = other.clone();
+        api.expect_get_random_quote()
+            .returning(move || Ok(random.clone()));
+
+        let service = new_service(
+            QuotesConfig {
+                feedback_dislike_penalty: 1e9,
+                ..QuotesConfig::default()
+            },
+            (db, api),
+        );
+
+        let res = service.get_quote(&USER_ID).await;
+        assert_eq!(res.unwrap(), from_database_quote_to_quote(other));
+    }
+
+    #[tokio::test]
+    async fn test_get_same_quote_weighs_feedback() {
+        let disliked = quote_model {
+            id: uuid::UUIDv4.fake(),
+            author: format!("{} Disliked", QUOTE.author),
+            ..QUOTE.clone()
+        };
+        let other = quote_model {
+            id: uuid::UUIDv4.fake(),
+            ..QUOTE.clone()
+        };
+        let other_id = other.id.clone();
+
+        let mut db = MockDatabase::new();
+
+        db.expect_get_quote()
+            .with(eq(QUOTE_ID.clone()))
+            .returning(|_| Ok(QUOTE.clone()));
+
+        let profile = disliked.clone();
+        db.expect_get_feedback()
+            .with(eq(USER_ID.clone()))
+            .returning(move |_| Ok(vec![("dislike".to_string(), profile.clone())]));
+
+        db.expect_get_same_quote().never();
+
+        let candidates = vec![disliked, other.clone()];
+        db.expect_get_same_quotes()
+            .with(
+                eq(USER_ID.clone()),
+                eq(QUOTE.clone()),
+                eq(SAME_QUOTE_CANDIDATES),
+            )
+            .times(1)
+            .returning(move |_, _, _| Ok(candidates.clone()));
+
+        db.expect_mark_as_viewed()
+            .with(eq(USER_ID.clone()), eq(other_id))
+            .times(1)
+            .returning(|_, _| Ok(()));
+
+        let service = new_service(
+            QuotesConfig {
+                feedback_dislike_penalty: 1.0,
+                ..QuotesConfig::default()
+            },
+            (db, MockApi::new()),
+        );
+
+        let res = service.get_same_quote(&USER_ID, &QUOTE_ID).await;
+        assert_eq!(res.unwrap(), from_database_quote_to_quote(other));
+    }
+
+    #[tokio::test]
+    async fn test_get_quote_of_the_day_stored() {
+        let mut db = MockDatabase::new();
+
+        db.expect_get_daily_quote()
+            .with(always(), eq("Europe/Kyiv"), eq("life"))
+            .returning(|_, _, _| Ok(QUOTE.clone()));
+
+        let params = DailyQuoteParams::parse(Some("Europe/Kyiv"), Some("Life")).unwrap();
+        let service = new_service(QuotesConfig::default(), (db, MockApi::new()));
+        let res = service.get_quote_of_the_day(&params).await;
+        assert!(res.is_ok());
+        assert_eq!(res.unwrap(), from_database_quote_to_quote(QUOTE.clone()));
+    }
+
+    #[tokio::test]
+    async fn test_get_quote_of_the_day_deterministic() {
+        let quotes: Vec<quote_model> = (0..10)
+            .map(|_| quote_model {
+                id: uuid::UUIDv4.fake(),
+                likes: (0..100).fake(),
+                ..QUOTE.clone()
+            })
+            .collect();
+        // unweighted pick of the seed, computed apart from the service
+        let pool = quotes.clone();
+        let expected_pick = move |day: NaiveDate| {
+            let digest = Sha256::digest(format!("{day}/UTC/"));
+            let seed = u64::from_be_bytes(digest[..8].try_into().unwrap());
+            let index = (seed as f64 / u64::MAX as f64 * pool.len() as f64) as usize;
+            pool[index.min(pool.len() - 1)].clone()
+        };
+        let stored: Arc<std::sync::Mutex<Option<NaiveDate>>> = Arc::default();
+
+        let mut db = MockDatabase::new();
+
+        let (stored_day, expected) = (stored.clone(), expected_pick.clone());
+        db.expect_get_daily_quote()
+            .with(always(), eq("UTC"), eq(""))
+            .times(3)
+            .returning(move |_, _, _| match *stored_day.lock().unwrap() {
+                Some(day) => Ok(expected(day)),
+                None => Err(anyhow!(DatabaseErrors::ErrNotFound)),
+            });
+
+        let candidates = quotes.clone();
+        db.expect_get_daily_candidates()
+            .with(eq(""))
+            .times(1)
+            .returning(move |_| Ok(candidates.clone()));
+
+        let (stored_day, expected) = (stored.clone(), expected_pick.clone());
+        db.expect_save_daily_quote()
+            .withf(move |daily_quote, overwrite| {
+                daily_quote.quote_id == expected(daily_quote.day).id
+                    && !daily_quote.overridden
+                    && !overwrite
+            })
+            .times(1)
+            .returning(move |daily_quote, _| {
+                *stored_day.lock().unwrap() = Some(daily_quote.day);
+                Ok(())
+            });
+
+        let service = new_service(QuotesConfig::default(), (db, MockApi::new()));
+
+        let picked = service
+            .get_quote_of_the_day(&DailyQuoteParams::default())
+            .await
+            .unwrap();
+        let day = stored
+            .lock()
+            .unwrap()
+            .expect("quote of the day is not stored");
+        assert_eq!(picked, from_database_quote_to_quote(expected_pick(day)));
+
+        // the stored quote is returned for the rest of the day without picking again
+        let again = service
+            .get_quote_of_the_day(&DailyQuoteParams::default())
+            .await
+            .unwrap();
+        assert_eq!(again, picked);
+    }
+
+    #[tokio::test]
+    async fn test_set_quote_of_the_day_success() {
+        let mut db = MockDatabase::new();
+
+        db.expect_get_quote()
+            .with(eq(QUOTE_ID.clone()))
+            .returning(|_| Ok(QUOTE.clone()));
+
+        db.expect_save_daily_quote()
+            .withf(|daily_quote, overwrite| {
+                daily_quote.overridden
+                    && *overwrite
+                    && daily_quote.quote_id == *QUOTE_ID
+                    && daily_quote.day.to_string() == "2026-10-19"
+                    && daily_quote.timezone == "UTC"
+            })
+            .returning(|_, _| Ok(()));
+
+        let daily_override =
+            DailyQuoteOverride::parse(Some("2026-10-19"), None, None, &QUOTE_ID).unwrap();
+        let service = new_service(QuotesConfig::default(), (db, MockApi::new()));
+        let res = service.set_quote_of_the_day(&daily_override).await;
+        assert!(res.is_ok());
+        assert_eq!(res.unwrap(), from_database_quote_to_quote(QUOTE.clone()));
+    }
+
+    #[tokio::test]
+    async fn test_report_quote_sends_quote_to_review() {
+        let mut db = MockDatabase::new();
+
+        db.expect_get_quote()
+            .with(eq(QUOTE_ID.clone()))
+            .returning(|_| Ok(QUOTE.clone()));
+
+        db.expect_report_quote()
+            .withf(|report| report.quote_id == *QUOTE_ID && report.reason == "offensive")
+            .times(2)
+            .returning({
+                let mut reports = 0;
+                move |_| {
+                    reports += 1;
+                    Ok(reports)
+                }
+            });
+
+        db.expect_set_quote_status()
+            .withf(|decision, resolve_reports| {
+                decision.status == ModerationStatus::Pending
+                    && decision.moderator == REPORTS_MODERATOR
+                    && decision.note == "2 open reports"
+                    && !*resolve_reports
+            })
+            .times(1)
+            .returning(|decision, _| Ok(new_audit_entry(decision)));
+
+        let service = new_service(
+            QuotesConfig {
+                moderation_report_threshold: 2,
+                ..QuotesConfig::default()
+            },
+            (db, MockApi::new()),
+        );
+
+        let report = QuoteReport::parse(&USER_ID, &QUOTE_ID, Some(" offensive ")).unwrap();
+        assert!(service.report_quote(&report).await.is_ok());
+        assert!(service.report_quote(&report).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_get_quote_skips_hidden_api_quote() {
+        let mut db = MockDatabase::new();
+
+        db.expect_get_quotes()
+            .with(eq(USER_ID.clone()))
+            .returning(|_| Ok(vec![QUOTE.clone()]));
+
+        db.expect_mark_as_viewed()
+            .with(eq(USER_ID.clone()), eq(QUOTE_ID.clone()))
+            .returning(|_, _| Ok(()));
+
+        let mut api = MockApi::new();
+
+        api.expect_get_random_quote().times(1).returning(|| {
+            Ok(quote_model {
+                id: uuid::UUIDv4.fake(),
+                status: ModerationStatus::Hidden.as_str().to_string(),
+                ..QUOTE.clone()
+            })
+        });
+
+        let service = new_service(
+            QuotesConfig {
+                random_quote_chance: 100.0,
+                ..QuotesConfig::default()
+            },
+            (db, api),
+        );
+
+        let res = service.get_quote(&USER_ID).await;
+        assert_eq!(res.unwrap(), from_database_quote_to_quote(QUOTE.clone()));
+    }
+
+    #[tokio::test]
+    async fn test_hidden_quote_by_id_not_found() {
+        let hidden = quote_model {
+            id: uuid::UUIDv4.fake(),
+            status: ModerationStatus::Hidden.as_str().to_string(),
+            ..QUOTE.clone()
+        };
+
+        let mut db = MockDatabase::new();
+
+        let quote = hidden.clone();
+        db.expect_get_quote()
+            .with(eq(hidden.id.clone()))
+            .returning(move |_| Ok(quote.clone()));
+
+        let quotes = vec![QUOTE.clone(), hidden.clone()];
+        db.expect_get_quotes_by_ids()
+            .returning(move |_| Ok(quotes.clone()));
+
+        let service = new_service(QuotesConfig::default(), (db, MockApi::new()));
+
+        let err = service.get_quote_by_id(&hidden.id).await.unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<DatabaseErrors>(),
+            Some(DatabaseErrors::ErrNotFound)
+        ));
+
+        let quotes = service
+            .get_quotes_by_ids(&[QUOTE_ID.clone(), hidden.id.clone()])
+            .await
+            .unwrap();
+        assert_eq!(quotes, vec![from_database_quote_to_quote(QUOTE.clone())]);
+    }
+
+    #[tokio::test]
+    async fn test_moderate_quote_resolves_reports() {
+        let mut db = MockDatabase::new();
+
+        db.expect_set_quote_status()
+            .withf(|decision, resolve_reports| {
+                decision.status == ModerationStatus::Hidden
+                    && decision.moderator == "admin"
+                    && *resolve_reports
+            })
+            .times(1)
+            .returning(|decision, _| Ok(new_audit_entry(decision)));
+
+        let service = new_service(QuotesConfig::default(), (db, MockApi::new()));
+
+        let decision =
+            ModerationDecision::parse(&QUOTE_ID, "hidden", None, Some("misattributed")).unwrap();
+        let entry = service.moderate_quote(&decision).await.unwrap();
+        assert_eq!(entry.previous_status, ModerationStatus::Approved);
+        assert_eq!(entry.status, ModerationStatus::Hidden);
+        assert_eq!(entry.note, "misattributed");
+
+        assert!(ModerationDecision::parse(&QUOTE_ID, "deleted", None, None).is_err());
+    }
+
+    fn new_audit_entry(decision: &ModerationDecision) -> audit_model {
+        audit_model {
+            id: uuid::UUIDv4.fake(),
+            quote_id: decision.quote_id.clone(),
+            previous_status: ModerationStatus::Approved.as_str().to_string(),
+            status: decision.status.as_str().to_string(),
+            moderator: decision.moderator.clone(),
+            note: decision.note.clone(),
+            created_at: chrono::Utc::now().fixed_offset(),
+        }
+    }
+
     fn new_service(cfg: QuotesConfig, mocks: (MockDatabase, MockApi)) -> Service {
-        Service::new(&cfg, Arc::new(mocks.0), Arc::new(mocks.1))
+        Service::new(&cfg, Arc::new(mocks.0), Arc::new(mocks.1), Bus::new())
     }
 }
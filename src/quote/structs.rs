@@ -1,14 +1,28 @@
+use anyhow::{anyhow, Result};
+use chrono::NaiveDate;
+use chrono_tz::Tz;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use utoipa::ToSchema;
 
+use crate::catalogue::structs::{DEFAULT_LIMIT, MAX_LIMIT};
+use crate::database::canonical::slugify;
+use crate::database::structs::moderation_audit::Model as ModerationAudit;
+use crate::database::structs::quote_reports::Model as QuoteReports;
 use crate::database::structs::quotes::Model as Quotes;
 
-#[derive(PartialEq, PartialOrd, Debug, Serialize, Deserialize, Default)]
+#[derive(Clone, PartialEq, PartialOrd, Debug, Serialize, Deserialize, Default, ToSchema)]
 pub struct Quote {
     pub id: String,
+    /// The quote itself
     pub quote: String,
     pub author: String,
     pub tags: Vec<String>,
     pub likes: i32,
+    /// The provider the quote came from, e.g. dummyjson or manual
+    pub source: String,
+    /// The ID of the quote at its provider
+    pub external_id: Option<String>,
 }
 
 pub fn from_database_quote_to_quote(quote: Quotes) -> Quote {
@@ -18,5 +32,371 @@ pub fn from_database_quote_to_quote(quote: Quotes) -> Quote {
         author: quote.author,
         tags: quote.tags,
         likes: quote.likes,
+        source: quote.source,
+        external_id: quote.external_id,
+    }
+}
+
+pub const DEFAULT_BATCH_COUNT: usize = 10;
+pub const MAX_BATCH_COUNT: usize = 50;
+
+/// Validates the number of quotes requested in one batch.
+pub fn parse_batch_count(count: Option<u64>) -> Result<usize> {
+    match count {
+        None => Ok(DEFAULT_BATCH_COUNT),
+        Some(count) if count == 0 || count > MAX_BATCH_COUNT as u64 => {
+            Err(anyhow!("count must be between 1 and {MAX_BATCH_COUNT}"))
+        }
+        Some(count) => Ok(count as usize),
+    }
+}
+
+/// Narrows the quotes down to an author and to the ones having at least one of the tags,
+/// both are kept as slugs.
+#[derive(PartialEq, Debug, Clone, Default)]
+pub struct QuoteFilter {
+    pub author: Option<String>,
+    pub tags: Vec<String>,
+}
+
+impl QuoteFilter {
+    pub fn new(author: Option<&str>, tags: &[String]) -> Self {
+        QuoteFilter {
+            author: author.map(slugify).filter(|author| !author.is_empty()),
+            tags: tags
+                .iter()
+                .map(|tag| slugify(tag))
+                .filter(|tag| !tag.is_empty())
+                .collect(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.author.is_none() && self.tags.is_empty()
+    }
+
+    pub fn matches(&self, quote: &Quotes) -> bool {
+        self.author
+            .as_ref()
+            .is_none_or(|author| slugify(&quote.author).eq(author))
+            && (self.tags.is_empty() || quote.tags.iter().any(|tag| self.tags.contains(tag)))
+    }
+}
+
+#[derive(PartialEq, Debug, Clone, Default)]
+pub struct DailyQuoteParams {
+    pub timezone: Option<Tz>,
+    pub tag: Option<String>,
+}
+
+impl DailyQuoteParams {
+    pub fn parse(timezone: Option<&str>, tag: Option<&str>) -> Result<Self> {
+        let timezone = match timezone.filter(|timezone| !timezone.is_empty()) {
+            Some(timezone) => Some(
+                timezone
+                    .parse::<Tz>()
+                    .map_err(|_| anyhow!("unknown timezone: {timezone}"))?,
+            ),
+            None => None,
+        };
+
+        Ok(DailyQuoteParams {
+            timezone,
+            tag: tag.map(slugify).filter(|tag| !tag.is_empty()),
+        })
+    }
+}
+
+#[derive(PartialEq, Debug, Clone)]
+pub struct DailyQuoteOverride {
+    pub date: Option<NaiveDate>,
+    pub params: DailyQuoteParams,
+    pub quote_id: String,
+}
+
+impl DailyQuoteOverride {
+    pub fn parse(
+        date: Option<&str>,
+        timezone: Option<&str>,
+        tag: Option<&str>,
+        quote_id: &str,
+    ) -> Result<Self> {
+        let date = match date.filter(|date| !date.is_empty()) {
+            Some(date) => Some(
+                date.parse::<NaiveDate>()
+                    .map_err(|_| anyhow!("date must be in the YYYY-MM-DD format: {date}"))?,
+            ),
+            None => None,
+        };
+
+        if quote_id.is_empty() {
+            return Err(anyhow!("quote id must not be empty"));
+        }
+
+        Ok(DailyQuoteOverride {
+            date,
+            params: DailyQuoteParams::parse(timezone, tag)?,
+            quote_id: quote_id.to_string(),
+        })
+    }
+}
+
+/// Moderation state of a quote, only the approved quotes are picked for the users.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ModerationStatus {
+    /// Reported by enough users, waiting for a review
+    Pending,
+    Approved,
+    Hidden,
+}
+
+impl ModerationStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ModerationStatus::Pending => "pending",
+            ModerationStatus::Approved => "approved",
+            ModerationStatus::Hidden => "hidden",
+        }
+    }
+
+    pub fn parse(status: &str) -> Result<Self> {
+        match status {
+            "pending" => Ok(ModerationStatus::Pending),
+            "approved" => Ok(ModerationStatus::Approved),
+            "hidden" => Ok(ModerationStatus::Hidden),
+            _ => Err(anyhow!(
+                "status must be pending, approved or hidden: {status}"
+            )),
+        }
+    }
+}
+
+pub const MAX_REPORT_REASON_LENGTH: usize = 500;
+pub const DEFAULT_MODERATOR: &str = "admin";
+
+#[derive(PartialEq, Debug, Clone)]
+pub struct QuoteReport {
+    pub user_id: String,
+    pub quote_id: String,
+    pub reason: String,
+}
+
+impl QuoteReport {
+    pub fn parse(user_id: &str, quote_id: &str, reason: Option<&str>) -> Result<Self> {
+        if user_id.is_empty() {
+            return Err(anyhow!("user id must not be empty"));
+        }
+        if quote_id.is_empty() {
+            return Err(anyhow!("quote id must not be empty"));
+        }
+
+        let reason = reason.unwrap_or_default().trim();
+        if reason.chars().count() > MAX_REPORT_REASON_LENGTH {
+            return Err(anyhow!(
+                "reason must be at most {MAX_REPORT_REASON_LENGTH} characters"
+            ));
+        }
+
+        Ok(QuoteReport {
+            user_id: user_id.to_string(),
+            quote_id: quote_id.to_string(),
+            reason: reason.to_string(),
+        })
+    }
+}
+
+#[derive(PartialEq, Debug, Clone)]
+pub struct ModerationDecision {
+    pub quote_id: String,
+    pub status: ModerationStatus,
+    pub moderator: String,
+    pub note: String,
+}
+
+impl ModerationDecision {
+    pub fn parse(
+        quote_id: &str,
+        status: &str,
+        moderator: Option<&str>,
+        note: Option<&str>,
+    ) -> Result<Self> {
+        if quote_id.is_empty() {
+            return Err(anyhow!("quote id must not be empty"));
+        }
+
+        Ok(ModerationDecision {
+            quote_id: quote_id.to_string(),
+            status: ModerationStatus::parse(status)?,
+            moderator: moderator
+                .map(str::trim)
+                .filter(|moderator| !moderator.is_empty())
+                .unwrap_or(DEFAULT_MODERATOR)
+                .to_string(),
+            note: note.unwrap_or_default().trim().to_string(),
+        })
+    }
+}
+
+/// Page of the review queue.
+#[derive(PartialEq, Debug, Clone)]
+pub struct ReviewPage {
+    pub limit: u64,
+    pub offset: u64,
+}
+
+impl ReviewPage {
+    /// Zero or missing limit means the default one, as for the catalogue lists.
+    pub fn parse(limit: Option<u64>, offset: Option<u64>) -> Result<Self> {
+        let limit = match limit.filter(|limit| *limit != 0) {
+            Some(limit) if limit > MAX_LIMIT => {
+                return Err(anyhow!("limit must not be greater than {MAX_LIMIT}"))
+            }
+            Some(limit) => limit,
+            None => DEFAULT_LIMIT,
+        };
+
+        Ok(ReviewPage {
+            limit,
+            offset: offset.unwrap_or_default(),
+        })
+    }
+}
+
+/// Open report of a quote.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize, ToSchema)]
+pub struct Report {
+    pub user_id: String,
+    pub reason: String,
+    /// RFC 3339 time of the report
+    pub created_at: String,
+}
+
+/// Counters of the service since it started.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize, ToSchema)]
+pub struct Stats {
+    /// Quotes of the site or the corpus rejected by the validation
+    pub rejected_quotes: u64,
+}
+
+/// Quote waiting for a review with its open reports, newest first.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize, ToSchema)]
+pub struct ReviewItem {
+    pub quote: Quote,
+    pub status: ModerationStatus,
+    pub reports: Vec<Report>,
+}
+
+/// Change of the moderation status of a quote.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize, ToSchema)]
+pub struct AuditEntry {
+    pub id: String,
+    pub quote_id: String,
+    pub previous_status: ModerationStatus,
+    pub status: ModerationStatus,
+    /// Name given by the admin, `reports` when enough reports sent the quote to review
+    pub moderator: String,
+    pub note: String,
+    /// RFC 3339 time of the change
+    pub created_at: String,
+}
+
+pub fn from_database_report(report: QuoteReports) -> Report {
+    Report {
+        user_id: report.user_id,
+        reason: report.reason,
+        created_at: report.created_at.to_rfc3339(),
+    }
+}
+
+pub fn from_database_audit(entry: ModerationAudit) -> Result<AuditEntry> {
+    Ok(AuditEntry {
+        previous_status: ModerationStatus::parse(&entry.previous_status)?,
+        status: ModerationStatus::parse(&entry.status)?,
+        id: entry.id,
+        quote_id: entry.quote_id,
+        moderator: entry.moderator,
+        note: entry.note,
+        created_at: entry.created_at.to_rfc3339(),
+    })
+}
+
+/// Reaction of a user to a viewed quote, besides the like.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Feedback {
+    Dislike,
+    Skip,
+}
+
+impl Feedback {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Feedback::Dislike => "dislike",
+            Feedback::Skip => "skip",
+        }
+    }
+
+    pub fn parse(feedback: &str) -> Result<Self> {
+        match feedback {
+            "dislike" => Ok(Feedback::Dislike),
+            "skip" => Ok(Feedback::Skip),
+            _ => Err(anyhow!("feedback must be dislike or skip: {feedback}")),
+        }
+    }
+}
+
+/// Penalties of the authors and tags a user disliked or skipped, keyed by their slugs.
+#[derive(Debug, Default)]
+pub struct FeedbackProfile {
+    authors: HashMap<String, f64>,
+    tags: HashMap<String, f64>,
+}
+
+impl FeedbackProfile {
+    /// Sums the penalty of every quote the user gave feedback on, unknown feedback is ignored.
+    pub fn new(feedback: &[(String, Quotes)], dislike_penalty: f64, skip_penalty: f64) -> Self {
+        let mut profile = FeedbackProfile::default();
+        for (feedback, quote) in feedback {
+            let penalty = match Feedback::parse(feedback) {
+                Ok(Feedback::Dislike) => dislike_penalty,
+                Ok(Feedback::Skip) => skip_penalty,
+                Err(_) => continue,
+            };
+            if penalty <= 0.0 {
+                continue;
+            }
+
+            *profile.authors.entry(slugify(&quote.author)).or_default() += penalty;
+            for tag in &quote.tags {
+                *profile.tags.entry(slugify(tag)).or_default() += penalty;
+            }
+        }
+
+        profile
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.authors.is_empty() && self.tags.is_empty()
+    }
+
+    /// Multiplier of the weight of the quote, one when the user has no feedback on its
+    /// author or tags and closer to zero the more they disliked or skipped them.
+    pub fn factor(&self, quote: &Quotes) -> f64 {
+        if self.is_empty() {
+            return 1.0;
+        }
+
+        let author = self
+            .authors
+            .get(&slugify(&quote.author))
+            .copied()
+            .unwrap_or_default();
+        let tags: f64 = quote
+            .tags
+            .iter()
+            .filter_map(|tag| self.tags.get(&slugify(tag)))
+            .sum();
+
+        1.0 / (1.0 + author + tags)
     }
 }
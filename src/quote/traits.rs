@@ -1,9 +1,15 @@
 use anyhow::Result;
 use async_trait::async_trait;
+use chrono::NaiveDate;
 
+use crate::database::structs::daily_quotes::Model as DailyQuotes;
+use crate::database::structs::moderation_audit::Model as ModerationAudit;
+use crate::database::structs::quote_reports::Model as QuoteReports;
 use crate::database::structs::quotes::Model as Quotes;
 use crate::database::structs::views::Model as Views;
 
+use super::structs::{Feedback, ModerationDecision, QuoteFilter, QuoteReport};
+
 #[cfg(test)]
 use mockall::automock;
 
@@ -13,14 +19,63 @@ pub trait Database {
     async fn get_quote(&self, quote_id: &str) -> Result<Quotes>;
     async fn get_quotes(&self, user_id: &str) -> Result<Vec<Quotes>>;
     async fn get_same_quote(&self, user_id: &str, viewed_quote: &Quotes) -> Result<Quotes>;
+    async fn get_same_quotes(
+        &self,
+        user_id: &str,
+        viewed_quote: &Quotes,
+        limit: u64,
+    ) -> Result<Vec<Quotes>>;
+    async fn find_quotes(
+        &self,
+        filter: &QuoteFilter,
+        limit: u64,
+        offset: u64,
+    ) -> Result<Vec<Quotes>>;
     async fn get_view(&self, user_id: &str, quote_id: &str) -> Result<Views>;
+    async fn get_quotes_by_ids(&self, quote_ids: &[String]) -> Result<Vec<Quotes>>;
+    async fn get_views_by_keys(&self, keys: &[(String, String)]) -> Result<Vec<Views>>;
+    async fn count_views(&self, quote_ids: &[String]) -> Result<Vec<(String, i64)>>;
+    async fn get_viewed_quote_ids(
+        &self,
+        user_id: &str,
+        limit: u64,
+        offset: u64,
+    ) -> Result<Vec<String>>;
     async fn mark_as_viewed(&self, user_id: &str, quote_id: &str) -> Result<()>;
+    async fn mark_as_viewed_many(&self, user_id: &str, quote_ids: &[String]) -> Result<()>;
     async fn mark_as_liked(&self, user_id: &str, quote_id: &str) -> Result<()>;
-    async fn like_quote(&self, quote_id: &str) -> Result<()>;
+    /// Records the feedback on a viewed quote, replacing the previous one.
+    async fn set_feedback(&self, user_id: &str, quote_id: &str, feedback: Feedback) -> Result<()>;
+    /// Feedback of the user along with the quotes it was given on.
+    async fn get_feedback(&self, user_id: &str) -> Result<Vec<(String, Quotes)>>;
+    async fn like_quote(&self, quote_id: &str) -> Result<Quotes>;
+    async fn get_daily_quote(&self, day: NaiveDate, timezone: &str, tag: &str) -> Result<Quotes>;
+    async fn get_daily_candidates(&self, tag: &str) -> Result<Vec<Quotes>>;
+    async fn save_daily_quote(&self, daily_quote: DailyQuotes, overwrite: bool) -> Result<()>;
+    /// Saves the report, a user reporting a quote again replaces their report.
+    /// Returns the number of open reports of the quote.
+    async fn report_quote(&self, report: &QuoteReport) -> Result<u64>;
+    /// Changes the status of the quote and records the change, the open reports are
+    /// resolved along with it when asked to.
+    async fn set_quote_status(
+        &self,
+        decision: &ModerationDecision,
+        resolve_reports: bool,
+    ) -> Result<ModerationAudit>;
+    /// Pending quotes and quotes with open reports, most reported first, along with
+    /// their open reports.
+    async fn get_review_queue(
+        &self,
+        limit: u64,
+        offset: u64,
+    ) -> Result<Vec<(Quotes, Vec<QuoteReports>)>>;
+    async fn get_moderation_audit(&self, quote_id: &str) -> Result<Vec<ModerationAudit>>;
 }
 
 #[cfg_attr(test, automock)]
 #[async_trait]
 pub trait Api {
     async fn get_random_quote(&self) -> Result<Quotes>;
+    /// Quotes of the providers rejected by the validation since the start.
+    fn rejected_quotes(&self) -> u64;
 }
@@ -0,0 +1,430 @@
+use anyhow::{bail, Context, Result};
+use rand::Rng;
+use reqwest::{Proxy, StatusCode};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::config::QuoteApiConfig;
+
+const DEFAULT_USER_AGENT: &str = concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"));
+
+/// HTTP client of the quote site: failed fetches are retried with a jittered exponential
+/// backoff and the site is skipped altogether while it keeps failing.
+pub struct Client {
+    http: reqwest::Client,
+    retries: u32,
+    backoff: Duration,
+    /// Zero doesn't limit the responses.
+    max_body_bytes: usize,
+    breaker: CircuitBreaker,
+}
+
+impl Client {
+    /// Body of a `GET` request, fails right away while the circuit is open.
+    pub async fn get(&self, url: &str) -> Result<String> {
+        let Some(call) = self.breaker.allows() else {
+            bail!("quote api is failing, circuit is open");
+        };
+
+        let res = self.get_with_retries(url).await;
+        call.record(res.is_ok());
+        res
+    }
+
+    async fn get_with_retries(&self, url: &str) -> Result<String> {
+        let mut attempt = 0;
+        loop {
+            match self.try_get(url).await {
+                Ok(body) => return Ok(body),
+                Err(err) if err.retryable && attempt < self.retries => {
+                    let delay = self.backoff(attempt);
+                    log::warn!(
+                        "failed to get {url}, retrying in {delay:?}: {:#}",
+                        err.inner
+                    );
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                }
+                Err(err) => return Err(err.inner),
+            }
+        }
+    }
+
+    async fn try_get(&self, url: &str) -> Result<String, FetchError> {
+        let mut resp = self
+            .http
+            .get(url)
+            .send()
+            .await
+            .map_err(FetchError::transport)?;
+
+        let status = resp.status();
+        if !status.is_success() {
+            return Err(FetchError {
+                retryable: status.is_server_error() || status == StatusCode::TOO_MANY_REQUESTS,
+                inner: anyhow::anyhow!("quote api answered with {status}"),
+            });
+        }
+
+        // the body is read in chunks so a huge response is dropped before it's buffered
+        let mut body = Vec::new();
+        while let Some(chunk) = resp.chunk().await.map_err(FetchError::transport)? {
+            if self.max_body_bytes > 0 && body.len() + chunk.len() > self.max_body_bytes {
+                return Err(FetchError {
+                    retryable: false,
+                    inner: anyhow::anyhow!(
+                        "quote api answered with more than {} bytes",
+                        self.max_body_bytes
+                    ),
+                });
+            }
+            body.extend_from_slice(&chunk);
+        }
+
+        String::from_utf8(body).map_err(|err| FetchError {
+            retryable: false,
+            inner: anyhow::Error::new(err).context("failed to decode response"),
+        })
+    }
+
+    /// Full jitter: a random delay up to the doubled backoff of the previous attempt.
+    fn backoff(&self, attempt: u32) -> Duration {
+        let max = self.backoff.saturating_mul(2u32.saturating_pow(attempt));
+        if max.is_zero() {
+            return max;
+        }
+
+        rand::rng().random_range(Duration::ZERO..=max)
+    }
+
+    pub fn new(cfg: &QuoteApiConfig) -> Result<Self> {
+        let user_agent = match cfg.user_agent.as_str() {
+            "" => DEFAULT_USER_AGENT,
+            user_agent => user_agent,
+        };
+
+        let mut builder = reqwest::Client::builder()
+            .connect_timeout(Duration::from_millis(cfg.connect_timeout_ms))
+            .read_timeout(Duration::from_millis(cfg.read_timeout_ms))
+            .user_agent(user_agent);
+        if !cfg.proxy.is_empty() {
+            builder = builder.proxy(Proxy::all(&cfg.proxy).context("failed to parse proxy")?);
+        }
+
+        Ok(Client {
+            http: builder.build().context("failed to build http client")?,
+            retries: cfg.retries,
+            backoff: Duration::from_millis(cfg.retry_backoff_ms),
+            max_body_bytes: cfg.max_body_bytes,
+            breaker: CircuitBreaker::new(
+                cfg.failure_threshold,
+                Duration::from_secs(cfg.open_circuit_secs),
+            ),
+        })
+    }
+}
+
+struct FetchError {
+    retryable: bool,
+    inner: anyhow::Error,
+}
+
+impl FetchError {
+    /// Connection failures and timeouts are worth another try, the other errors are not.
+    fn transport(err: reqwest::Error) -> Self {
+        FetchError {
+            retryable: err.is_connect() || err.is_timeout() || err.is_request(),
+            inner: anyhow::Error::new(err).context("failed to receive response"),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum State {
+    Closed {
+        failures: u32,
+    },
+    Open {
+        until: Instant,
+    },
+    /// The cooldown is over, a single trial request decides whether the circuit closes.
+    HalfOpen,
+}
+
+/// Stops calling the site after `threshold` failed fetches in a row, for `cooldown`.
+/// A zero threshold never opens the circuit.
+struct CircuitBreaker {
+    threshold: u32,
+    cooldown: Duration,
+    state: Mutex<State>,
+}
+
+impl CircuitBreaker {
+    /// The call to record the outcome with, none while the circuit is open.
+    fn allows(&self) -> Option<Call<'_>> {
+        let mut state = self.state.lock().unwrap_or_else(|err| err.into_inner());
+        let trial = match *state {
+            State::Closed { .. } => false,
+            State::Open { until } if Instant::now() >= until => {
+                *state = State::HalfOpen;
+                true
+            }
+            State::Open { .. } | State::HalfOpen => return None,
+        };
+
+        Some(Call {
+            breaker: self,
+            trial,
+            recorded: false,
+        })
+    }
+
+    fn record(&self, success: bool) {
+        let mut state = self.state.lock().unwrap_or_else(|err| err.into_inner());
+        if success {
+            *state = State::Closed { failures: 0 };
+            return;
+        }
+
+        // a failed trial opens the circuit again
+        let failures = match *state {
+            State::Closed { failures } => failures + 1,
+            State::Open { .. } | State::HalfOpen => self.threshold,
+        };
+
+        if self.threshold > 0 && failures >= self.threshold {
+            log::warn!(
+                "quote api failed {failures} times, skipping it for {:?}",
+                self.cooldown
+            );
+            *state = State::Open {
+                until: Instant::now() + self.cooldown,
+            };
+        } else {
+            *state = State::Closed { failures };
+        }
+    }
+
+    fn new(threshold: u32, cooldown: Duration) -> Self {
+        CircuitBreaker {
+            threshold,
+            cooldown,
+            state: Mutex::new(State::Closed { failures: 0 }),
+        }
+    }
+}
+
+/// Call let through by the breaker, a trial dropped before its outcome is recorded
+/// counts as failed so the circuit doesn't stay half open.
+struct Call<'a> {
+    breaker: &'a CircuitBreaker,
+    trial: bool,
+    recorded: bool,
+}
+
+impl Call<'_> {
+    fn record(mut self, success: bool) {
+        self.recorded = true;
+        self.breaker.record(success);
+    }
+}
+
+impl Drop for Call<'_> {
+    fn drop(&mut self) {
+        if self.trial && !self.recorded {
+            self.breaker.record(false);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mockito::Server;
+
+    use super::*;
+
+    fn new_client(retries: u32, failure_threshold: u32) -> Client {
+        Client::new(&QuoteApiConfig {
+            connect_timeout_ms: 500,
+            read_timeout_ms: 200,
+            retries,
+            retry_backoff_ms: 1,
+            failure_threshold,
+            open_circuit_secs: 60,
+            max_body_bytes: 1024,
+            user_agent: "quotes-test".to_string(),
+            ..QuoteApiConfig::default()
+        })
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_retries_server_errors() {
+        let mut server = Server::new_async().await;
+        let failing = server
+            .mock("GET", "/")
+            .with_status(503)
+            .expect(2)
+            .create_async()
+            .await;
+        let working = server
+            .mock("GET", "/")
+            .with_body("quote")
+            .create_async()
+            .await;
+
+        let client = new_client(2, 5);
+        assert_eq!(client.get(&server.url()).await.unwrap(), "quote");
+        failing.assert_async().await;
+        working.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_user_agent() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("GET", "/")
+            .match_header("user-agent", "quotes-test")
+            .with_body("quote")
+            .create_async()
+            .await;
+
+        let client = new_client(0, 5);
+        assert_eq!(client.get(&server.url()).await.unwrap(), "quote");
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_client_errors_are_not_retried() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("GET", "/")
+            .with_status(404)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let client = new_client(3, 5);
+        assert!(client.get(&server.url()).await.is_err());
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_huge_body_is_refused() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("GET", "/")
+            .with_body("q".repeat(2048))
+            .expect(1)
+            .create_async()
+            .await;
+
+        let client = new_client(2, 5);
+        let err = client.get(&server.url()).await.unwrap_err();
+        assert!(err.to_string().contains("more than 1024 bytes"));
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_read_timeout() {
+        // accepts connections but never answers
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let accepted = tokio::spawn(async move {
+            let mut streams = vec![];
+            while let Ok((stream, _)) = listener.accept().await {
+                streams.push(stream);
+            }
+        });
+
+        let client = new_client(1, 5);
+        let started = Instant::now();
+        let err = client.get(&url).await.unwrap_err();
+        assert!(format!("{err:#}").contains("failed to receive response"));
+        // two attempts of 200ms each
+        assert!(started.elapsed() < Duration::from_millis(900));
+        accepted.abort();
+    }
+
+    #[tokio::test]
+    async fn test_circuit_opens_after_failures() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("GET", "/")
+            .with_status(500)
+            .expect(2)
+            .create_async()
+            .await;
+
+        let client = new_client(0, 2);
+        for _ in 0..3 {
+            assert!(client.get(&server.url()).await.is_err());
+        }
+        // the third fetch is short-circuited
+        mock.assert_async().await;
+    }
+
+    #[test]
+    fn test_circuit_half_open() {
+        let breaker = CircuitBreaker::new(1, Duration::ZERO);
+        breaker.record(false);
+        assert!(matches!(*breaker.state.lock().unwrap(), State::Open { .. }));
+
+        // the cooldown is over, only one trial goes through
+        let trial = breaker.allows().unwrap();
+        assert!(breaker.allows().is_none());
+        trial.record(true);
+        assert_eq!(
+            *breaker.state.lock().unwrap(),
+            State::Closed { failures: 0 }
+        );
+    }
+
+    #[test]
+    fn test_dropped_trial_reopens_circuit() {
+        let breaker = CircuitBreaker::new(1, Duration::from_secs(60));
+        *breaker.state.lock().unwrap() = State::Open {
+            until: Instant::now(),
+        };
+
+        drop(breaker.allows().unwrap());
+        assert!(matches!(*breaker.state.lock().unwrap(), State::Open { .. }));
+
+        // calls of a closed circuit don't count when dropped
+        *breaker.state.lock().unwrap() = State::Closed { failures: 0 };
+        drop(breaker.allows().unwrap());
+        assert_eq!(
+            *breaker.state.lock().unwrap(),
+            State::Closed { failures: 0 }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_cancelled_trial() {
+        // accepts connections but never answers
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let accepted = tokio::spawn(async move {
+            let mut streams = vec![];
+            while let Ok((stream, _)) = listener.accept().await {
+                streams.push(stream);
+            }
+        });
+
+        let client = Client::new(&QuoteApiConfig {
+            read_timeout_ms: 200,
+            failure_threshold: 1,
+            open_circuit_secs: 0,
+            ..QuoteApiConfig::default()
+        })
+        .unwrap();
+        assert!(client.get(&url).await.is_err());
+
+        // the trial is dropped mid-flight, the next fetch is a new trial
+        let cancelled = tokio::time::timeout(Duration::from_millis(50), client.get(&url)).await;
+        assert!(cancelled.is_err());
+        let err = client.get(&url).await.unwrap_err();
+        assert!(format!("{err:#}").contains("failed to receive response"));
+        accepted.abort();
+    }
+}
@@ -0,0 +1,134 @@
+use anyhow::{bail, Context, Result};
+use async_trait::async_trait;
+use rand::seq::IndexedRandom;
+use std::path::Path;
+
+use crate::database::structs::quotes::Model as Quotes;
+use crate::quote_api::structs::{self, CorpusQuote, CsvQuote};
+use crate::quote_api::traits::Provider;
+
+const BUNDLED: &str = include_str!("corpus.json");
+
+/// Quotes of a local file, drawn from when the site can't be reached and used to seed
+/// an empty database.
+pub struct Corpus {
+    quotes: Vec<CorpusQuote>,
+}
+
+impl Corpus {
+    pub fn quotes(&self) -> impl Iterator<Item = Quotes> + '_ {
+        self.quotes.iter().cloned().map(structs::from_corpus)
+    }
+
+    /// Reads a JSON array or a CSV file with a `quote,author,tags` header depending on the
+    /// extension, the bundled corpus is used when the path is empty.
+    pub fn load(path: &str) -> Result<Self> {
+        if path.is_empty() {
+            return Self::from_json(BUNDLED).context("failed to parse bundled corpus");
+        }
+
+        let data =
+            std::fs::read_to_string(path).with_context(|| format!("failed to read {path}"))?;
+        let is_csv = Path::new(path)
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("csv"));
+
+        if is_csv {
+            Self::from_csv(&data)
+        } else {
+            Self::from_json(&data)
+        }
+        .with_context(|| format!("failed to parse {path}"))
+    }
+
+    fn from_json(data: &str) -> Result<Self> {
+        Self::from_quotes(serde_json::from_str(data).context("failed to deserialize quotes")?)
+    }
+
+    fn from_csv(data: &str) -> Result<Self> {
+        let quotes = csv::Reader::from_reader(data.as_bytes())
+            .deserialize::<CsvQuote>()
+            .map(|row| row.map(CorpusQuote::from))
+            .collect::<Result<Vec<_>, _>>()
+            .context("failed to deserialize quotes")?;
+
+        Self::from_quotes(quotes)
+    }
+
+    fn from_quotes(quotes: Vec<CorpusQuote>) -> Result<Self> {
+        if quotes.is_empty() {
+            bail!("corpus has no quotes");
+        }
+
+        Ok(Corpus { quotes })
+    }
+}
+
+#[async_trait]
+impl Provider for Corpus {
+    fn name(&self) -> &str {
+        structs::CORPUS_SOURCE
+    }
+
+    async fn fetch(&self) -> Result<Quotes> {
+        let quote = self
+            .quotes
+            .choose(&mut rand::rng())
+            .context("corpus has no quotes")?;
+
+        Ok(structs::from_corpus(quote.clone()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bundled_corpus() {
+        let corpus = Corpus::load("").unwrap();
+        assert!(corpus.quotes().all(|quote| {
+            !quote.quote.is_empty()
+                && !quote.author.is_empty()
+                && quote.source == structs::CORPUS_SOURCE
+                && quote.external_id.is_some()
+        }));
+    }
+
+    #[test]
+    fn test_csv_corpus() {
+        let corpus = Corpus::from_csv(
+            "quote,author,tags\n\
+             \"Well done is better than well said.\",Benjamin Franklin,action; wisdom\n\
+             \"Be yourself, everyone else is already taken.\",Oscar Wilde,\n",
+        )
+        .unwrap();
+
+        let quotes: Vec<Quotes> = corpus.quotes().collect();
+        assert_eq!(quotes.len(), 2);
+        assert_eq!(quotes[0].author, "Benjamin Franklin");
+        assert_eq!(quotes[0].tags, vec!["action", "wisdom"]);
+        assert_eq!(
+            quotes[1].quote,
+            "Be yourself, everyone else is already taken."
+        );
+        assert!(quotes[1].tags.is_empty());
+    }
+
+    #[test]
+    fn test_empty_corpus() {
+        assert!(Corpus::from_json("[]").is_err());
+        assert!(Corpus::from_csv("quote,author,tags\n").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_fetch_keeps_external_id() {
+        let corpus =
+            Corpus::from_json(r#"[{"quote": "Know thyself.", "author": "Thales"}]"#).unwrap();
+
+        let first = corpus.fetch().await.unwrap();
+        let second = corpus.fetch().await.unwrap();
+        assert_ne!(first.id, second.id);
+        assert_eq!(first.external_id, second.external_id);
+    }
+}
@@ -0,0 +1,43 @@
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+
+use crate::config::QuoteApiConfig;
+use crate::database::structs::quotes::Model as Quotes;
+use crate::quote_api::client::Client;
+use crate::quote_api::structs;
+use crate::quote_api::traits::Provider;
+
+/// Random quotes of the dummyjson site.
+pub struct DummyJson {
+    client: Client,
+    url: String,
+}
+
+impl DummyJson {
+    pub fn new(cfg: &QuoteApiConfig) -> Result<Self> {
+        Ok(DummyJson {
+            client: Client::new(cfg).context("failed to create quote api client")?,
+            url: cfg.url.clone(),
+        })
+    }
+}
+
+#[async_trait]
+impl Provider for DummyJson {
+    fn name(&self) -> &str {
+        structs::SOURCE
+    }
+
+    async fn fetch(&self) -> Result<Quotes> {
+        let data = self
+            .client
+            .get(&self.url)
+            .await
+            .context("failed to receive random quote from site")?;
+
+        let quote: structs::Quote =
+            serde_json::from_str(&data).context("failed to deserialize random quote")?;
+
+        Ok(structs::to_database(quote))
+    }
+}
@@ -1,53 +1,158 @@
+mod client;
+mod corpus;
+mod dummyjson;
+mod prefetch;
 mod structs;
 pub mod traits;
+mod validation;
 
-use anyhow::{Context, Result};
+use anyhow::{anyhow, Context, Result};
 use async_trait::async_trait;
 use std::sync::Arc;
 
+use crate::config::QuoteApiConfig;
+use crate::events::{Bus, Event};
 pub use crate::quote_api::traits::Database;
+use crate::quote_api::traits::Provider;
 use crate::{database::structs::quotes::Model as Quotes, quote};
 
-const RANDOM_QUOTE_URL: &str = "https://dummyjson.com/quotes/random";
+use corpus::Corpus;
+use dummyjson::DummyJson;
+use prefetch::Prefetcher;
+use validation::{Validated, Validator};
 
 pub struct Service {
     db: Arc<dyn Database + Send + Sync>,
-    client: reqwest::Client,
-    quote_url: String,
+    corpus: Arc<Corpus>,
+    providers: Vec<Arc<dyn Provider + Send + Sync>>,
+    validator: Arc<Validator>,
+    prefetcher: Arc<Prefetcher>,
+    events: Bus,
 }
 
 impl Service {
+    /// Takes a prefetched quote, the providers are asked while the caller waits only when none is left.
     pub async fn get_random_quote(&self) -> Result<Quotes> {
-        let resp = self
-            .client
-            .get(&self.quote_url)
-            .send()
-            .await
-            .context("failed to receive random quote from site")?;
+        let quote = match self.prefetcher.take() {
+            Some(quote) => quote,
+            None => self
+                .fetch()
+                .await
+                .context("failed to receive random quote from site")?,
+        };
 
-        let data = resp
-            .text()
+        let id = quote.id.clone();
+        let saved = self
+            .db
+            .save_quote(quote)
             .await
-            .context("failed to receive response text")?;
+            .context("failed to save new random quote")?;
+
+        // Known quotes come back with the id they were stored with before.
+        if saved.id == id {
+            self.events.publish(Event::NewQuoteAdded(
+                quote::structs::from_database_quote_to_quote(saved.clone()),
+            ));
+        }
 
-        let quote: structs::Quote =
-            serde_json::from_str(&data).context("failed to deserialize random quote")?;
+        Ok(saved)
+    }
 
-        let quote = structs::to_database(quote);
-        self.db
-            .save_quote(quote.to_owned())
+    /// Saves the corpus quotes when the database has no quotes yet, returns how many were saved.
+    pub async fn seed(&self) -> Result<usize> {
+        let count = self
+            .db
+            .count_quotes()
             .await
-            .context("failed to save new random quote")?;
+            .context("failed to count quotes")?;
+        if count > 0 {
+            return Ok(0);
+        }
 
-        Ok(quote)
+        // the corpus may be any file, its quotes are held to the same rules as the site's
+        let mut seeded = 0;
+        for quote in self.corpus.quotes() {
+            let Some(quote) = self.validator.validate(quote, self.corpus.name()) else {
+                continue;
+            };
+            self.db
+                .save_quote(quote)
+                .await
+                .context("failed to save seed quote")?;
+            seeded += 1;
+        }
+
+        Ok(seeded)
     }
 
-    pub fn new(db: Arc<dyn Database + Send + Sync>) -> Self {
-        Service {
-            db,
-            client: reqwest::Client::new(),
-            quote_url: RANDOM_QUOTE_URL.to_string(),
+    /// Starts refilling the prefetched quotes in the background.
+    pub fn spawn_prefetch(&self) {
+        self.prefetcher.clone().spawn();
+    }
+
+    /// First quote fetched by the providers in order.
+    async fn fetch(&self) -> Result<Quotes> {
+        let mut last_err = anyhow!("no quote providers");
+        for provider in &self.providers {
+            match provider.fetch().await {
+                Ok(quote) => return Ok(quote),
+                Err(err) => last_err = err.context(format!("provider {}", provider.name())),
+            }
         }
+
+        Err(last_err)
+    }
+
+    pub fn new(
+        cfg: &QuoteApiConfig,
+        db: Arc<dyn Database + Send + Sync>,
+        events: Bus,
+    ) -> Result<Self> {
+        let corpus = Arc::new(Corpus::load(&cfg.corpus_path).context("failed to load corpus")?);
+
+        // Offline the quotes are drawn from the corpus instead of the site.
+        let providers: Vec<Arc<dyn Provider + Send + Sync>> = if cfg.offline {
+            vec![corpus.clone()]
+        } else {
+            let dummyjson = DummyJson::new(cfg).context("failed to create dummyjson provider")?;
+            vec![Arc::new(dummyjson)]
+        };
+
+        // Every quote received from a provider is sanitized before it's stored.
+        let validator = Arc::new(Validator::new(cfg).context("failed to create quote validator")?);
+        let providers = providers
+            .into_iter()
+            .map(|provider| -> Arc<dyn Provider + Send + Sync> {
+                Arc::new(Validated::new(
+                    provider,
+                    validator.clone(),
+                    cfg.validation_attempts,
+                ))
+            })
+            .collect();
+
+        Self::from_providers(cfg, db, corpus, providers, validator, events)
+    }
+
+    pub fn from_providers(
+        cfg: &QuoteApiConfig,
+        db: Arc<dyn Database + Send + Sync>,
+        corpus: Arc<Corpus>,
+        providers: Vec<Arc<dyn Provider + Send + Sync>>,
+        validator: Arc<Validator>,
+        events: Bus,
+    ) -> Result<Self> {
+        let prefetcher =
+            Prefetcher::new(cfg, providers.clone()).context("failed to create quote prefetcher")?;
+
+        Ok(Service {
+            db,
+            corpus,
+            providers,
+            validator,
+            prefetcher: Arc::new(prefetcher),
+            events,
+        })
     }
 }
 
@@ -56,19 +161,25 @@ impl quote::Api for Service {
     async fn get_random_quote(&self) -> Result<Quotes> {
         self.get_random_quote().await
     }
+
+    fn rejected_quotes(&self) -> u64 {
+        self.validator.rejected()
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    use crate::quote_api::traits::MockDatabase;
+    use crate::quote_api::traits::{MockDatabase, MockProvider};
+    use enclose::enclose;
     use fake::{
         faker::{lorem, name},
         Fake, Faker,
     };
     use mockall::predicate::*;
     use mockito::Server;
+    use uuid::Uuid;
 
     #[tokio::test]
     async fn test_get_random_quote_success() {
@@ -79,10 +190,16 @@ mod tests {
             tags: Faker.fake(),
         };
 
+        let expected = structs::to_database(quote.clone());
         let mut db = MockDatabase::new();
         db.expect_save_quote()
-            .with(eq(structs::to_database(quote.clone())))
-            .returning(|_| Ok(()));
+            .with(function(enclose!((expected) move |saved: &Quotes| {
+                saved == &Quotes {
+                    id: saved.id.clone(),
+                    ..expected.clone()
+                }
+            })))
+            .returning(Ok);
 
         let raw_quote = match serde_json::to_string(&quote.clone()) {
             Ok(quote) => quote,
@@ -96,15 +213,158 @@ mod tests {
             .create_async()
             .await;
 
-        let service = Service {
-            db: Arc::new(db),
-            client: reqwest::Client::new(),
-            quote_url: server.url(),
+        let events = Bus::new();
+        let mut received = events.subscribe();
+        let cfg = QuoteApiConfig {
+            url: server.url(),
+            connect_timeout_ms: 500,
+            read_timeout_ms: 500,
+            ..QuoteApiConfig::default()
         };
+        let service = Service::new(&cfg, Arc::new(db), events).unwrap();
 
         let res = service.get_random_quote().await;
         assert!(res.is_ok());
-        assert_eq!(res.unwrap(), structs::to_database(quote));
+
+        let res = res.unwrap();
+        assert_eq!(
+            received.try_recv().unwrap(),
+            Event::NewQuoteAdded(quote::structs::from_database_quote_to_quote(res.clone()))
+        );
+        assert_eq!(res.source, structs::SOURCE);
+        assert_eq!(res.external_id, Some(quote.id.to_string()));
+        assert_eq!(
+            res,
+            Quotes {
+                id: res.id.clone(),
+                ..expected
+            }
+        );
         mock.assert_async().await;
     }
+
+    #[tokio::test]
+    async fn test_get_random_quote_prefetched() {
+        let mut provider = MockProvider::new();
+        provider
+            .expect_name()
+            .return_const(structs::SOURCE.to_string());
+        provider.expect_fetch().times(2).returning(|| {
+            Ok(structs::to_database(structs::Quote {
+                id: Faker.fake(),
+                quote: lorem::en::Sentence(5..10).fake(),
+                author: name::en::Name().fake(),
+                tags: None,
+            }))
+        });
+
+        let mut db = MockDatabase::new();
+        db.expect_save_quote().times(2).returning(Ok);
+
+        let cfg = QuoteApiConfig {
+            prefetch_size: 1,
+            ..QuoteApiConfig::default()
+        };
+        let service = Service::from_providers(
+            &cfg,
+            Arc::new(db),
+            Arc::new(Corpus::load("").unwrap()),
+            vec![Arc::new(provider)],
+            Arc::new(Validator::new(&cfg).unwrap()),
+            Bus::new(),
+        )
+        .unwrap();
+
+        // the first fetch fills the buffer, the second one happens while the caller waits
+        assert_eq!(service.prefetcher.refill().await, 1);
+        assert!(service.get_random_quote().await.is_ok());
+        assert_eq!(service.prefetcher.len(), 0);
+        assert!(service.get_random_quote().await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_seed_empty_database() {
+        let corpus = Corpus::load("").unwrap();
+        let expected = corpus.quotes().count();
+
+        let mut db = MockDatabase::new();
+        db.expect_count_quotes().times(1).returning(|| Ok(0));
+        db.expect_save_quote()
+            .times(expected)
+            .withf(|quote| quote.source == structs::CORPUS_SOURCE)
+            .returning(Ok);
+
+        let cfg = QuoteApiConfig {
+            offline: true,
+            ..QuoteApiConfig::default()
+        };
+        let service = Service::new(&cfg, Arc::new(db), Bus::new()).unwrap();
+        assert_eq!(service.seed().await.unwrap(), expected);
+    }
+
+    #[tokio::test]
+    async fn test_seed_validates_corpus() {
+        let path = std::env::temp_dir().join(format!("quotes-seed-{}.json", Uuid::new_v4()));
+        std::fs::write(
+            &path,
+            r#"[
+                {"quote": "<p>Know thyself.</p>", "author": "Thales"},
+                {"quote": "Darn.", "author": "Nobody"},
+                {"quote": "Orphan quote", "author": "<br>"}
+            ]"#,
+        )
+        .unwrap();
+        let corpus = Corpus::load(path.to_str().unwrap()).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let mut db = MockDatabase::new();
+        db.expect_count_quotes().times(1).returning(|| Ok(0));
+        db.expect_save_quote()
+            .times(1)
+            .withf(|quote| quote.quote == "Know thyself.")
+            .returning(Ok);
+
+        let cfg = QuoteApiConfig {
+            blocked_words: "darn".to_string(),
+            ..QuoteApiConfig::default()
+        };
+        let service = Service::from_providers(
+            &cfg,
+            Arc::new(db),
+            Arc::new(corpus),
+            vec![],
+            Arc::new(Validator::new(&cfg).unwrap()),
+            Bus::new(),
+        )
+        .unwrap();
+
+        assert_eq!(service.seed().await.unwrap(), 1);
+        assert_eq!(quote::Api::rejected_quotes(&service), 2);
+    }
+
+    #[tokio::test]
+    async fn test_seed_skips_filled_database() {
+        let mut db = MockDatabase::new();
+        db.expect_count_quotes().times(1).returning(|| Ok(3));
+        db.expect_save_quote().never();
+
+        let service = Service::new(&QuoteApiConfig::default(), Arc::new(db), Bus::new()).unwrap();
+        assert_eq!(service.seed().await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_get_random_quote_offline() {
+        let mut db = MockDatabase::new();
+        db.expect_save_quote().times(1).returning(Ok);
+
+        let cfg = QuoteApiConfig {
+            url: "http://127.0.0.1:1".to_string(),
+            offline: true,
+            ..QuoteApiConfig::default()
+        };
+        let service = Service::new(&cfg, Arc::new(db), Bus::new()).unwrap();
+
+        let res = service.get_random_quote().await.unwrap();
+        assert_eq!(res.source, structs::CORPUS_SOURCE);
+    }
 }
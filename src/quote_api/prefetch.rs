@@ -0,0 +1,207 @@
+use anyhow::{anyhow, Context, Result};
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::task::JoinHandle;
+
+use crate::config::QuoteApiConfig;
+use crate::database::structs::quotes::Model as Quotes;
+use crate::quote_api::traits::Provider;
+
+/// Buffer of quotes fetched ahead of the requests, refilled by a background worker.
+pub struct Prefetcher {
+    providers: Vec<Arc<dyn Provider + Send + Sync>>,
+    size: usize,
+    interval: Duration,
+    /// Most quotes fetched from a provider per refill, unlisted providers are not limited.
+    quotas: HashMap<String, usize>,
+    buffer: Mutex<VecDeque<Quotes>>,
+}
+
+impl Prefetcher {
+    /// Oldest buffered quote.
+    pub fn take(&self) -> Option<Quotes> {
+        self.buffer().pop_front()
+    }
+
+    pub fn len(&self) -> usize {
+        self.buffer().len()
+    }
+
+    /// Fetches the missing quotes, a failing provider is skipped until the next refill.
+    pub async fn refill(&self) -> usize {
+        let mut added = 0;
+        for provider in &self.providers {
+            let quota = self
+                .quotas
+                .get(provider.name())
+                .copied()
+                .unwrap_or(usize::MAX);
+
+            for _ in 0..quota {
+                if self.len() >= self.size {
+                    return added;
+                }
+
+                match provider.fetch().await {
+                    Ok(quote) => {
+                        self.buffer().push_back(quote);
+                        added += 1;
+                    }
+                    Err(err) => {
+                        log::warn!("failed to prefetch quote from {}: {err:#}", provider.name());
+                        break;
+                    }
+                }
+            }
+        }
+
+        added
+    }
+
+    /// Refills the buffer every interval, nothing is spawned when the buffer is disabled.
+    pub fn spawn(self: Arc<Self>) -> Option<JoinHandle<()>> {
+        if self.size == 0 {
+            return None;
+        }
+
+        Some(tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(self.interval);
+            loop {
+                ticker.tick().await;
+                let added = self.refill().await;
+                if added > 0 {
+                    log::debug!("prefetched {added} quotes, {} buffered", self.len());
+                }
+            }
+        }))
+    }
+
+    fn buffer(&self) -> std::sync::MutexGuard<'_, VecDeque<Quotes>> {
+        self.buffer.lock().unwrap_or_else(|err| err.into_inner())
+    }
+
+    pub fn new(
+        cfg: &QuoteApiConfig,
+        providers: Vec<Arc<dyn Provider + Send + Sync>>,
+    ) -> Result<Self> {
+        Ok(Prefetcher {
+            providers,
+            size: cfg.prefetch_size,
+            interval: Duration::from_millis(cfg.prefetch_interval_ms.max(1)),
+            quotas: parse_quotas(&cfg.prefetch_quotas).context("failed to parse quotas")?,
+            buffer: Mutex::new(VecDeque::with_capacity(cfg.prefetch_size)),
+        })
+    }
+}
+
+/// `name=count` pairs separated by commas.
+fn parse_quotas(quotas: &str) -> Result<HashMap<String, usize>> {
+    quotas
+        .split(',')
+        .map(str::trim)
+        .filter(|quota| !quota.is_empty())
+        .map(|quota| {
+            let (name, count) = quota
+                .split_once('=')
+                .ok_or_else(|| anyhow!("quota {quota:?} is not name=count"))?;
+            let count = count
+                .trim()
+                .parse()
+                .with_context(|| format!("failed to parse quota of {name}"))?;
+            Ok((name.trim().to_string(), count))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::quote_api::structs;
+    use crate::quote_api::traits::MockProvider;
+    use fake::{
+        faker::{lorem, name},
+        Fake, Faker,
+    };
+
+    fn new_quote() -> Quotes {
+        structs::to_database(structs::Quote {
+            id: Faker.fake(),
+            quote: lorem::en::Sentence(5..10).fake(),
+            author: name::en::Name().fake(),
+            tags: None,
+        })
+    }
+
+    fn new_provider(name: &str, fetches: usize) -> Arc<dyn Provider + Send + Sync> {
+        let mut provider = MockProvider::new();
+        provider.expect_name().return_const(name.to_string());
+        provider
+            .expect_fetch()
+            .times(fetches)
+            .returning(|| Ok(new_quote()));
+        Arc::new(provider)
+    }
+
+    fn new_prefetcher(
+        size: usize,
+        quotas: &str,
+        providers: Vec<Arc<dyn Provider + Send + Sync>>,
+    ) -> Prefetcher {
+        let cfg = QuoteApiConfig {
+            prefetch_size: size,
+            prefetch_quotas: quotas.to_string(),
+            ..QuoteApiConfig::default()
+        };
+        Prefetcher::new(&cfg, providers).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_refill_up_to_size() {
+        let prefetcher = new_prefetcher(3, "", vec![new_provider("first", 3)]);
+
+        assert_eq!(prefetcher.refill().await, 3);
+        assert_eq!(prefetcher.refill().await, 0);
+
+        let first = prefetcher.buffer().front().cloned();
+        assert_eq!(prefetcher.take(), first);
+        assert_eq!(prefetcher.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_refill_respects_quotas() {
+        let prefetcher = new_prefetcher(
+            5,
+            "first=1, second=2",
+            vec![new_provider("first", 1), new_provider("second", 2)],
+        );
+
+        assert_eq!(prefetcher.refill().await, 3);
+        assert_eq!(prefetcher.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_refill_skips_failing_provider() {
+        let mut failing = MockProvider::new();
+        failing.expect_name().return_const("failing".to_string());
+        failing
+            .expect_fetch()
+            .times(1)
+            .returning(|| Err(anyhow!("quote api is failing, circuit is open")));
+
+        let prefetcher = new_prefetcher(2, "", vec![Arc::new(failing), new_provider("working", 2)]);
+
+        assert_eq!(prefetcher.refill().await, 2);
+    }
+
+    #[test]
+    fn test_parse_quotas() {
+        let quotas = parse_quotas("dummyjson=5,,local = 2").unwrap();
+        assert_eq!(quotas.get("dummyjson"), Some(&5));
+        assert_eq!(quotas.get("local"), Some(&2));
+
+        assert!(parse_quotas("dummyjson").is_err());
+        assert!(parse_quotas("dummyjson=many").is_err());
+    }
+}
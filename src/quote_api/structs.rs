@@ -1,6 +1,12 @@
 use serde::{Deserialize, Serialize};
+use uuid::Uuid;
 
+use crate::database::canonical::fingerprint;
 use crate::database::structs::quotes::Model as Quotes;
+use crate::quote::structs::ModerationStatus;
+
+pub const SOURCE: &str = "dummyjson";
+pub const CORPUS_SOURCE: &str = "corpus";
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct Quote {
@@ -12,10 +18,62 @@ pub struct Quote {
 
 pub fn to_database(quote: Quote) -> Quotes {
     Quotes {
-        id: quote.id.to_string(),
+        id: Uuid::new_v4().to_string(),
         quote: quote.quote,
         author: quote.author,
         tags: quote.tags.unwrap_or_default(),
         likes: 0i32,
+        fingerprint: None,
+        source: SOURCE.to_string(),
+        external_id: Some(quote.id.to_string()),
+        status: ModerationStatus::Approved.as_str().to_string(),
+    }
+}
+
+/// Quote of a local corpus file, identified by the fingerprint of its text.
+#[derive(Deserialize, Clone, Debug)]
+pub struct CorpusQuote {
+    pub quote: String,
+    pub author: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+/// Row of a CSV corpus, the tags are separated by `;`.
+#[derive(Deserialize)]
+pub struct CsvQuote {
+    pub quote: String,
+    pub author: String,
+    #[serde(default)]
+    pub tags: String,
+}
+
+impl From<CsvQuote> for CorpusQuote {
+    fn from(row: CsvQuote) -> Self {
+        CorpusQuote {
+            quote: row.quote,
+            author: row.author,
+            tags: row
+                .tags
+                .split(';')
+                .map(str::trim)
+                .filter(|tag| !tag.is_empty())
+                .map(str::to_string)
+                .collect(),
+        }
+    }
+}
+
+pub fn from_corpus(quote: CorpusQuote) -> Quotes {
+    Quotes {
+        id: Uuid::new_v4().to_string(),
+        external_id: Some(fingerprint(&quote.quote)),
+        quote: quote.quote,
+        author: quote.author,
+        tags: quote.tags,
+        likes: 0i32,
+        fingerprint: None,
+        source: CORPUS_SOURCE.to_string(),
+        status: ModerationStatus::Approved.as_str().to_string(),
     }
 }
@@ -7,5 +7,16 @@ use mockall::automock;
 #[cfg_attr(test, automock)]
 #[async_trait]
 pub trait Database {
-    async fn save_quote(&self, quote: Quotes) -> anyhow::Result<()>;
+    async fn save_quote(&self, quote: Quotes) -> anyhow::Result<Quotes>;
+    async fn count_quotes(&self) -> anyhow::Result<u64>;
+}
+
+/// Source of new quotes, the fetched quotes are not saved yet.
+#[cfg_attr(test, automock)]
+#[async_trait]
+pub trait Provider {
+    /// Name the prefetch quotas refer to.
+    fn name(&self) -> &str;
+
+    async fn fetch(&self) -> anyhow::Result<Quotes>;
 }
@@ -0,0 +1,481 @@
+use anyhow::{anyhow, Context, Result};
+use async_trait::async_trait;
+use std::collections::HashSet;
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use unicode_normalization::UnicodeNormalization;
+use whatlang::Lang;
+
+use crate::config::QuoteApiConfig;
+use crate::database::canonical::{clean_name, slugify};
+use crate::database::structs::quotes::Model as Quotes;
+use crate::quote_api::traits::Provider;
+
+#[derive(Debug, PartialEq)]
+pub enum Rejection {
+    TooShort,
+    TooLong,
+    MissingAuthor,
+    AuthorTooLong,
+    BlockedWord(String),
+    BlockedAuthor,
+    Language(&'static str),
+}
+
+impl fmt::Display for Rejection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Rejection::TooShort => write!(f, "quote is too short"),
+            Rejection::TooLong => write!(f, "quote is too long"),
+            Rejection::MissingAuthor => write!(f, "author is missing"),
+            Rejection::AuthorTooLong => write!(f, "author is too long"),
+            Rejection::BlockedWord(word) => write!(f, "quote contains blocked word {word:?}"),
+            Rejection::BlockedAuthor => write!(f, "author is blocked"),
+            Rejection::Language(code) => write!(f, "quote is written in {code}"),
+        }
+    }
+}
+
+/// Sanitizes the quotes received from the providers and rejects the ones not worth storing.
+pub struct Validator {
+    min_length: usize,
+    max_length: usize,
+    max_author_length: usize,
+    /// Lowercase words, matched against the whole words of the quote.
+    blocked_words: HashSet<String>,
+    /// Slugs of the authors.
+    blocked_authors: HashSet<String>,
+    /// Any language is accepted when empty.
+    languages: Vec<Lang>,
+    rejected: AtomicU64,
+}
+
+impl Validator {
+    /// Quote with its markup stripped and its text normalized, or the reason it's rejected.
+    pub fn sanitize(&self, quote: Quotes) -> Result<Quotes, Rejection> {
+        let text = clean_text(&quote.quote);
+        let author = clean_text(&quote.author);
+        let tags = quote
+            .tags
+            .iter()
+            .map(|tag| clean_text(tag))
+            .filter(|tag| !tag.is_empty())
+            .collect();
+
+        let length = text.chars().count();
+        if length < self.min_length {
+            return Err(Rejection::TooShort);
+        }
+        if self.max_length > 0 && length > self.max_length {
+            return Err(Rejection::TooLong);
+        }
+
+        if author.is_empty() {
+            return Err(Rejection::MissingAuthor);
+        }
+        if self.max_author_length > 0 && author.chars().count() > self.max_author_length {
+            return Err(Rejection::AuthorTooLong);
+        }
+        if self.blocked_authors.contains(&slugify(&author)) {
+            return Err(Rejection::BlockedAuthor);
+        }
+
+        let blocked = text
+            .to_lowercase()
+            .split(|char: char| !char.is_alphanumeric())
+            .find(|word| self.blocked_words.contains(*word))
+            .map(str::to_string);
+        if let Some(word) = blocked {
+            return Err(Rejection::BlockedWord(word));
+        }
+
+        if !self.languages.is_empty() {
+            // short quotes are often misdetected, only a reliable guess rejects them
+            if let Some(info) = whatlang::detect(&text) {
+                if info.is_reliable() && !self.languages.contains(&info.lang()) {
+                    return Err(Rejection::Language(info.lang().code()));
+                }
+            }
+        }
+
+        Ok(Quotes {
+            quote: text,
+            author,
+            tags,
+            ..quote
+        })
+    }
+
+    /// Sanitized quote, a rejected one is logged with where it comes from and counted.
+    pub fn validate(&self, quote: Quotes, origin: &str) -> Option<Quotes> {
+        let external_id = quote.external_id.clone().unwrap_or_default();
+        match self.sanitize(quote) {
+            Ok(quote) => Some(quote),
+            Err(rejection) => {
+                let rejected = self.rejected.fetch_add(1, Ordering::Relaxed) + 1;
+                log::warn!(
+                    "rejected quote {external_id:?} from {origin}: {rejection}, {rejected} rejected so far"
+                );
+                None
+            }
+        }
+    }
+
+    /// Quotes rejected since the start.
+    pub fn rejected(&self) -> u64 {
+        self.rejected.load(Ordering::Relaxed)
+    }
+
+    pub fn new(cfg: &QuoteApiConfig) -> Result<Self> {
+        let languages = parse_list(&cfg.languages)
+            .map(|code| Lang::from_code(code).ok_or_else(|| anyhow!("unknown language {code:?}")))
+            .collect::<Result<_>>()
+            .context("failed to parse languages")?;
+
+        Ok(Validator {
+            min_length: cfg.min_length,
+            max_length: cfg.max_length,
+            max_author_length: cfg.max_author_length,
+            blocked_words: parse_list(&cfg.blocked_words)
+                .map(str::to_lowercase)
+                .collect(),
+            blocked_authors: parse_list(&cfg.blocked_authors).map(slugify).collect(),
+            languages,
+            rejected: AtomicU64::new(0),
+        })
+    }
+}
+
+/// Provider whose quotes go through the validator, a rejected quote is fetched again.
+pub struct Validated {
+    inner: Arc<dyn Provider + Send + Sync>,
+    validator: Arc<Validator>,
+    attempts: u32,
+}
+
+impl Validated {
+    pub fn new(
+        inner: Arc<dyn Provider + Send + Sync>,
+        validator: Arc<Validator>,
+        attempts: u32,
+    ) -> Self {
+        Validated {
+            inner,
+            validator,
+            attempts: attempts.max(1),
+        }
+    }
+}
+
+#[async_trait]
+impl Provider for Validated {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    async fn fetch(&self) -> Result<Quotes> {
+        for _ in 0..self.attempts {
+            let quote = self.inner.fetch().await?;
+            if let Some(quote) = self.validator.validate(quote, self.name()) {
+                return Ok(quote);
+            }
+        }
+
+        Err(anyhow!(
+            "no valid quote from {} after {} attempts",
+            self.name(),
+            self.attempts
+        ))
+    }
+}
+
+const BLOCK_TAGS: [&str; 8] = ["br", "p", "div", "li", "tr", "td", "blockquote", "hr"];
+/// Elements whose content is code, dropped along with their tags.
+const RAW_TEXT_TAGS: [&str; 2] = ["script", "style"];
+
+/// Text without HTML tags, entities, control characters and repeated whitespace, in NFC.
+/// The entities are decoded before the tags are stripped so escaped markup doesn't come
+/// out as live markup.
+fn clean_text(value: &str) -> String {
+    let decoded = html_escape::decode_html_entities(value);
+    let stripped = strip_tags(&decoded);
+    let normalized: String = stripped
+        .nfc()
+        .map(|char| if char.is_control() { ' ' } else { char })
+        .collect();
+
+    clean_name(&normalized)
+}
+
+/// Text outside the tags and the script and style elements. A `<` not opening a closed
+/// tag is text, as in `x < y` or a quote cut after it.
+fn strip_tags(value: &str) -> String {
+    let mut stripped = String::with_capacity(value.len());
+    let mut tag: Option<String> = None;
+    // the raw text element being dropped, until its closing tag
+    let mut raw_text: Option<String> = None;
+    for char in value.chars() {
+        let keep = raw_text.is_none();
+        match (&mut tag, char) {
+            (None, '<') => tag = Some(String::new()),
+            (None, _) if keep => stripped.push(char),
+            (None, _) => {}
+            (Some(content), '>') if is_tag(content) => {
+                let closing = content.starts_with('/');
+                let name = tag_name(content);
+                match &raw_text {
+                    Some(element) if closing && *element == name => raw_text = None,
+                    Some(_) => {}
+                    // a self-closed script has no content to drop
+                    None if !closing
+                        && !content.ends_with('/')
+                        && RAW_TEXT_TAGS.contains(&name.as_str()) =>
+                    {
+                        raw_text = Some(name)
+                    }
+                    // block tags separate words, inline ones don't
+                    None if BLOCK_TAGS.contains(&name.as_str()) => stripped.push(' '),
+                    None => {}
+                }
+                tag = None;
+            }
+            (Some(content), '>') => {
+                if keep {
+                    stripped.push('<');
+                    stripped.push_str(content);
+                    stripped.push('>');
+                }
+                tag = None;
+            }
+            (Some(content), '<') => {
+                if keep {
+                    stripped.push('<');
+                    stripped.push_str(content);
+                }
+                content.clear();
+            }
+            (Some(content), _) => content.push(char),
+        }
+    }
+    if let (Some(content), None) = (tag, raw_text) {
+        stripped.push('<');
+        stripped.push_str(&content);
+    }
+
+    stripped
+}
+
+/// Content of `<...>` looking like a tag, a closing tag or a comment.
+fn is_tag(content: &str) -> bool {
+    let content = content.strip_prefix('/').unwrap_or(content);
+    content.starts_with(|char: char| char.is_ascii_alphabetic() || char == '!')
+}
+
+/// Lowercase name of the tag, without its attributes.
+fn tag_name(content: &str) -> String {
+    content
+        .trim_matches(|char: char| char == '/' || char.is_whitespace())
+        .split_whitespace()
+        .next()
+        .unwrap_or_default()
+        .to_lowercase()
+}
+
+fn parse_list(list: &str) -> impl Iterator<Item = &str> {
+    list.split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::quote_api::traits::MockProvider;
+    use mockall::Sequence;
+
+    fn new_validator(blocked_words: &str, blocked_authors: &str, languages: &str) -> Validator {
+        Validator::new(&QuoteApiConfig {
+            min_length: 5,
+            max_length: 100,
+            max_author_length: 20,
+            blocked_words: blocked_words.to_string(),
+            blocked_authors: blocked_authors.to_string(),
+            languages: languages.to_string(),
+            ..QuoteApiConfig::default()
+        })
+        .unwrap()
+    }
+
+    fn new_quote(quote: &str, author: &str) -> Quotes {
+        Quotes {
+            id: "id".to_string(),
+            quote: quote.to_string(),
+            author: author.to_string(),
+            tags: vec![" <b>wisdom</b> ".to_string(), "<br/>".to_string()],
+            likes: 0,
+            fingerprint: None,
+            source: "test".to_string(),
+            external_id: Some("1".to_string()),
+            status: "approved".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_sanitize() {
+        let validator = new_validator("", "", "");
+
+        let quote = validator
+            .sanitize(new_quote(
+                "<p>Stay  <i>hungry</i>, stay&nbsp;foolish &amp; curious.</p><br/>Or not.\u{0007}",
+                "Cafe\u{0301}\n Author",
+            ))
+            .unwrap();
+
+        assert_eq!(quote.quote, "Stay hungry, stay foolish & curious. Or not.");
+        assert_eq!(quote.author, "Café Author");
+        assert_eq!(quote.tags, vec!["wisdom"]);
+    }
+
+    #[test]
+    fn test_stray_angle_brackets() {
+        assert_eq!(
+            clean_text("Less is <i>more</i> than <everything"),
+            "Less is more than <everything"
+        );
+        assert_eq!(clean_text("If x < y and y > z <3"), "If x < y and y > z <3");
+        assert_eq!(clean_text("a <<b>bold</b> move"), "a <bold move");
+        assert_eq!(clean_text("<!-- note -->Kept</br>"), "Kept");
+    }
+
+    #[test]
+    fn test_escaped_markup_is_stripped() {
+        assert_eq!(
+            clean_text("Hello &lt;script&gt;alert(1)&lt;/script&gt;world"),
+            "Hello world"
+        );
+        assert_eq!(
+            clean_text("&lt;b&gt;Bold&lt;/b&gt; &amp; x &lt; y"),
+            "Bold & x < y"
+        );
+    }
+
+    #[test]
+    fn test_script_and_style_are_dropped() {
+        assert_eq!(
+            clean_text("Be <SCRIPT type=\"text/javascript\">if (a < b) alert(1)</script>kind"),
+            "Be kind"
+        );
+        assert_eq!(
+            clean_text("<style>p { color: red }</style><p>Styled</p>"),
+            "Styled"
+        );
+        assert_eq!(clean_text("Cut <script>alert(1)"), "Cut");
+        assert_eq!(clean_text("Empty <script/>script"), "Empty script");
+    }
+
+    #[test]
+    fn test_length_bounds() {
+        let validator = new_validator("", "", "");
+
+        assert_eq!(
+            validator.sanitize(new_quote("<b>Hi</b>", "Author")),
+            Err(Rejection::TooShort)
+        );
+        assert_eq!(
+            validator.sanitize(new_quote(&"long ".repeat(30), "Author")),
+            Err(Rejection::TooLong)
+        );
+        assert_eq!(
+            validator.sanitize(new_quote("Quote text", " <i></i> ")),
+            Err(Rejection::MissingAuthor)
+        );
+        assert_eq!(
+            validator.sanitize(new_quote("Quote text", &"a".repeat(21))),
+            Err(Rejection::AuthorTooLong)
+        );
+    }
+
+    #[test]
+    fn test_blocklists() {
+        let validator = new_validator("Darn, heck", "Bad Author", "");
+
+        assert_eq!(
+            validator.sanitize(new_quote("Oh DARN it all", "Author")),
+            Err(Rejection::BlockedWord("darn".to_string()))
+        );
+        // only whole words are blocked
+        assert!(validator
+            .sanitize(new_quote("Darnell checked it", "Author"))
+            .is_ok());
+        assert_eq!(
+            validator.sanitize(new_quote("Quote text", "bad_author")),
+            Err(Rejection::BlockedAuthor)
+        );
+    }
+
+    #[test]
+    fn test_languages() {
+        let validator = new_validator("", "", "eng");
+
+        assert!(validator
+            .sanitize(new_quote(
+                "The only way to do great work is to love what you do.",
+                "Author"
+            ))
+            .is_ok());
+        assert_eq!(
+            validator.sanitize(new_quote(
+                "Der Mensch ist, was er isst, und er wird, was er denkt und tut.",
+                "Author"
+            )),
+            Err(Rejection::Language("deu"))
+        );
+
+        assert!(Validator::new(&QuoteApiConfig {
+            languages: "klingon".to_string(),
+            ..QuoteApiConfig::default()
+        })
+        .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_rejected_quotes_are_fetched_again() {
+        let mut seq = Sequence::new();
+        let mut provider = MockProvider::new();
+        provider.expect_name().return_const("test".to_string());
+        provider
+            .expect_fetch()
+            .times(1)
+            .in_sequence(&mut seq)
+            .returning(|| Ok(new_quote("", "Author")));
+        provider
+            .expect_fetch()
+            .times(1)
+            .in_sequence(&mut seq)
+            .returning(|| Ok(new_quote("Quote text", "Author")));
+
+        let validator = Arc::new(new_validator("", "", ""));
+        let validated = Validated::new(Arc::new(provider), validator.clone(), 3);
+
+        assert_eq!(validated.fetch().await.unwrap().quote, "Quote text");
+        assert_eq!(validator.rejected.load(Ordering::Relaxed), 1);
+    }
+
+    #[tokio::test]
+    async fn test_gives_up_after_attempts() {
+        let mut provider = MockProvider::new();
+        provider.expect_name().return_const("test".to_string());
+        provider
+            .expect_fetch()
+            .times(2)
+            .returning(|| Ok(new_quote("", "Author")));
+
+        let validator = Arc::new(new_validator("", "", ""));
+        let validated = Validated::new(Arc::new(provider), validator.clone(), 2);
+
+        assert!(validated.fetch().await.is_err());
+        assert_eq!(validator.rejected.load(Ordering::Relaxed), 2);
+    }
+}
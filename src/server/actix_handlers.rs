@@ -1,80 +1,535 @@
+use actix_web::body::{BoxBody, MessageBody};
+use actix_web::dev::{ServiceRequest, ServiceResponse};
+use actix_web::http::header::{
+    HeaderName, HeaderValue, AUTHORIZATION, CONTENT_TYPE, IF_NONE_MATCH,
+};
+use actix_web::http::StatusCode;
+use actix_web::middleware::Next;
 use actix_web::web::Data;
-use actix_web::{get, patch, web, Error, HttpRequest, HttpResponse, Responder};
-use juniper_actix::graphql_handler;
+use actix_web::{get, patch, post, put, routes, web, Error, HttpRequest, HttpResponse, Responder};
+use actix_ws::{CloseCode, CloseReason, Message, MessageStream, Session};
+use futures::{future, SinkExt, StreamExt};
+use juniper::ScalarValue;
+use juniper_actix::{graphiql_handler, playground_handler};
+use juniper_graphql_ws::{graphql_transport_ws, graphql_ws, ArcSchema, ConnectionConfig};
+use std::fmt;
+use std::pin::pin;
+use std::sync::Arc;
 
+use super::admin::Admin;
+use super::cors::{
+    Cors, CorsReply, CorsRequest, ACCESS_CONTROL_REQUEST_HEADERS, ACCESS_CONTROL_REQUEST_METHOD,
+    ORIGIN,
+};
+use super::graphql::guard::{BatchRequest, Guard, Reply as GraphQLReply};
 use super::graphql::quotes_resolver::{Context as graphql_context, Schema};
-use crate::heartbeat::Heartbeat;
-use crate::quote::Service;
-use crate::server::structs;
+use super::graphql::subscriptions::{guard_graphql_ws, guard_transport_ws};
+use super::graphql::{GRAPHQL_ENDPOINT, SUBSCRIPTIONS_ENDPOINT};
+use super::openapi;
+use super::request_id::{self, REQUEST_ID_HEADER};
+use super::rest::{self, Reply, Rest};
 
-#[get("/heartbeat")]
-async fn heartbeat_handler(heartbeat: Data<Heartbeat>) -> impl Responder {
-    match heartbeat.ping_database().await {
-        Ok(_) => HttpResponse::Ok(),
-        Err(err) => {
-            log::error!("failed to ping database: {err:#}");
-            HttpResponse::InternalServerError()
+impl Responder for Reply {
+    type Body = BoxBody;
+
+    fn respond_to(self, _req: &HttpRequest) -> HttpResponse {
+        let status = StatusCode::from_u16(self.status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+        let mut response = HttpResponse::build(status);
+        for header in self.headers {
+            response.insert_header(header);
+        }
+        match self.content_type {
+            Some(content_type) => response.content_type(content_type).body(self.body),
+            None => response.body(self.body),
         }
     }
 }
 
+fn query(req: &HttpRequest) -> Option<&str> {
+    Some(req.query_string()).filter(|query| !query.is_empty())
+}
+
+fn authorization(req: &HttpRequest) -> Option<&str> {
+    req.headers()
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+}
+
+fn if_none_match(req: &HttpRequest) -> Option<&str> {
+    req.headers()
+        .get(IF_NONE_MATCH)
+        .and_then(|value| value.to_str().ok())
+}
+
+#[routes]
+#[get("/v1/heartbeat")]
+#[get("/heartbeat")]
+async fn heartbeat_handler(rest: Data<Rest>) -> Reply {
+    rest.heartbeat().await
+}
+
+#[routes]
+#[get("/v1/quotes/random")]
 #[get("/")]
-async fn get_quote_handler(
-    query: web::Query<structs::UserID>,
-    quotes: Data<Service>,
-) -> impl Responder {
-    match quotes.get_quote(&query.user_id).await {
-        Ok(quote) => HttpResponse::Ok().json(quote),
-        Err(err) => {
-            log::error!("failed to get quote: {err:#}");
-            HttpResponse::InternalServerError().finish()
-        }
-    }
+async fn get_quote_handler(req: HttpRequest, rest: Data<Rest>) -> Reply {
+    rest.get_quote(query(&req)).await
+}
+
+#[routes]
+#[get("/v1/quotes")]
+#[get("/batch")]
+async fn get_quotes_handler(req: HttpRequest, rest: Data<Rest>) -> Reply {
+    rest.get_quotes(query(&req)).await
+}
+
+#[get("/v1/quotes/{id}")]
+async fn get_quote_by_id_handler(
+    req: HttpRequest,
+    quote_id: web::Path<String>,
+    rest: Data<Rest>,
+) -> Reply {
+    rest.get_quote_by_id(&quote_id, if_none_match(&req)).await
+}
+
+#[patch("/v1/quotes/{id}/like")]
+async fn like_quote_by_id_handler(
+    req: HttpRequest,
+    quote_id: web::Path<String>,
+    rest: Data<Rest>,
+) -> Reply {
+    rest.like_quote_by_id(&quote_id, query(&req)).await
 }
 
 #[patch("/like")]
-async fn like_quote_handler(
-    query: web::Query<structs::UserAndQuoteID>,
-    quotes: Data<Service>,
-) -> impl Responder {
-    match quotes.like_quote(&query.user_id, &query.quote_id).await {
-        Ok(_) => HttpResponse::Ok(),
-        Err(err) => {
-            log::error!("failed to like quote: {err:#}");
-            HttpResponse::InternalServerError()
-        }
-    }
+async fn like_quote_handler(req: HttpRequest, rest: Data<Rest>) -> Reply {
+    rest.like_quote(query(&req)).await
+}
+
+#[patch("/v1/quotes/{id}/dislike")]
+async fn dislike_quote_handler(
+    req: HttpRequest,
+    quote_id: web::Path<String>,
+    rest: Data<Rest>,
+) -> Reply {
+    rest.dislike_quote(&quote_id, query(&req)).await
+}
+
+#[patch("/v1/quotes/{id}/skip")]
+async fn skip_quote_handler(
+    req: HttpRequest,
+    quote_id: web::Path<String>,
+    rest: Data<Rest>,
+) -> Reply {
+    rest.skip_quote(&quote_id, query(&req)).await
+}
+
+#[get("/v1/quotes/{id}/similar")]
+async fn get_similar_quote_handler(
+    req: HttpRequest,
+    quote_id: web::Path<String>,
+    rest: Data<Rest>,
+) -> Reply {
+    rest.get_similar_quote(&quote_id, query(&req)).await
 }
 
 #[get("/same")]
-async fn get_same_quote_handler(
-    query: web::Query<structs::UserAndQuoteID>,
-    quotes: Data<Service>,
-) -> impl Responder {
-    match quotes.get_same_quote(&query.user_id, &query.quote_id).await {
-        Ok(quote) => HttpResponse::Ok().json(quote),
-        Err(err) => {
-            log::error!("failed to get same quote: {err:#}");
-            HttpResponse::InternalServerError().finish()
+async fn get_same_quote_handler(req: HttpRequest, rest: Data<Rest>) -> Reply {
+    rest.get_same_quote(query(&req)).await
+}
+
+#[routes]
+#[get("/v1/tags")]
+#[get("/tags")]
+async fn get_tags_handler(req: HttpRequest, rest: Data<Rest>) -> Reply {
+    rest.get_tags(query(&req), if_none_match(&req)).await
+}
+
+#[routes]
+#[get("/v1/authors")]
+#[get("/authors")]
+async fn get_authors_handler(req: HttpRequest, rest: Data<Rest>) -> Reply {
+    rest.get_authors(query(&req), if_none_match(&req)).await
+}
+
+#[routes]
+#[get("/v1/daily")]
+#[get("/daily")]
+async fn get_quote_of_the_day_handler(req: HttpRequest, rest: Data<Rest>) -> Reply {
+    rest.get_quote_of_the_day(query(&req), if_none_match(&req))
+        .await
+}
+
+#[routes]
+#[put("/v1/admin/daily")]
+#[put("/admin/daily")]
+async fn set_quote_of_the_day_handler(req: HttpRequest, rest: Data<Rest>) -> Reply {
+    rest.set_quote_of_the_day(authorization(&req), query(&req))
+        .await
+}
+
+#[post("/v1/quotes/{id}/report")]
+async fn report_quote_handler(
+    req: HttpRequest,
+    quote_id: web::Path<String>,
+    rest: Data<Rest>,
+) -> Reply {
+    rest.report_quote(&quote_id, query(&req)).await
+}
+
+#[get("/v1/admin/reports")]
+async fn get_review_queue_handler(req: HttpRequest, rest: Data<Rest>) -> Reply {
+    rest.get_review_queue(authorization(&req), query(&req))
+        .await
+}
+
+#[put("/v1/admin/quotes/{id}/moderation")]
+async fn moderate_quote_handler(
+    req: HttpRequest,
+    quote_id: web::Path<String>,
+    rest: Data<Rest>,
+) -> Reply {
+    rest.moderate_quote(authorization(&req), &quote_id, query(&req))
+        .await
+}
+
+#[get("/v1/admin/quotes/{id}/audit")]
+async fn get_moderation_audit_handler(
+    req: HttpRequest,
+    quote_id: web::Path<String>,
+    rest: Data<Rest>,
+) -> Reply {
+    rest.get_moderation_audit(authorization(&req), &quote_id)
+        .await
+}
+
+#[get("/v1/admin/stats")]
+async fn get_stats_handler(req: HttpRequest, rest: Data<Rest>) -> Reply {
+    rest.get_stats(authorization(&req)).await
+}
+
+/// Unknown paths and wrong methods, the routes don't tell them apart.
+pub async fn not_found_handler(req: HttpRequest) -> Reply {
+    Reply::unrouted(req.path())
+}
+
+/// Handles the request within the scope of its id and returns the id in the response.
+pub async fn request_id_middleware(
+    req: ServiceRequest,
+    next: Next<impl MessageBody + 'static>,
+) -> Result<ServiceResponse<impl MessageBody>, Error> {
+    let id = request_id::from_header(
+        req.headers()
+            .get(REQUEST_ID_HEADER)
+            .and_then(|value| value.to_str().ok()),
+    );
+
+    let mut response = request_id::scope(id.clone(), next.call(req)).await?;
+    if let Ok(value) = HeaderValue::from_str(&id) {
+        response
+            .headers_mut()
+            .insert(HeaderName::from_static(REQUEST_ID_HEADER), value);
+    }
+
+    Ok(response)
+}
+
+pub async fn cors_middleware(
+    cors: Data<Cors>,
+    req: ServiceRequest,
+    next: Next<impl MessageBody + 'static>,
+) -> Result<ServiceResponse<BoxBody>, Error> {
+    let header = |name| {
+        req.headers()
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+    };
+    let reply = cors.handle(&CorsRequest {
+        method: req.method().as_str(),
+        origin: header(ORIGIN),
+        request_method: header(ACCESS_CONTROL_REQUEST_METHOD),
+        request_headers: header(ACCESS_CONTROL_REQUEST_HEADERS),
+    });
+
+    let (mut response, headers) = match reply {
+        CorsReply::Preflight { status, headers } => {
+            let response = Reply::from_status(status).respond_to(req.request());
+            (req.into_response(response), headers)
+        }
+        CorsReply::Headers(headers) => (next.call(req).await?.map_into_boxed_body(), headers),
+    };
+    for (name, value) in headers {
+        if let Ok(value) = HeaderValue::from_str(&value) {
+            response
+                .headers_mut()
+                .append(HeaderName::from_static(name), value);
         }
     }
+
+    Ok(response)
+}
+
+pub async fn openapi_handler() -> HttpResponse {
+    HttpResponse::Ok()
+        .content_type("application/json")
+        .body(openapi::document())
+}
+
+pub async fn swagger_ui_handler() -> HttpResponse {
+    HttpResponse::Ok()
+        .content_type("text/html; charset=utf-8")
+        .body(openapi::swagger_ui())
+}
+
+/// Builds the request context from the shared one, the admin flag depends on the request headers.
+fn new_graphql_context(
+    req: &HttpRequest,
+    admin: &Admin,
+    context: &graphql_context,
+) -> graphql_context {
+    let authorization = req
+        .headers()
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok());
+
+    context.for_request(admin.is_authorized(authorization))
+}
+
+pub async fn get_graphql(
+    req: HttpRequest,
+    schema: Data<Schema>,
+    context: Data<graphql_context>,
+    admin: Data<Admin>,
+    guard: Data<Guard>,
+) -> HttpResponse {
+    let request = match rest::parse_graphql_query(query(&req)) {
+        Ok(request) => request,
+        Err(reply) => return reply.respond_to(&req),
+    };
+    let context = new_graphql_context(&req, &admin, &context);
+    let response = guard
+        .execute(&schema, &context, request.into_request())
+        .await;
+
+    to_graphql_response(response)
+}
+
+pub async fn post_graphql(
+    req: HttpRequest,
+    body: web::Bytes,
+    schema: Data<Schema>,
+    context: Data<graphql_context>,
+    admin: Data<Admin>,
+    guard: Data<Guard>,
+) -> HttpResponse {
+    let context = new_graphql_context(&req, &admin, &context);
+    let content_type = req
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok());
+    let response = guard
+        .execute(
+            &schema,
+            &context,
+            BatchRequest::from_body(content_type, &body),
+        )
+        .await;
+
+    to_graphql_response(response)
+}
+
+fn to_graphql_response(reply: GraphQLReply) -> HttpResponse {
+    if reply.ok {
+        HttpResponse::Ok().json(reply.response)
+    } else {
+        HttpResponse::BadRequest().json(reply.response)
+    }
 }
 
-pub async fn graphql(
+pub async fn graphql_subscriptions(
     req: HttpRequest,
-    payload: web::Payload,
+    stream: web::Payload,
     schema: Data<Schema>,
-    quotes: Data<Service>,
-    heartbeat: Data<Heartbeat>,
+    context: Data<graphql_context>,
+    admin: Data<Admin>,
+    guard: Data<Guard>,
 ) -> Result<HttpResponse, Error> {
-    graphql_handler(
-        &schema,
-        &graphql_context {
-            quotes: quotes.get_ref().clone(),
-            heartbeat: heartbeat.get_ref().clone(),
-        },
-        req,
-        payload,
-    )
-    .await
+    let config = ConnectionConfig::new(new_graphql_context(&req, &admin, &context));
+    let legacy = req
+        .headers()
+        .get("sec-websocket-protocol")
+        .map(HeaderValue::as_bytes)
+        == Some(GRAPHQL_WS.as_bytes());
+
+    let (mut response, session, messages) = actix_ws::handle(&req, stream)?;
+    let (schema, guard) = (schema.into_inner(), guard.into_inner());
+    let protocol = if legacy {
+        actix_web::rt::spawn(serve_graphql_ws(session, messages, schema, config, guard));
+        GRAPHQL_WS
+    } else {
+        actix_web::rt::spawn(serve_graphql_transport_ws(
+            session, messages, schema, config, guard,
+        ));
+        GRAPHQL_TRANSPORT_WS
+    };
+
+    response.headers_mut().insert(
+        HeaderName::from_static("sec-websocket-protocol"),
+        HeaderValue::from_static(protocol),
+    );
+    Ok(response)
+}
+
+const GRAPHQL_TRANSPORT_WS: &str = "graphql-transport-ws";
+const GRAPHQL_WS: &str = "graphql-ws";
+
+async fn serve_graphql_transport_ws(
+    session: Session,
+    messages: MessageStream,
+    schema: Arc<Schema>,
+    config: ConnectionConfig<graphql_context>,
+    guard: Arc<Guard>,
+) {
+    let connection = graphql_transport_ws::Connection::new(ArcSchema(schema.clone()), config);
+    let (s_tx, s_rx) = guard_transport_ws(connection, guard, schema);
+
+    let input = messages
+        .filter_map(|message| future::ready(message.ok().map(|message| Ok(ClientMessage(message)))))
+        .forward(s_tx.sink_map_err(|err| match err {}));
+
+    let output = async move {
+        let mut s_rx = pin!(s_rx);
+        let mut session = session;
+        while let Some(output) = s_rx.next().await {
+            let text = match output {
+                graphql_transport_ws::Output::Message(message) => serde_json::to_string(&message),
+                graphql_transport_ws::Output::Close { code, message } => {
+                    let reason = CloseReason {
+                        code: code.into(),
+                        description: Some(message),
+                    };
+                    let _ = session.close(Some(reason)).await;
+                    return;
+                }
+            };
+            match text {
+                Ok(text) => {
+                    if session.text(text).await.is_err() {
+                        return;
+                    }
+                }
+                Err(err) => {
+                    let _ = session.close(Some(serialization_failure(err))).await;
+                    return;
+                }
+            }
+        }
+        let _ = session.close(Some(CloseCode::Normal.into())).await;
+    };
+
+    // Whichever side finishes first closes the connection, errors have nowhere to go.
+    let _ = future::select(pin!(input), pin!(output)).await;
+}
+
+async fn serve_graphql_ws(
+    session: Session,
+    messages: MessageStream,
+    schema: Arc<Schema>,
+    config: ConnectionConfig<graphql_context>,
+    guard: Arc<Guard>,
+) {
+    let connection = graphql_ws::Connection::new(ArcSchema(schema.clone()), config);
+    let (s_tx, s_rx) = guard_graphql_ws(connection, guard, schema);
+
+    let input = messages
+        .filter_map(|message| future::ready(message.ok().map(|message| Ok(ClientMessage(message)))))
+        .forward(s_tx.sink_map_err(|err| match err {}));
+
+    let output = async move {
+        let mut s_rx = pin!(s_rx);
+        let mut session = session;
+        while let Some(message) = s_rx.next().await {
+            match serde_json::to_string(&message) {
+                Ok(text) => {
+                    if session.text(text).await.is_err() {
+                        return;
+                    }
+                }
+                Err(err) => {
+                    let _ = session.close(Some(serialization_failure(err))).await;
+                    return;
+                }
+            }
+        }
+        let _ = session.close(Some(CloseCode::Normal.into())).await;
+    };
+
+    // Whichever side finishes first closes the connection, errors have nowhere to go.
+    let _ = future::select(pin!(input), pin!(output)).await;
+}
+
+fn serialization_failure(err: serde_json::Error) -> CloseReason {
+    CloseReason {
+        code: CloseCode::Error,
+        description: Some(format!("failed to serialize response: {err}")),
+    }
+}
+
+/// Raw WebSocket message received from a subscriptions client.
+struct ClientMessage(Message);
+
+#[derive(Debug)]
+enum ClientMessageError {
+    Serde(serde_json::Error),
+    Unexpected(Message),
+}
+
+impl fmt::Display for ClientMessageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Serde(err) => write!(f, "failed to deserialize message: {err}"),
+            Self::Unexpected(message) => write!(f, "unexpected message: {message:?}"),
+        }
+    }
+}
+
+impl std::error::Error for ClientMessageError {}
+
+impl<S: ScalarValue> TryFrom<ClientMessage> for graphql_transport_ws::Input<S> {
+    type Error = ClientMessageError;
+
+    fn try_from(message: ClientMessage) -> Result<Self, Self::Error> {
+        match message.0 {
+            Message::Text(text) => serde_json::from_str(&text)
+                .map(Self::Message)
+                .map_err(ClientMessageError::Serde),
+            Message::Binary(bytes) => serde_json::from_slice(&bytes)
+                .map(Self::Message)
+                .map_err(ClientMessageError::Serde),
+            Message::Close(_) => Ok(Self::Close),
+            other => Err(ClientMessageError::Unexpected(other)),
+        }
+    }
+}
+
+impl<S: ScalarValue> TryFrom<ClientMessage> for graphql_ws::ClientMessage<S> {
+    type Error = ClientMessageError;
+
+    fn try_from(message: ClientMessage) -> Result<Self, Self::Error> {
+        match message.0 {
+            Message::Text(text) => serde_json::from_str(&text).map_err(ClientMessageError::Serde),
+            Message::Binary(bytes) => {
+                serde_json::from_slice(&bytes).map_err(ClientMessageError::Serde)
+            }
+            Message::Close(_) => Ok(Self::ConnectionTerminate),
+            other => Err(ClientMessageError::Unexpected(other)),
+        }
+    }
+}
+
+pub async fn graphiql() -> Result<HttpResponse, Error> {
+    graphiql_handler(GRAPHQL_ENDPOINT, Some(SUBSCRIPTIONS_ENDPOINT)).await
+}
+
+pub async fn playground() -> Result<HttpResponse, Error> {
+    playground_handler(GRAPHQL_ENDPOINT, Some(SUBSCRIPTIONS_ENDPOINT)).await
 }
@@ -0,0 +1,51 @@
+use sha2::{digest::Output, Digest, Sha256};
+
+/// Guards the admin API with the bearer token from the configuration.
+#[derive(Clone)]
+pub struct Admin {
+    /// Digest of the token, `None` when no token is configured.
+    token: Option<Output<Sha256>>,
+}
+
+impl Admin {
+    pub fn new(token: &str) -> Self {
+        Admin {
+            token: (!token.is_empty()).then(|| Sha256::digest(token)),
+        }
+    }
+
+    /// Checks an `Authorization` header value, the admin API is disabled when no token is configured.
+    /// The digests are compared in constant time, so the timing tells nothing about the token.
+    pub fn is_authorized(&self, authorization: Option<&str>) -> bool {
+        let (Some(expected), Some(token)) = (
+            &self.token,
+            authorization.and_then(|value| value.strip_prefix("Bearer ")),
+        ) else {
+            return false;
+        };
+
+        Sha256::digest(token)
+            .iter()
+            .zip(expected.iter())
+            .fold(0, |diff, (a, b)| diff | (a ^ b))
+            == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_authorized() {
+        let admin = Admin::new("secret");
+        assert!(admin.is_authorized(Some("Bearer secret")));
+        assert!(!admin.is_authorized(Some("Bearer secreT")));
+        assert!(!admin.is_authorized(Some("secret")));
+        assert!(!admin.is_authorized(None));
+
+        let disabled = Admin::new("");
+        assert!(!disabled.is_authorized(Some("Bearer ")));
+        assert!(!disabled.is_authorized(None));
+    }
+}
@@ -1,59 +1,722 @@
+use axum::body::Bytes;
+use axum::extract::ws::{close_code, CloseFrame, Message, WebSocket};
+use axum::http::header::{CONTENT_TYPE, IF_NONE_MATCH};
 use axum::{
-    extract::{Query, State},
-    http::StatusCode,
-    response::{IntoResponse, Response},
-    Json,
+    extract::{Path, RawQuery, Request as HttpRequest, State, WebSocketUpgrade},
+    http::{header::AUTHORIZATION, HeaderMap, HeaderName, HeaderValue, StatusCode, Uri},
+    middleware::Next,
+    response::{Html, IntoResponse, Response},
+    Extension, Json,
 };
+use futures::{future, SinkExt, StreamExt};
+use juniper::ScalarValue;
+use juniper_graphql_ws::{graphql_transport_ws, graphql_ws, ArcSchema, ConnectionConfig};
+use std::fmt;
+use std::sync::Arc;
 
-use crate::{heartbeat::Heartbeat, quote::Service};
+use crate::{
+    catalogue::structs::{Author, Page, Tag},
+    quote::structs::{AuditEntry, Quote, ReviewItem, Stats},
+};
 
+use super::admin::Admin;
+use super::cors::{
+    Cors, CorsReply, CorsRequest, ACCESS_CONTROL_REQUEST_HEADERS, ACCESS_CONTROL_REQUEST_METHOD,
+    ORIGIN,
+};
+use super::graphql::guard::{BatchRequest, GetRequest, Guard, Reply as GraphQLReply, Request};
+use super::graphql::quotes_resolver::{Context as graphql_context, Schema};
+use super::graphql::subscriptions::{guard_graphql_ws, guard_transport_ws};
+use super::openapi;
+use super::request_id::{self, REQUEST_ID_HEADER};
+use super::rest::{self, ErrorBody, Reply, Rest};
 use super::structs;
 
-pub async fn heartbeat_handler(heartbeat: State<Heartbeat>) -> StatusCode {
-    match heartbeat.ping_database().await {
-        Ok(_) => StatusCode::OK,
-        Err(err) => {
-            log::error!("failed to ping database: {err:#}");
-            StatusCode::INTERNAL_SERVER_ERROR
+impl IntoResponse for Reply {
+    fn into_response(self) -> Response {
+        let status = StatusCode::from_u16(self.status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+        let mut response = (status, self.body).into_response();
+        for (name, value) in self.headers {
+            if let Ok(value) = HeaderValue::from_str(&value) {
+                response
+                    .headers_mut()
+                    .insert(HeaderName::from_static(name), value);
+            }
         }
+        match self.content_type {
+            Some(content_type) => response
+                .headers_mut()
+                .insert(CONTENT_TYPE, HeaderValue::from_static(content_type)),
+            None => response.headers_mut().remove(CONTENT_TYPE),
+        };
+
+        response
+    }
+}
+
+fn authorization(headers: &HeaderMap) -> Option<&str> {
+    headers
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+}
+
+fn if_none_match(headers: &HeaderMap) -> Option<&str> {
+    headers
+        .get(IF_NONE_MATCH)
+        .and_then(|value| value.to_str().ok())
+}
+
+pub async fn not_found_handler() -> Reply {
+    Reply::not_found()
+}
+
+pub async fn method_not_allowed_handler(uri: Uri) -> Reply {
+    Reply::unrouted(uri.path())
+}
+
+/// Handles the request within the scope of its id and returns the id in the response,
+/// shared by the axum and gRPC servers.
+pub async fn request_id_middleware(request: HttpRequest, next: Next) -> Response {
+    let id = request_id::from_header(
+        request
+            .headers()
+            .get(REQUEST_ID_HEADER)
+            .and_then(|value| value.to_str().ok()),
+    );
+
+    let mut response = request_id::scope(id.clone(), next.run(request)).await;
+    if let Ok(value) = HeaderValue::from_str(&id) {
+        response
+            .headers_mut()
+            .insert(HeaderName::from_static(REQUEST_ID_HEADER), value);
+    }
+
+    response
+}
+
+pub async fn cors_middleware(
+    State(cors): State<Arc<Cors>>,
+    request: HttpRequest,
+    next: Next,
+) -> Response {
+    let reply = {
+        let header = |name| {
+            request
+                .headers()
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+        };
+        cors.handle(&CorsRequest {
+            method: request.method().as_str(),
+            origin: header(ORIGIN),
+            request_method: header(ACCESS_CONTROL_REQUEST_METHOD),
+            request_headers: header(ACCESS_CONTROL_REQUEST_HEADERS),
+        })
+    };
+
+    let (mut response, headers) = match reply {
+        CorsReply::Preflight { status, headers } => {
+            (Reply::from_status(status).into_response(), headers)
+        }
+        CorsReply::Headers(headers) => (next.run(request).await, headers),
+    };
+    for (name, value) in headers {
+        if let Ok(value) = HeaderValue::from_str(&value) {
+            response
+                .headers_mut()
+                .append(HeaderName::from_static(name), value);
+        }
+    }
+
+    response
+}
+
+#[utoipa::path(
+    get,
+    path = "/v1/heartbeat",
+    tag = "service",
+    description = "Check if server and database working normally",
+    responses(
+        (status = 200, description = "Everything is ok"),
+        (status = 500, description = "Database is not working", body = ErrorBody),
+    ),
+)]
+pub async fn heartbeat_handler(rest: State<Rest>) -> Reply {
+    rest.heartbeat().await
+}
+
+#[utoipa::path(
+    get,
+    path = "/v1/quotes/random",
+    tag = "quotes",
+    description = "Get unseen quote randomly or new quote from external site api",
+    params(structs::UserID),
+    responses(
+        (status = 200, description = "Random quote", body = Quote),
+        (status = 400, description = "Invalid query", body = ErrorBody),
+        (status = 500, description = "Failed to get random quote", body = ErrorBody),
+    ),
+)]
+pub async fn get_quote_handler(RawQuery(query): RawQuery, rest: State<Rest>) -> Reply {
+    rest.get_quote(query.as_deref()).await
+}
+
+#[utoipa::path(
+    get,
+    path = "/v1/quotes",
+    tag = "quotes",
+    description = "Get several distinct unseen quotes at once, topped up with new quotes from \
+        external site api when there are not enough of them",
+    params(structs::BatchQuery),
+    responses(
+        (status = 200, description = "Quotes", body = Vec<Quote>),
+        (status = 400, description = "Invalid query or count", body = ErrorBody),
+        (status = 500, description = "Failed to get quotes", body = ErrorBody),
+    ),
+)]
+pub async fn get_quotes_handler(RawQuery(query): RawQuery, rest: State<Rest>) -> Reply {
+    rest.get_quotes(query.as_deref()).await
+}
+
+#[utoipa::path(
+    patch,
+    path = "/v1/quotes/{id}/like",
+    tag = "quotes",
+    description = "Like viewed quote",
+    params(("id" = String, Path, description = "ID of the quote to like"), structs::UserID),
+    responses(
+        (status = 200, description = "Quote successfully liked"),
+        (status = 400, description = "Invalid query", body = ErrorBody),
+        (status = 404, description = "Quote not viewed by the user", body = ErrorBody),
+        (status = 500, description = "Failed to like quote", body = ErrorBody),
+    ),
+)]
+pub async fn like_quote_by_id_handler(
+    Path(quote_id): Path<String>,
+    RawQuery(query): RawQuery,
+    rest: State<Rest>,
+) -> Reply {
+    rest.like_quote_by_id(&quote_id, query.as_deref()).await
+}
+
+pub async fn like_quote_handler(RawQuery(query): RawQuery, rest: State<Rest>) -> Reply {
+    rest.like_quote(query.as_deref()).await
+}
+
+#[utoipa::path(
+    patch,
+    path = "/v1/quotes/{id}/dislike",
+    tag = "quotes",
+    description = "Dislike viewed quote, its author and tags are picked less often for the user",
+    params(("id" = String, Path, description = "ID of the quote to dislike"), structs::UserID),
+    responses(
+        (status = 200, description = "Quote successfully disliked"),
+        (status = 400, description = "Invalid query", body = ErrorBody),
+        (status = 404, description = "Quote not viewed by the user", body = ErrorBody),
+        (status = 500, description = "Failed to save feedback", body = ErrorBody),
+    ),
+)]
+pub async fn dislike_quote_handler(
+    Path(quote_id): Path<String>,
+    RawQuery(query): RawQuery,
+    rest: State<Rest>,
+) -> Reply {
+    rest.dislike_quote(&quote_id, query.as_deref()).await
+}
+
+#[utoipa::path(
+    patch,
+    path = "/v1/quotes/{id}/skip",
+    tag = "quotes",
+    description = "Skip viewed quote, its author and tags are picked slightly less often for \
+        the user. Skipping a disliked quote replaces the dislike",
+    params(("id" = String, Path, description = "ID of the quote to skip"), structs::UserID),
+    responses(
+        (status = 200, description = "Quote successfully skipped"),
+        (status = 400, description = "Invalid query", body = ErrorBody),
+        (status = 404, description = "Quote not viewed by the user", body = ErrorBody),
+        (status = 500, description = "Failed to save feedback", body = ErrorBody),
+    ),
+)]
+pub async fn skip_quote_handler(
+    Path(quote_id): Path<String>,
+    RawQuery(query): RawQuery,
+    rest: State<Rest>,
+) -> Reply {
+    rest.skip_quote(&quote_id, query.as_deref()).await
+}
+
+#[utoipa::path(
+    get,
+    path = "/v1/quotes/{id}",
+    tag = "quotes",
+    description = "Get quote by its ID",
+    params(("id" = String, Path, description = "ID of the quote"), ("If-None-Match" = Option<String>, Header, description = "ETag of the cached response")),
+    responses(
+        (status = 200, description = "Quote", body = Quote, headers(("ETag" = String), ("Cache-Control" = String))),
+        (status = 304, description = "Cached quote is still valid"),
+        (status = 404, description = "Quote not found", body = ErrorBody),
+        (status = 500, description = "Failed to get quote", body = ErrorBody),
+    ),
+)]
+pub async fn get_quote_by_id_handler(
+    Path(quote_id): Path<String>,
+    headers: HeaderMap,
+    rest: State<Rest>,
+) -> Reply {
+    rest.get_quote_by_id(&quote_id, if_none_match(&headers))
+        .await
+}
+
+#[utoipa::path(
+    get,
+    path = "/v1/quotes/{id}/similar",
+    tag = "quotes",
+    description = "Get quote that is pretty similar to a specified one",
+    params(("id" = String, Path, description = "ID of the quote to match"), structs::UserID),
+    responses(
+        (status = 200, description = "Similar quote", body = Quote),
+        (status = 400, description = "Invalid query", body = ErrorBody),
+        (status = 404, description = "Quote not found", body = ErrorBody),
+        (status = 500, description = "Failed to get similar quote", body = ErrorBody),
+    ),
+)]
+pub async fn get_similar_quote_handler(
+    Path(quote_id): Path<String>,
+    RawQuery(query): RawQuery,
+    rest: State<Rest>,
+) -> Reply {
+    rest.get_similar_quote(&quote_id, query.as_deref()).await
+}
+
+pub async fn get_same_quote_handler(RawQuery(query): RawQuery, rest: State<Rest>) -> Reply {
+    rest.get_same_quote(query.as_deref()).await
+}
+
+#[utoipa::path(
+    get,
+    path = "/v1/tags",
+    tag = "catalogue",
+    description = "Get all tags with the number of quotes that use them",
+    params(structs::ListQuery, ("If-None-Match" = Option<String>, Header, description = "ETag of the cached response")),
+    responses(
+        (status = 200, description = "Page of tags", body = Page<Tag>, headers(("ETag" = String), ("Cache-Control" = String))),
+        (status = 304, description = "Cached page is still valid"),
+        (status = 400, description = "Invalid sorting or pagination parameters", body = ErrorBody),
+        (status = 500, description = "Failed to get tags", body = ErrorBody),
+    ),
+)]
+pub async fn get_tags_handler(
+    RawQuery(query): RawQuery,
+    headers: HeaderMap,
+    rest: State<Rest>,
+) -> Reply {
+    rest.get_tags(query.as_deref(), if_none_match(&headers))
+        .await
+}
+
+#[utoipa::path(
+    get,
+    path = "/v1/authors",
+    tag = "catalogue",
+    description = "Get all authors with the number of their quotes and likes",
+    params(structs::ListQuery, ("If-None-Match" = Option<String>, Header, description = "ETag of the cached response")),
+    responses(
+        (status = 200, description = "Page of authors", body = Page<Author>, headers(("ETag" = String), ("Cache-Control" = String))),
+        (status = 304, description = "Cached page is still valid"),
+        (status = 400, description = "Invalid sorting or pagination parameters", body = ErrorBody),
+        (status = 500, description = "Failed to get authors", body = ErrorBody),
+    ),
+)]
+pub async fn get_authors_handler(
+    RawQuery(query): RawQuery,
+    headers: HeaderMap,
+    rest: State<Rest>,
+) -> Reply {
+    rest.get_authors(query.as_deref(), if_none_match(&headers))
+        .await
+}
+
+#[utoipa::path(
+    get,
+    path = "/v1/daily",
+    tag = "quotes",
+    description = "Get the quote of the day, it's picked once per calendar day in the requested \
+        timezone and stays the same for everyone until the day ends",
+    params(structs::DailyQuery, ("If-None-Match" = Option<String>, Header, description = "ETag of the cached response")),
+    responses(
+        (status = 200, description = "Quote of the day", body = Quote, headers(("ETag" = String), ("Cache-Control" = String))),
+        (status = 304, description = "Cached quote is still valid"),
+        (status = 400, description = "Unknown timezone", body = ErrorBody),
+        (status = 404, description = "There are no quotes to pick from", body = ErrorBody),
+        (status = 500, description = "Failed to get quote of the day", body = ErrorBody),
+    ),
+)]
+pub async fn get_quote_of_the_day_handler(
+    RawQuery(query): RawQuery,
+    headers: HeaderMap,
+    rest: State<Rest>,
+) -> Reply {
+    rest.get_quote_of_the_day(query.as_deref(), if_none_match(&headers))
+        .await
+}
+
+#[utoipa::path(
+    put,
+    path = "/v1/admin/daily",
+    tag = "admin",
+    description = "Override the quote of the day",
+    params(structs::DailyOverrideQuery),
+    security(("admin_token" = [])),
+    responses(
+        (status = 200, description = "The new quote of the day", body = Quote),
+        (status = 400, description = "Invalid date or timezone", body = ErrorBody),
+        (status = 401, description = "Missing or wrong admin token", body = ErrorBody),
+        (status = 404, description = "Quote not found", body = ErrorBody),
+        (status = 500, description = "Failed to set quote of the day", body = ErrorBody),
+    ),
+)]
+pub async fn set_quote_of_the_day_handler(
+    headers: HeaderMap,
+    RawQuery(query): RawQuery,
+    rest: State<Rest>,
+) -> Reply {
+    rest.set_quote_of_the_day(authorization(&headers), query.as_deref())
+        .await
+}
+
+#[utoipa::path(
+    post,
+    path = "/v1/quotes/{id}/report",
+    tag = "quotes",
+    description = "Report an offensive or incorrect quote, a quote reported by enough users is \
+        hidden until an admin reviews it. Reporting a quote again replaces the previous report",
+    params(("id" = String, Path, description = "ID of the quote to report"), structs::ReportQuery),
+    responses(
+        (status = 200, description = "Quote successfully reported"),
+        (status = 400, description = "Invalid query or too long reason", body = ErrorBody),
+        (status = 404, description = "Quote not found", body = ErrorBody),
+        (status = 500, description = "Failed to report quote", body = ErrorBody),
+    ),
+)]
+pub async fn report_quote_handler(
+    Path(quote_id): Path<String>,
+    RawQuery(query): RawQuery,
+    rest: State<Rest>,
+) -> Reply {
+    rest.report_quote(&quote_id, query.as_deref()).await
+}
+
+#[utoipa::path(
+    get,
+    path = "/v1/admin/reports",
+    tag = "admin",
+    description = "Get the review queue: pending quotes and quotes with open reports, most \
+        reported first",
+    params(structs::ReviewQuery),
+    security(("admin_token" = [])),
+    responses(
+        (status = 200, description = "Quotes to review with their open reports", body = Vec<ReviewItem>),
+        (status = 400, description = "Invalid pagination parameters", body = ErrorBody),
+        (status = 401, description = "Missing or wrong admin token", body = ErrorBody),
+        (status = 500, description = "Failed to get review queue", body = ErrorBody),
+    ),
+)]
+pub async fn get_review_queue_handler(
+    headers: HeaderMap,
+    RawQuery(query): RawQuery,
+    rest: State<Rest>,
+) -> Reply {
+    rest.get_review_queue(authorization(&headers), query.as_deref())
+        .await
+}
+
+#[utoipa::path(
+    put,
+    path = "/v1/admin/quotes/{id}/moderation",
+    tag = "admin",
+    description = "Approve or hide a quote, its open reports are resolved and the decision is \
+        recorded in the audit trail",
+    params(("id" = String, Path, description = "ID of the quote to moderate"), structs::ModerationQuery),
+    security(("admin_token" = [])),
+    responses(
+        (status = 200, description = "Recorded decision", body = AuditEntry),
+        (status = 400, description = "Invalid status", body = ErrorBody),
+        (status = 401, description = "Missing or wrong admin token", body = ErrorBody),
+        (status = 404, description = "Quote not found", body = ErrorBody),
+        (status = 500, description = "Failed to moderate quote", body = ErrorBody),
+    ),
+)]
+pub async fn moderate_quote_handler(
+    headers: HeaderMap,
+    Path(quote_id): Path<String>,
+    RawQuery(query): RawQuery,
+    rest: State<Rest>,
+) -> Reply {
+    rest.moderate_quote(authorization(&headers), &quote_id, query.as_deref())
+        .await
+}
+
+#[utoipa::path(
+    get,
+    path = "/v1/admin/quotes/{id}/audit",
+    tag = "admin",
+    description = "Get the status changes of a quote, oldest first",
+    params(("id" = String, Path, description = "ID of the quote")),
+    security(("admin_token" = [])),
+    responses(
+        (status = 200, description = "Audit trail of the quote", body = Vec<AuditEntry>),
+        (status = 401, description = "Missing or wrong admin token", body = ErrorBody),
+        (status = 500, description = "Failed to get moderation audit", body = ErrorBody),
+    ),
+)]
+pub async fn get_moderation_audit_handler(
+    headers: HeaderMap,
+    Path(quote_id): Path<String>,
+    rest: State<Rest>,
+) -> Reply {
+    rest.get_moderation_audit(authorization(&headers), &quote_id)
+        .await
+}
+
+#[utoipa::path(
+    get,
+    path = "/v1/admin/stats",
+    tag = "admin",
+    description = "Get the counters of the service since it started",
+    security(("admin_token" = [])),
+    responses(
+        (status = 200, description = "Counters of the service", body = Stats),
+        (status = 401, description = "Missing or wrong admin token", body = ErrorBody),
+    ),
+)]
+pub async fn get_stats_handler(headers: HeaderMap, rest: State<Rest>) -> Reply {
+    rest.get_stats(authorization(&headers)).await
+}
+
+pub async fn openapi_handler() -> Response {
+    ([(CONTENT_TYPE, "application/json")], openapi::document()).into_response()
+}
+
+pub async fn swagger_ui_handler() -> Html<String> {
+    Html(openapi::swagger_ui())
+}
+
+/// Builds the request context from the shared one, the admin flag depends on the request headers.
+fn new_graphql_context(
+    headers: &HeaderMap,
+    admin: &Admin,
+    context: graphql_context,
+) -> graphql_context {
+    context.for_request(admin.is_authorized(authorization(headers)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/graphql",
+    tag = "graphql",
+    description = "Execute a GraphQL query, GraphiQL is served at `/graphiql` and the schema can \
+        be introspected there",
+    params(GetRequest),
+    responses(
+        (status = 200, description = "GraphQL response", body = Object),
+        (status = 400, description = "Malformed or refused query, with the GraphQL errors", body = Object),
+    ),
+)]
+pub async fn get_graphql(
+    headers: HeaderMap,
+    Extension(schema): Extension<Arc<Schema>>,
+    Extension(context): Extension<graphql_context>,
+    Extension(admin): Extension<Admin>,
+    Extension(guard): Extension<Arc<Guard>>,
+    RawQuery(query): RawQuery,
+) -> Response {
+    let request = match rest::parse_graphql_query(query.as_deref()) {
+        Ok(request) => request,
+        Err(reply) => return reply.into_response(),
+    };
+    let context = new_graphql_context(&headers, &admin, context);
+    let reply = guard
+        .execute(&schema, &context, request.into_request())
+        .await;
+
+    to_graphql_response(reply)
+}
+
+#[utoipa::path(
+    post,
+    path = "/graphql",
+    tag = "graphql",
+    description = "Execute a GraphQL query or a batch of them, the body may also be a bare \
+        `application/graphql` query",
+    request_body = Request,
+    responses(
+        (status = 200, description = "GraphQL response", body = Object),
+        (status = 400, description = "Malformed or refused query, with the GraphQL errors", body = Object),
+    ),
+)]
+pub async fn post_graphql(
+    headers: HeaderMap,
+    Extension(schema): Extension<Arc<Schema>>,
+    Extension(context): Extension<graphql_context>,
+    Extension(admin): Extension<Admin>,
+    Extension(guard): Extension<Arc<Guard>>,
+    body: Bytes,
+) -> Response {
+    let context = new_graphql_context(&headers, &admin, context);
+    let content_type = headers
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok());
+    let request = BatchRequest::from_body(content_type, &body);
+
+    let reply = guard.execute(&schema, &context, request).await;
+
+    to_graphql_response(reply)
+}
+
+fn to_graphql_response(reply: GraphQLReply) -> Response {
+    if reply.ok {
+        (StatusCode::OK, Json(reply.response)).into_response()
+    } else {
+        (StatusCode::BAD_REQUEST, Json(reply.response)).into_response()
     }
 }
 
-pub async fn get_quote_handler(
-    query: Query<structs::UserID>,
-    quotes: State<Service>,
-) -> (StatusCode, Response) {
-    match quotes.get_quote(&query.user_id).await {
-        Ok(quote) => (StatusCode::OK, Json(quote).into_response()),
-        Err(err) => {
-            log::error!("failed to get quote: {err:#}");
-            (StatusCode::INTERNAL_SERVER_ERROR, "".into_response())
+pub async fn graphql_subscriptions(
+    headers: HeaderMap,
+    Extension(schema): Extension<Arc<Schema>>,
+    Extension(context): Extension<graphql_context>,
+    Extension(admin): Extension<Admin>,
+    Extension(guard): Extension<Arc<Guard>>,
+    ws: WebSocketUpgrade,
+) -> Response {
+    let config = ConnectionConfig::new(new_graphql_context(&headers, &admin, context));
+    ws.protocols([GRAPHQL_TRANSPORT_WS, GRAPHQL_WS])
+        .on_upgrade(move |socket| async move {
+            if socket.protocol().map(HeaderValue::as_bytes) == Some(GRAPHQL_WS.as_bytes()) {
+                serve_graphql_ws(socket, schema, config, guard).await
+            } else {
+                serve_graphql_transport_ws(socket, schema, config, guard).await
+            }
+        })
+}
+
+const GRAPHQL_TRANSPORT_WS: &str = "graphql-transport-ws";
+const GRAPHQL_WS: &str = "graphql-ws";
+
+async fn serve_graphql_transport_ws(
+    socket: WebSocket,
+    schema: Arc<Schema>,
+    config: ConnectionConfig<graphql_context>,
+    guard: Arc<Guard>,
+) {
+    let (ws_tx, ws_rx) = socket.split();
+    let connection = graphql_transport_ws::Connection::new(ArcSchema(schema.clone()), config);
+    let (s_tx, s_rx) = guard_transport_ws(connection, guard, schema);
+
+    let input = ws_rx
+        .filter_map(|message| future::ready(message.ok().map(|message| Ok(ClientMessage(message)))))
+        .forward(s_tx.sink_map_err(|err| match err {}));
+
+    let output = s_rx
+        .map(|output| {
+            Ok(match output {
+                graphql_transport_ws::Output::Message(message) => to_text_message(&message),
+                graphql_transport_ws::Output::Close { code, message } => {
+                    Message::Close(Some(CloseFrame {
+                        code,
+                        reason: message.into(),
+                    }))
+                }
+            })
+        })
+        .forward(ws_tx);
+
+    // Whichever side finishes first closes the connection, errors have nowhere to go.
+    let _ = future::select(input, output).await;
+}
+
+async fn serve_graphql_ws(
+    socket: WebSocket,
+    schema: Arc<Schema>,
+    config: ConnectionConfig<graphql_context>,
+    guard: Arc<Guard>,
+) {
+    let (ws_tx, ws_rx) = socket.split();
+    let connection = graphql_ws::Connection::new(ArcSchema(schema.clone()), config);
+    let (s_tx, s_rx) = guard_graphql_ws(connection, guard, schema);
+
+    let input = ws_rx
+        .filter_map(|message| future::ready(message.ok().map(|message| Ok(ClientMessage(message)))))
+        .forward(s_tx.sink_map_err(|err| match err {}));
+
+    let output = s_rx
+        .map(|message| Ok(to_text_message(&message)))
+        .forward(ws_tx);
+
+    // Whichever side finishes first closes the connection, errors have nowhere to go.
+    let _ = future::select(input, output).await;
+}
+
+fn to_text_message<T: serde::Serialize>(message: &T) -> Message {
+    serde_json::to_string(message)
+        .map(Message::text)
+        .unwrap_or_else(|err| {
+            Message::Close(Some(CloseFrame {
+                code: close_code::ERROR,
+                reason: format!("failed to serialize response: {err}").into(),
+            }))
+        })
+}
+
+/// Raw WebSocket message received from a subscriptions client.
+struct ClientMessage(Message);
+
+#[derive(Debug)]
+enum ClientMessageError {
+    Serde(serde_json::Error),
+    Unexpected(Message),
+}
+
+impl fmt::Display for ClientMessageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Serde(err) => write!(f, "failed to deserialize message: {err}"),
+            Self::Unexpected(message) => write!(f, "unexpected message: {message:?}"),
         }
     }
 }
 
-pub async fn like_quote_handler(
-    query: Query<structs::UserAndQuoteID>,
-    quotes: State<Service>,
-) -> StatusCode {
-    match quotes.like_quote(&query.user_id, &query.quote_id).await {
-        Ok(_) => StatusCode::OK,
-        Err(err) => {
-            log::error!("failed to like quote: {err:#}");
-            StatusCode::INTERNAL_SERVER_ERROR
+impl std::error::Error for ClientMessageError {}
+
+impl<S: ScalarValue> TryFrom<ClientMessage> for graphql_transport_ws::Input<S> {
+    type Error = ClientMessageError;
+
+    fn try_from(message: ClientMessage) -> Result<Self, Self::Error> {
+        match message.0 {
+            Message::Text(text) => serde_json::from_str(&text)
+                .map(Self::Message)
+                .map_err(ClientMessageError::Serde),
+            Message::Binary(bytes) => serde_json::from_slice(&bytes)
+                .map(Self::Message)
+                .map_err(ClientMessageError::Serde),
+            Message::Close(_) => Ok(Self::Close),
+            other => Err(ClientMessageError::Unexpected(other)),
         }
     }
 }
 
-pub async fn get_same_quote_handler(
-    query: Query<structs::UserAndQuoteID>,
-    quotes: State<Service>,
-) -> (StatusCode, Response) {
-    match quotes.get_same_quote(&query.user_id, &query.quote_id).await {
-        Ok(quote) => (StatusCode::OK, Json(quote).into_response()),
-        Err(err) => {
-            log::error!("failed to get same quote: {err:#}");
-            (StatusCode::INTERNAL_SERVER_ERROR, "".into_response())
+impl<S: ScalarValue> TryFrom<ClientMessage> for graphql_ws::ClientMessage<S> {
+    type Error = ClientMessageError;
+
+    fn try_from(message: ClientMessage) -> Result<Self, Self::Error> {
+        match message.0 {
+            Message::Text(text) => serde_json::from_str(&text).map_err(ClientMessageError::Serde),
+            Message::Binary(bytes) => {
+                serde_json::from_slice(&bytes).map_err(ClientMessageError::Serde)
+            }
+            Message::Close(_) => Ok(Self::ConnectionTerminate),
+            other => Err(ClientMessageError::Unexpected(other)),
         }
     }
 }
@@ -0,0 +1,325 @@
+use anyhow::{bail, Context, Result};
+use axum::http::{HeaderName, HeaderValue, Method};
+use std::collections::HashSet;
+use std::time::Duration;
+use tower_http::cors::{AllowHeaders, AllowOrigin, CorsLayer};
+
+use crate::config::CorsConfig;
+
+pub const ORIGIN: &str = "origin";
+pub const ACCESS_CONTROL_REQUEST_METHOD: &str = "access-control-request-method";
+pub const ACCESS_CONTROL_REQUEST_HEADERS: &str = "access-control-request-headers";
+
+/// Headers of a request the CORS policy depends on.
+pub struct CorsRequest<'a> {
+    pub method: &'a str,
+    pub origin: Option<&'a str>,
+    pub request_method: Option<&'a str>,
+    pub request_headers: Option<&'a str>,
+}
+
+/// Outcome of the CORS policy, applied as is by the actix, axum and rocket adapters.
+#[derive(Debug, PartialEq)]
+pub enum CorsReply {
+    /// Preflight request, answered right away without calling the handlers.
+    Preflight {
+        status: u16,
+        headers: Vec<(&'static str, String)>,
+    },
+    /// Headers appended to the response of the handler.
+    Headers(Vec<(&'static str, String)>),
+}
+
+/// CORS policy shared by the HTTP servers, `None` lists allow anything.
+#[derive(Clone)]
+pub struct Cors {
+    origins: Option<HashSet<String>>,
+    methods: Vec<String>,
+    headers: Option<Vec<String>>,
+    allow_credentials: bool,
+    max_age: u64,
+}
+
+impl Cors {
+    pub fn new(cfg: &CorsConfig) -> Result<Self> {
+        let origins = parse_list(&cfg.allowed_origins);
+        let methods: Vec<String> = parse_list(&cfg.allowed_methods)
+            .iter()
+            .map(|method| method.to_uppercase())
+            .collect();
+        let headers = parse_list(&cfg.allowed_headers);
+
+        if methods.is_empty() || methods.iter().any(|method| method == "*") {
+            bail!("CORS_ALLOWED_METHODS must list the allowed methods");
+        }
+        if let Some(origin) = origins.iter().find(|origin| origin.ends_with('/')) {
+            bail!("CORS allowed origin {origin} must not end with a slash");
+        }
+
+        Ok(Cors {
+            origins: (!origins.iter().any(|origin| origin == "*"))
+                .then(|| origins.into_iter().collect()),
+            methods,
+            headers: (!headers.iter().any(|header| header == "*")).then(|| {
+                headers
+                    .into_iter()
+                    .map(|header| header.to_lowercase())
+                    .collect()
+            }),
+            allow_credentials: cfg.allow_credentials,
+            max_age: cfg.max_age,
+        })
+    }
+
+    pub fn handle(&self, request: &CorsRequest) -> CorsReply {
+        // requests without an origin are not cross-origin ones
+        let Some(origin) = request.origin else {
+            return CorsReply::Headers(Vec::new());
+        };
+        let preflight =
+            request.method.eq_ignore_ascii_case("OPTIONS") && request.request_method.is_some();
+        let forbidden = || {
+            if preflight {
+                CorsReply::Preflight {
+                    status: 403,
+                    headers: Vec::new(),
+                }
+            } else {
+                CorsReply::Headers(Vec::new())
+            }
+        };
+
+        if !self.allows_origin(origin) {
+            return forbidden();
+        }
+
+        let mut headers = self.origin_headers(origin);
+        if !preflight {
+            return CorsReply::Headers(headers);
+        }
+
+        let method = request.request_method.unwrap_or_default();
+        if !self.methods.iter().any(|allowed| allowed == method) {
+            return forbidden();
+        }
+
+        let requested = parse_list(request.request_headers.unwrap_or_default());
+        let allowed_headers = match &self.headers {
+            // mirrored, a `*` value would not cover the `Authorization` header
+            None => requested.join(", "),
+            Some(allowed) => {
+                if !requested
+                    .iter()
+                    .all(|header| allowed.contains(&header.to_lowercase()))
+                {
+                    return forbidden();
+                }
+                allowed.join(", ")
+            }
+        };
+
+        headers.push(("access-control-allow-methods", self.methods.join(", ")));
+        if !allowed_headers.is_empty() {
+            headers.push(("access-control-allow-headers", allowed_headers));
+        }
+        if self.max_age > 0 {
+            headers.push(("access-control-max-age", self.max_age.to_string()));
+        }
+
+        CorsReply::Preflight {
+            status: 204,
+            headers,
+        }
+    }
+
+    /// Same policy as a tower layer, for the gRPC server whose services are not axum handlers.
+    pub fn layer(&self, expose_headers: &[&'static str]) -> Result<CorsLayer> {
+        let origin = match &self.origins {
+            // any origin is answered with `*` unless credentials are allowed
+            None if self.allow_credentials => AllowOrigin::mirror_request(),
+            None => AllowOrigin::any(),
+            Some(origins) => AllowOrigin::list(
+                origins
+                    .iter()
+                    .map(|origin| HeaderValue::from_str(origin))
+                    .collect::<Result<Vec<_>, _>>()
+                    .context("failed to parse allowed origins")?,
+            ),
+        };
+        let methods = self
+            .methods
+            .iter()
+            .map(|method| Method::from_bytes(method.as_bytes()))
+            .collect::<Result<Vec<_>, _>>()
+            .context("failed to parse allowed methods")?;
+        let headers = match &self.headers {
+            None => AllowHeaders::mirror_request(),
+            Some(headers) => AllowHeaders::list(
+                headers
+                    .iter()
+                    .map(|header| HeaderName::from_bytes(header.as_bytes()))
+                    .collect::<Result<Vec<_>, _>>()
+                    .context("failed to parse allowed headers")?,
+            ),
+        };
+
+        let layer = CorsLayer::new()
+            .allow_origin(origin)
+            .allow_methods(methods)
+            .allow_headers(headers)
+            .allow_credentials(self.allow_credentials)
+            .expose_headers(
+                expose_headers
+                    .iter()
+                    .map(|header| HeaderName::from_static(header))
+                    .collect::<Vec<_>>(),
+            );
+
+        Ok(match self.max_age {
+            0 => layer,
+            max_age => layer.max_age(Duration::from_secs(max_age)),
+        })
+    }
+
+    fn allows_origin(&self, origin: &str) -> bool {
+        self.origins
+            .as_ref()
+            .is_none_or(|origins| origins.contains(origin))
+    }
+
+    /// The origin is echoed back unless any origin is allowed without credentials.
+    fn origin_headers(&self, origin: &str) -> Vec<(&'static str, String)> {
+        if self.origins.is_none() && !self.allow_credentials {
+            return vec![("access-control-allow-origin", "*".to_string())];
+        }
+
+        let mut headers = vec![
+            ("access-control-allow-origin", origin.to_string()),
+            ("vary", "Origin".to_string()),
+        ];
+        if self.allow_credentials {
+            headers.push(("access-control-allow-credentials", "true".to_string()));
+        }
+
+        headers
+    }
+}
+
+fn parse_list(list: &str) -> Vec<String> {
+    list.split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn new_cors(origins: &str, headers: &str, allow_credentials: bool) -> Cors {
+        Cors::new(&CorsConfig {
+            allowed_origins: origins.to_string(),
+            allowed_methods: "get, put".to_string(),
+            allowed_headers: headers.to_string(),
+            allow_credentials,
+            max_age: 60,
+        })
+        .unwrap()
+    }
+
+    fn preflight<'a>(origin: &'a str, method: &'a str, headers: &'a str) -> CorsRequest<'a> {
+        CorsRequest {
+            method: "OPTIONS",
+            origin: Some(origin),
+            request_method: Some(method),
+            request_headers: Some(headers),
+        }
+    }
+
+    #[test]
+    fn test_layer() {
+        // tower refuses credentials along with wildcards, they are mirrored instead
+        for (origins, headers) in [
+            ("*", "*"),
+            ("https://a.dev, https://b.dev", "authorization"),
+        ] {
+            assert!(new_cors(origins, headers, true)
+                .layer(&["grpc-status"])
+                .is_ok());
+        }
+    }
+
+    #[test]
+    fn test_preflight() {
+        let cors = new_cors("*", "*", false);
+
+        assert_eq!(
+            cors.handle(&preflight("https://a.dev", "PUT", "authorization")),
+            CorsReply::Preflight {
+                status: 204,
+                headers: vec![
+                    ("access-control-allow-origin", "*".to_string()),
+                    ("access-control-allow-methods", "GET, PUT".to_string()),
+                    ("access-control-allow-headers", "authorization".to_string()),
+                    ("access-control-max-age", "60".to_string()),
+                ],
+            }
+        );
+        assert_eq!(
+            cors.handle(&preflight("https://a.dev", "DELETE", "")),
+            CorsReply::Preflight {
+                status: 403,
+                headers: Vec::new(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_allowed_origins() {
+        let cors = new_cors("https://a.dev, https://b.dev", "content-type", true);
+        let request = |origin| CorsRequest {
+            method: "GET",
+            origin,
+            request_method: None,
+            request_headers: None,
+        };
+
+        assert_eq!(
+            cors.handle(&request(Some("https://b.dev"))),
+            CorsReply::Headers(vec![
+                ("access-control-allow-origin", "https://b.dev".to_string()),
+                ("vary", "Origin".to_string()),
+                ("access-control-allow-credentials", "true".to_string()),
+            ])
+        );
+        assert_eq!(
+            cors.handle(&request(Some("https://c.dev"))),
+            CorsReply::Headers(Vec::new())
+        );
+        assert_eq!(cors.handle(&request(None)), CorsReply::Headers(Vec::new()));
+
+        let CorsReply::Preflight { status, .. } =
+            cors.handle(&preflight("https://a.dev", "GET", "Authorization"))
+        else {
+            panic!("preflight is not answered");
+        };
+        assert_eq!(status, 403);
+    }
+
+    #[test]
+    fn test_invalid_config() {
+        let cfg = CorsConfig {
+            allowed_origins: "https://a.dev/".to_string(),
+            allowed_methods: "GET".to_string(),
+            ..CorsConfig::default()
+        };
+        assert!(Cors::new(&cfg).is_err());
+
+        let cfg = CorsConfig {
+            allowed_methods: "*".to_string(),
+            ..CorsConfig::default()
+        };
+        assert!(Cors::new(&cfg).is_err());
+    }
+}
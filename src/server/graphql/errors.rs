@@ -0,0 +1,60 @@
+use juniper::{graphql_value, FieldError};
+use std::fmt::Display;
+
+use crate::server::structs::is_not_found;
+
+/// Machine readable kind of a field error, sent as the `code` extension.
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum Code {
+    BadRequest,
+    Unauthorized,
+    NotFound,
+    Internal,
+    QueryTooLarge,
+    QueryTooDeep,
+    QueryTooComplex,
+    BatchTooLarge,
+    PersistedQueryNotFound,
+    PersistedQueryRequired,
+}
+
+impl Code {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Code::BadRequest => "BAD_REQUEST",
+            Code::Unauthorized => "UNAUTHORIZED",
+            Code::NotFound => "NOT_FOUND",
+            Code::Internal => "INTERNAL",
+            Code::QueryTooLarge => "QUERY_TOO_LARGE",
+            Code::QueryTooDeep => "QUERY_TOO_DEEP",
+            Code::QueryTooComplex => "QUERY_TOO_COMPLEX",
+            Code::BatchTooLarge => "BATCH_TOO_LARGE",
+            Code::PersistedQueryNotFound => "PERSISTED_QUERY_NOT_FOUND",
+            Code::PersistedQueryRequired => "PERSISTED_QUERY_REQUIRED",
+        }
+    }
+}
+
+pub fn new(code: Code, message: impl Display) -> FieldError {
+    FieldError::new(message, graphql_value!({ "code": code.as_str() }))
+}
+
+/// Invalid client input, the message explains what is wrong with it.
+pub fn bad_request(err: anyhow::Error) -> FieldError {
+    new(Code::BadRequest, format!("{err:#}"))
+}
+
+pub fn unauthorized() -> FieldError {
+    new(Code::Unauthorized, "unauthorized")
+}
+
+/// Maps a service error: missing entities become `NOT_FOUND`, anything else is logged
+/// and hidden from the client behind `INTERNAL`.
+pub fn from_service(err: anyhow::Error, action: &str) -> FieldError {
+    if is_not_found(&err) {
+        return new(Code::NotFound, "not found");
+    }
+
+    log::error!("failed to {action}: {err:#}");
+    new(Code::Internal, format!("failed to {action}"))
+}
@@ -0,0 +1,309 @@
+use anyhow::Result;
+use futures::future;
+use juniper::http::{GraphQLBatchResponse, GraphQLRequest, GraphQLResponse};
+use juniper::{FieldError, FieldResult, InputValue, Variables};
+use serde::Deserialize;
+use utoipa::{IntoParams, ToSchema};
+
+use super::errors::{self, Code};
+use super::limits::Limits;
+use super::persisted::PersistedQueries;
+use super::quotes_resolver::{Context, Schema};
+use crate::config::GraphQLConfig;
+
+/// GraphQL request which may reference a persisted query instead of carrying it, either
+/// with `id` or with the `persistedQuery` extension.
+#[derive(Deserialize, Debug, Default, ToSchema)]
+#[serde(rename_all = "camelCase")]
+#[schema(as = GraphQLRequest)]
+pub struct Request {
+    pub query: Option<String>,
+    /// sha256 hash of a persisted query
+    pub id: Option<String>,
+    pub operation_name: Option<String>,
+    #[schema(value_type = Option<Object>)]
+    pub variables: Option<InputValue>,
+    /// `persistedQuery.sha256Hash` references a persisted query like `id`
+    #[schema(value_type = Option<Object>)]
+    pub extensions: Option<Extensions>,
+}
+
+#[derive(Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct Extensions {
+    pub persisted_query: Option<PersistedQuery>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct PersistedQuery {
+    pub sha256_hash: String,
+}
+
+impl Request {
+    fn persisted_hash(&self) -> Option<&str> {
+        self.id.as_deref().or_else(|| {
+            self.extensions
+                .as_ref()?
+                .persisted_query
+                .as_ref()
+                .map(|persisted| persisted.sha256_hash.as_str())
+        })
+    }
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(untagged)]
+pub enum BatchRequest {
+    Single(Request),
+    Batch(Vec<Request>),
+}
+
+impl BatchRequest {
+    /// Parses a POST body, either JSON or a bare `application/graphql` query.
+    pub fn from_body(content_type: Option<&str>, body: &[u8]) -> FieldResult<Self> {
+        if content_type.is_some_and(|content_type| content_type.starts_with("application/graphql"))
+        {
+            let query =
+                String::from_utf8(body.to_vec()).map_err(|err| errors::bad_request(err.into()))?;
+            return Ok(BatchRequest::Single(Request {
+                query: Some(query),
+                ..Request::default()
+            }));
+        }
+
+        serde_json::from_slice(body).map_err(|err| errors::bad_request(err.into()))
+    }
+}
+
+/// Query string of a GET request, the variables and extensions are JSON encoded.
+#[derive(Deserialize, Debug, Default, IntoParams)]
+#[serde(rename_all = "camelCase")]
+#[into_params(parameter_in = Query)]
+pub struct GetRequest {
+    query: Option<String>,
+    /// sha256 hash of a persisted query
+    id: Option<String>,
+    operation_name: Option<String>,
+    /// JSON encoded variables
+    variables: Option<String>,
+    /// JSON encoded extensions
+    extensions: Option<String>,
+}
+
+impl GetRequest {
+    pub fn into_request(self) -> FieldResult<BatchRequest> {
+        let variables = self
+            .variables
+            .map(|variables| serde_json::from_str(&variables))
+            .transpose()
+            .map_err(|err| errors::bad_request(err.into()))?;
+        let extensions = self
+            .extensions
+            .map(|extensions| serde_json::from_str(&extensions))
+            .transpose()
+            .map_err(|err| errors::bad_request(err.into()))?;
+
+        Ok(BatchRequest::Single(Request {
+            query: self.query,
+            id: self.id,
+            operation_name: self.operation_name,
+            variables,
+            extensions,
+        }))
+    }
+}
+
+/// Response to a GraphQL request, not `ok` when the request was malformed or refused
+/// which the transports answer with a bad request status.
+pub struct Reply {
+    pub response: GraphQLBatchResponse,
+    pub ok: bool,
+}
+
+/// Resolves the persisted queries and enforces the limits before executing a request.
+pub struct Guard {
+    limits: Limits,
+    persisted: PersistedQueries,
+    /// Operations accepted in a batch, zero accepts any number.
+    max_batch_size: usize,
+}
+
+impl Guard {
+    pub fn new(cfg: &GraphQLConfig) -> Result<Self> {
+        Ok(Guard {
+            limits: Limits::new(cfg),
+            persisted: PersistedQueries::new(cfg)?,
+            max_batch_size: cfg.max_batch_size,
+        })
+    }
+
+    pub async fn execute(
+        &self,
+        schema: &Schema,
+        context: &Context,
+        request: FieldResult<BatchRequest>,
+    ) -> Reply {
+        match request {
+            Ok(BatchRequest::Single(request)) => {
+                let (response, ok) = self.execute_one(schema, context, request).await;
+                Reply {
+                    response: GraphQLBatchResponse::Single(response),
+                    ok,
+                }
+            }
+            Ok(BatchRequest::Batch(requests)) => {
+                // the operations run concurrently, nothing runs when there are too many
+                if let Err(err) = self.check_batch(&requests) {
+                    return Reply {
+                        response: GraphQLBatchResponse::Single(GraphQLResponse::error(err)),
+                        ok: false,
+                    };
+                }
+
+                let (responses, oks): (Vec<_>, Vec<_>) = future::join_all(
+                    requests
+                        .into_iter()
+                        .map(|request| self.execute_one(schema, context, request)),
+                )
+                .await
+                .into_iter()
+                .unzip();
+
+                Reply {
+                    response: GraphQLBatchResponse::Batch(responses),
+                    ok: oks.into_iter().all(|ok| ok),
+                }
+            }
+            Err(err) => Reply {
+                response: GraphQLBatchResponse::Single(GraphQLResponse::error(err)),
+                ok: false,
+            },
+        }
+    }
+
+    fn check_batch(&self, requests: &[Request]) -> FieldResult<()> {
+        if self.max_batch_size > 0 && requests.len() > self.max_batch_size {
+            return Err(errors::new(
+                Code::BatchTooLarge,
+                format!(
+                    "batch has {} operations, the maximum is {}",
+                    requests.len(),
+                    self.max_batch_size
+                ),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Response and whether the request was valid.
+    async fn execute_one(
+        &self,
+        schema: &Schema,
+        context: &Context,
+        request: Request,
+    ) -> (GraphQLResponse, bool) {
+        match self.prepare(schema, request) {
+            Ok(request) => {
+                let response = request.execute(schema, context).await;
+                let ok = response.is_ok();
+                (response, ok)
+            }
+            Err(err) => (GraphQLResponse::error(err), false),
+        }
+    }
+
+    fn prepare(&self, schema: &Schema, request: Request) -> Result<GraphQLRequest, FieldError> {
+        let hash = request.persisted_hash().map(str::to_string);
+        let mut prepared =
+            GraphQLRequest::new(String::new(), request.operation_name, request.variables);
+        prepared.query = self.prepare_operation(
+            schema,
+            hash.as_deref(),
+            request.query,
+            prepared.operation_name.as_deref(),
+            &prepared.variables(),
+        )?;
+
+        Ok(prepared)
+    }
+
+    /// Query to execute for an operation, resolved from the persisted ones and checked
+    /// against the limits.
+    pub fn prepare_operation(
+        &self,
+        schema: &Schema,
+        hash: Option<&str>,
+        query: Option<String>,
+        operation_name: Option<&str>,
+        variables: &Variables,
+    ) -> FieldResult<String> {
+        let query = self.persisted.resolve(hash, query)?;
+
+        self.limits
+            .check(&schema.schema, &query, operation_name, variables)?;
+
+        if let Some(hash) = hash {
+            self.persisted.register(hash, &query);
+        }
+
+        Ok(query)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_body_reads_persisted_hashes() {
+        let body = r#"[
+            {"id": "abc"},
+            {"extensions": {"persistedQuery": {"version": 1, "sha256Hash": "def"}}},
+            {"query": "{ version }", "operationName": "version"}
+        ]"#;
+
+        let Ok(BatchRequest::Batch(requests)) =
+            BatchRequest::from_body(Some("application/json"), body.as_bytes())
+        else {
+            panic!("failed to parse batch");
+        };
+
+        let hashes: Vec<Option<&str>> = requests.iter().map(Request::persisted_hash).collect();
+        assert_eq!(hashes, vec![Some("abc"), Some("def"), None]);
+        assert_eq!(requests[2].operation_name.as_deref(), Some("version"));
+    }
+
+    #[test]
+    fn test_check_batch_rejects_large_batches() {
+        let guard = Guard::new(&GraphQLConfig {
+            max_batch_size: 2,
+            ..GraphQLConfig::default()
+        })
+        .unwrap();
+        let batch =
+            |size: usize| -> Vec<Request> { (0..size).map(|_| Request::default()).collect() };
+
+        assert!(guard.check_batch(&batch(2)).is_ok());
+        let err = guard.check_batch(&batch(3)).unwrap_err();
+        assert_eq!(
+            err.extensions(),
+            &juniper::graphql_value!({ "code": "BATCH_TOO_LARGE" })
+        );
+
+        let unlimited = Guard::new(&GraphQLConfig::default()).unwrap();
+        assert!(unlimited.check_batch(&batch(100)).is_ok());
+    }
+
+    #[test]
+    fn test_from_body_reads_bare_queries() {
+        let Ok(BatchRequest::Single(request)) =
+            BatchRequest::from_body(Some("application/graphql"), b"{ version }")
+        else {
+            panic!("failed to parse query");
+        };
+
+        assert_eq!(request.query.as_deref(), Some("{ version }"));
+    }
+}
@@ -0,0 +1,100 @@
+//! Result wrappers of the handler named fields, kept for the clients that have not
+//! moved to the idiomatic fields yet.
+
+use juniper::GraphQLObject;
+
+use crate::catalogue::structs as catalogue_structs;
+use crate::quote::structs as quote_structs;
+
+#[derive(GraphQLObject)]
+pub struct LegacyQuote {
+    pub id: String,
+    pub quote: String,
+    pub author: String,
+    pub tags: Vec<String>,
+    pub likes: i32,
+    pub source: String,
+    pub external_id: Option<String>,
+}
+
+impl From<quote_structs::Quote> for LegacyQuote {
+    fn from(quote: quote_structs::Quote) -> Self {
+        LegacyQuote {
+            id: quote.id,
+            quote: quote.quote,
+            author: quote.author,
+            tags: quote.tags,
+            likes: quote.likes,
+            source: quote.source,
+            external_id: quote.external_id,
+        }
+    }
+}
+
+#[derive(GraphQLObject)]
+pub struct QuoteResult {
+    pub success: bool,
+    pub errors: Vec<String>,
+    pub quote: Option<LegacyQuote>,
+}
+
+#[derive(GraphQLObject)]
+pub struct QuotesResult {
+    pub success: bool,
+    pub errors: Vec<String>,
+    pub quotes: Vec<LegacyQuote>,
+}
+
+#[derive(GraphQLObject)]
+pub struct EmptyResult {
+    pub success: bool,
+    pub errors: Vec<String>,
+}
+
+#[derive(GraphQLObject)]
+pub struct LegacyTag {
+    pub name: String,
+    pub quotes: i32,
+}
+
+impl From<catalogue_structs::Tag> for LegacyTag {
+    fn from(tag: catalogue_structs::Tag) -> Self {
+        LegacyTag {
+            name: tag.name,
+            quotes: tag.quotes as i32,
+        }
+    }
+}
+
+#[derive(GraphQLObject)]
+pub struct TagsResult {
+    pub success: bool,
+    pub errors: Vec<String>,
+    pub tags: Vec<LegacyTag>,
+    pub total: i32,
+}
+
+#[derive(GraphQLObject)]
+pub struct LegacyAuthor {
+    pub name: String,
+    pub quotes: i32,
+    pub likes: i32,
+}
+
+impl From<catalogue_structs::Author> for LegacyAuthor {
+    fn from(author: catalogue_structs::Author) -> Self {
+        LegacyAuthor {
+            name: author.name,
+            quotes: author.quotes as i32,
+            likes: author.likes as i32,
+        }
+    }
+}
+
+#[derive(GraphQLObject)]
+pub struct AuthorsResult {
+    pub success: bool,
+    pub errors: Vec<String>,
+    pub authors: Vec<LegacyAuthor>,
+    pub total: i32,
+}
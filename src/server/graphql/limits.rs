@@ -0,0 +1,562 @@
+use juniper::parser::parse_document_source;
+use juniper::{
+    DefaultScalarValue, Definition, FieldResult, InputValue, OperationType, SchemaType, Selection,
+    Variables,
+};
+use std::collections::HashMap;
+
+use super::errors::{self, Code};
+use crate::config::GraphQLConfig;
+
+/// Cost of the fields hitting the database, keyed by the parent type. Other fields cost 1
+/// when they return an object and nothing when they return a scalar.
+const FIELD_COSTS: &[(&str, &str, u64)] = &[
+    ("Query", "heartbeat", 2),
+    ("Query", "quote", 1),
+    ("Query", "history", 5),
+    ("Query", "randomQuote", 5),
+    ("Query", "randomQuotes", 5),
+    ("Query", "similarQuotes", 10),
+    ("Query", "quoteOfTheDay", 5),
+    ("Query", "tag", 2),
+    ("Query", "tags", 5),
+    ("Query", "author", 2),
+    ("Query", "authors", 5),
+    ("Query", "reviewQueue", 10),
+    ("Query", "moderationAudit", 5),
+    ("Query", "get_quote_handler", 5),
+    ("Query", "get_quotes_handler", 5),
+    ("Query", "get_same_quote_handler", 10),
+    ("Query", "get_quote_of_the_day_handler", 5),
+    ("Query", "get_tags_handler", 5),
+    ("Query", "get_authors_handler", 5),
+    ("Mutation", "likeQuote", 10),
+    ("Mutation", "dislikeQuote", 10),
+    ("Mutation", "skipQuote", 10),
+    ("Mutation", "setQuoteOfTheDay", 10),
+    ("Mutation", "reportQuote", 10),
+    ("Mutation", "moderateQuote", 10),
+    ("Mutation", "like_quote_handler", 10),
+    ("Mutation", "set_quote_of_the_day_handler", 10),
+    ("Quote", "views", 2),
+    ("Quote", "liked", 2),
+    ("Author", "quoteCount", 2),
+    ("Author", "likes", 2),
+    ("Author", "quotes", 10),
+    ("Tag", "quoteCount", 2),
+    ("Tag", "quotes", 10),
+];
+
+/// Arguments setting the number of items returned by a field.
+const PAGE_ARGUMENTS: [&str; 2] = ["limit", "count"];
+
+/// Largest default page size of the paginated fields.
+const DEFAULT_PAGE_SIZE: u64 = 20;
+
+/// Depth of the introspection selections, the introspection query of the explorers nests
+/// 13 to 15 levels depending on their version.
+const MAX_INTROSPECTION_DEPTH: usize = 20;
+
+/// Depth and complexity of an operation. Introspection resolves in memory, it has its
+/// own depth and costs nothing so that the explorers keep working under tight limits.
+#[derive(PartialEq, Debug, Clone, Copy, Default)]
+pub struct Cost {
+    pub depth: usize,
+    pub complexity: u64,
+    pub introspection_depth: usize,
+}
+
+impl Cost {
+    /// Cost of sibling selections.
+    fn merge(self, other: Cost) -> Cost {
+        Cost {
+            depth: self.depth.max(other.depth),
+            complexity: self.complexity.saturating_add(other.complexity),
+            introspection_depth: self.introspection_depth.max(other.introspection_depth),
+        }
+    }
+}
+
+/// Limits checked before a query is executed, a zero limit is disabled.
+#[derive(Debug, Clone, Default)]
+pub struct Limits {
+    pub max_depth: usize,
+    pub max_complexity: u64,
+    pub max_query_size: usize,
+}
+
+impl Limits {
+    pub fn new(cfg: &GraphQLConfig) -> Self {
+        Limits {
+            max_depth: cfg.max_depth,
+            max_complexity: cfg.max_complexity,
+            max_query_size: cfg.max_query_size,
+        }
+    }
+
+    pub fn check(
+        &self,
+        schema: &SchemaType<DefaultScalarValue>,
+        query: &str,
+        operation_name: Option<&str>,
+        variables: &Variables,
+    ) -> FieldResult<()> {
+        if self.max_query_size > 0 && query.len() > self.max_query_size {
+            return Err(errors::new(
+                Code::QueryTooLarge,
+                format!(
+                    "query is {} bytes long, the maximum is {}",
+                    query.len(),
+                    self.max_query_size
+                ),
+            ));
+        }
+
+        // syntax errors are reported by the executor like without limits
+        let Some(cost) = cost(schema, query, operation_name, variables) else {
+            return Ok(());
+        };
+
+        if self.max_depth > 0 && cost.depth > self.max_depth {
+            return Err(errors::new(
+                Code::QueryTooDeep,
+                format!(
+                    "query depth is {}, the maximum is {}",
+                    cost.depth, self.max_depth
+                ),
+            ));
+        }
+
+        if cost.introspection_depth > MAX_INTROSPECTION_DEPTH {
+            return Err(errors::new(
+                Code::QueryTooDeep,
+                format!(
+                    "introspection depth is {}, the maximum is {MAX_INTROSPECTION_DEPTH}",
+                    cost.introspection_depth
+                ),
+            ));
+        }
+
+        if self.max_complexity > 0 && cost.complexity > self.max_complexity {
+            return Err(errors::new(
+                Code::QueryTooComplex,
+                format!(
+                    "query complexity is {}, the maximum is {}",
+                    cost.complexity, self.max_complexity
+                ),
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+/// Cost of the operation to execute, of the costliest one when no name is given.
+/// Returns `None` when the query does not parse.
+pub fn cost(
+    schema: &SchemaType<DefaultScalarValue>,
+    query: &str,
+    operation_name: Option<&str>,
+    variables: &Variables,
+) -> Option<Cost> {
+    let document = parse_document_source(query, schema).ok()?;
+
+    let fragments = document
+        .iter()
+        .filter_map(|definition| match definition {
+            Definition::Fragment(fragment) => Some((
+                fragment.item.name.item,
+                (
+                    fragment.item.type_condition.item,
+                    fragment.item.selection_set.as_slice(),
+                ),
+            )),
+            Definition::Operation(_) => None,
+        })
+        .collect();
+
+    let mut analyzer = Analyzer {
+        schema,
+        fragments,
+        variables,
+        spreads: vec![],
+    };
+
+    let mut total = Cost::default();
+    for definition in &document {
+        let Definition::Operation(operation) = definition else {
+            continue;
+        };
+        let operation = &operation.item;
+        let name = operation.name.as_ref().map(|name| name.item);
+        if operation_name.is_some() && name != operation_name {
+            continue;
+        }
+
+        let root = match operation.operation_type {
+            OperationType::Query => Some(schema.concrete_query_type()),
+            OperationType::Mutation => schema.concrete_mutation_type(),
+            OperationType::Subscription => schema.concrete_subscription_type(),
+        };
+        let Some(root) = root.and_then(|root| root.name()) else {
+            continue;
+        };
+
+        let cost = analyzer.selections(root, &operation.selection_set);
+        total = Cost {
+            depth: total.depth.max(cost.depth),
+            complexity: total.complexity.max(cost.complexity),
+            introspection_depth: total.introspection_depth.max(cost.introspection_depth),
+        };
+    }
+
+    Some(total)
+}
+
+type Selections<'a> = [Selection<'a, DefaultScalarValue>];
+
+struct Analyzer<'a> {
+    schema: &'a SchemaType<'a, DefaultScalarValue>,
+    /// Type condition and selections of the fragments by name.
+    fragments: HashMap<&'a str, (&'a str, &'a Selections<'a>)>,
+    variables: &'a Variables,
+    /// Fragments being expanded, guards against cycles which the executor rejects later.
+    spreads: Vec<&'a str>,
+}
+
+impl<'a> Analyzer<'a> {
+    fn selections(&mut self, type_name: &str, selections: &'a Selections<'a>) -> Cost {
+        let schema = self.schema;
+        let mut total = Cost::default();
+
+        for selection in selections {
+            let cost = match selection {
+                Selection::Field(field) => {
+                    let field = &field.item;
+                    let name = field.name.item;
+                    if name.starts_with("__") {
+                        let nested = field
+                            .selection_set
+                            .as_ref()
+                            .map_or(0, |nested| self.introspection_depth(nested));
+                        total = total.merge(Cost {
+                            introspection_depth: nested + 1,
+                            ..Cost::default()
+                        });
+                        continue;
+                    }
+                    // unknown fields are reported by the validation
+                    let Some(meta) = schema
+                        .concrete_type_by_name(type_name)
+                        .and_then(|parent| parent.field_by_name(name))
+                    else {
+                        continue;
+                    };
+
+                    let nested = match &field.selection_set {
+                        Some(nested) => self.selections(meta.field_type.innermost_name(), nested),
+                        None => Cost::default(),
+                    };
+
+                    let takes_page = meta
+                        .arguments
+                        .iter()
+                        .flatten()
+                        .any(|argument| PAGE_ARGUMENTS.contains(&argument.name.as_str()));
+                    let page_size = if takes_page {
+                        field
+                            .arguments
+                            .iter()
+                            .flat_map(|arguments| arguments.item.items.iter())
+                            .find(|(argument, _)| PAGE_ARGUMENTS.contains(&argument.item))
+                            .and_then(|(_, value)| self.int_value(&value.item))
+                            .map_or(DEFAULT_PAGE_SIZE, |size| size.max(0) as u64)
+                    } else {
+                        1
+                    };
+
+                    Cost {
+                        depth: nested.depth + 1,
+                        complexity: field_cost(type_name, name, field.selection_set.is_some())
+                            .saturating_add(page_size.saturating_mul(nested.complexity)),
+                        introspection_depth: nested.introspection_depth,
+                    }
+                }
+                Selection::InlineFragment(fragment) => {
+                    let fragment = &fragment.item;
+                    let type_name = fragment
+                        .type_condition
+                        .as_ref()
+                        .map_or(type_name, |condition| condition.item);
+
+                    self.selections(type_name, &fragment.selection_set)
+                }
+                Selection::FragmentSpread(spread) => {
+                    let name = spread.item.name.item;
+                    match self.fragments.get(name).copied() {
+                        Some((type_condition, nested)) if !self.spreads.contains(&name) => {
+                            self.spreads.push(name);
+                            let cost = self.selections(type_condition, nested);
+                            self.spreads.pop();
+                            cost
+                        }
+                        _ => continue,
+                    }
+                }
+            };
+
+            total = total.merge(cost);
+        }
+
+        total
+    }
+
+    /// Depth of introspection selections, their types are not looked up in the schema.
+    fn introspection_depth(&mut self, selections: &'a Selections<'a>) -> usize {
+        let mut depth = 0;
+
+        for selection in selections {
+            let nested = match selection {
+                Selection::Field(field) => {
+                    let nested = field.item.selection_set.as_ref();
+                    nested.map_or(0, |nested| self.introspection_depth(nested)) + 1
+                }
+                Selection::InlineFragment(fragment) => {
+                    self.introspection_depth(&fragment.item.selection_set)
+                }
+                Selection::FragmentSpread(spread) => {
+                    let name = spread.item.name.item;
+                    match self.fragments.get(name).copied() {
+                        Some((_, nested)) if !self.spreads.contains(&name) => {
+                            self.spreads.push(name);
+                            let nested = self.introspection_depth(nested);
+                            self.spreads.pop();
+                            nested
+                        }
+                        _ => continue,
+                    }
+                }
+            };
+
+            depth = depth.max(nested);
+        }
+
+        depth
+    }
+
+    fn int_value(&self, value: &InputValue) -> Option<i32> {
+        match value {
+            InputValue::Variable(name) => self.variables.get(name)?.as_int_value(),
+            value => value.as_int_value(),
+        }
+    }
+}
+
+fn field_cost(type_name: &str, field_name: &str, is_object: bool) -> u64 {
+    FIELD_COSTS
+        .iter()
+        .find(|(parent, field, _)| *parent == type_name && *field == field_name)
+        .map_or(u64::from(is_object), |(_, _, cost)| *cost)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::quotes_resolver::{Mutation, Query, Schema, Subscription};
+    use super::*;
+
+    fn schema() -> Schema {
+        Schema::new(Query, Mutation, Subscription)
+    }
+
+    fn cost_of(query: &str) -> Cost {
+        cost(&schema().schema, query, None, &Variables::new()).unwrap()
+    }
+
+    #[test]
+    fn test_cost_counts_nested_fields() {
+        let cost = cost_of(r#"{ quote(id: "1") { text author { name quoteCount } } }"#);
+
+        assert_eq!(
+            cost,
+            Cost {
+                depth: 3,
+                complexity: 1 + 1 + 2,
+                ..Cost::default()
+            }
+        );
+    }
+
+    #[test]
+    fn test_cost_multiplies_pages() {
+        let query = r#"query ($limit: Int) {
+            author(name: "ada") { quotes(limit: $limit) { views tags { name } } }
+            tags { items { quoteCount } }
+        }"#;
+        let variables = Variables::from([("limit".to_string(), InputValue::scalar(3))]);
+
+        let cost = cost(&schema().schema, query, None, &variables).unwrap();
+
+        assert_eq!(
+            cost,
+            Cost {
+                depth: 4,
+                complexity: (2 + 10 + 3 * (2 + 1)) + (5 + DEFAULT_PAGE_SIZE * (1 + 2)),
+                ..Cost::default()
+            }
+        );
+    }
+
+    #[test]
+    fn test_cost_expands_fragments() {
+        let query = r#"
+            query { randomQuote(userId: "1") { ...quote ... on Quote { author { name } } } }
+            fragment quote on Quote { text tags { ...tag } }
+            fragment tag on Tag { name quotes { text } }
+        "#;
+
+        let cost = cost_of(query);
+
+        assert_eq!(
+            cost,
+            Cost {
+                depth: 4,
+                complexity: 5 + 1 + 10 + 1,
+                ..Cost::default()
+            }
+        );
+    }
+
+    #[test]
+    fn test_cost_stops_at_fragment_cycles() {
+        let query = r#"
+            query { randomQuote(userId: "1") { ...quote } }
+            fragment quote on Quote { tags { quotes { ...quote } } }
+        "#;
+
+        let cost = cost_of(query);
+
+        assert_eq!(cost.depth, 3);
+    }
+
+    #[test]
+    fn test_cost_measures_introspection_apart() {
+        let query = r#"
+            { __schema { types { ...type } } quote(id: "1") { __typename text } }
+            fragment type on __Type { fields { type { ofType { name } } } }
+        "#;
+
+        assert_eq!(
+            cost_of(query),
+            Cost {
+                depth: 2,
+                complexity: 1,
+                introspection_depth: 6
+            }
+        );
+    }
+
+    #[test]
+    fn test_check_bounds_introspection() {
+        let limits = Limits {
+            max_depth: 2,
+            ..Limits::default()
+        };
+        let introspection = |depth: usize| {
+            format!(
+                "{{ __schema {{ types {{ {}name{} }} }} }}",
+                "ofType { ".repeat(depth - 3),
+                " }".repeat(depth - 3)
+            )
+        };
+
+        assert!(limits
+            .check(
+                &schema().schema,
+                &introspection(MAX_INTROSPECTION_DEPTH),
+                None,
+                &Variables::new()
+            )
+            .is_ok());
+
+        let err = limits
+            .check(
+                &schema().schema,
+                &introspection(MAX_INTROSPECTION_DEPTH + 1),
+                None,
+                &Variables::new(),
+            )
+            .unwrap_err();
+        assert_eq!(
+            err.message(),
+            "introspection depth is 21, the maximum is 20"
+        );
+    }
+
+    #[test]
+    fn test_check_rejects_deep_queries() {
+        let limits = Limits {
+            max_depth: 2,
+            ..Limits::default()
+        };
+
+        let err = limits
+            .check(
+                &schema().schema,
+                r#"{ quote(id: "1") { author { name } } }"#,
+                None,
+                &Variables::new(),
+            )
+            .unwrap_err();
+
+        assert_eq!(err.message(), "query depth is 3, the maximum is 2");
+    }
+
+    #[test]
+    fn test_check_rejects_complex_queries() {
+        let limits = Limits {
+            max_complexity: 100,
+            ..Limits::default()
+        };
+
+        let err = limits
+            .check(
+                &schema().schema,
+                r#"{ tags(limit: 50) { items { quotes(limit: 50) { text } } } }"#,
+                None,
+                &Variables::new(),
+            )
+            .unwrap_err();
+
+        assert_eq!(err.message(), "query complexity is 555, the maximum is 100");
+    }
+
+    #[test]
+    fn test_check_rejects_large_queries() {
+        let limits = Limits {
+            max_query_size: 10,
+            ..Limits::default()
+        };
+
+        let err = limits
+            .check(&schema().schema, "{ version }", None, &Variables::new())
+            .unwrap_err();
+
+        assert_eq!(err.message(), "query is 11 bytes long, the maximum is 10");
+    }
+
+    #[test]
+    fn test_check_selects_the_operation() {
+        let limits = Limits {
+            max_depth: 1,
+            ..Limits::default()
+        };
+        let query = r#"query deep { quote(id: "1") { author { name } } } query flat { version }"#;
+
+        assert!(limits
+            .check(&schema().schema, query, Some("flat"), &Variables::new())
+            .is_ok());
+        assert!(limits
+            .check(&schema().schema, query, Some("deep"), &Variables::new())
+            .is_err());
+    }
+}
@@ -0,0 +1,133 @@
+use dataloader::cached::Loader;
+use dataloader::BatchFn;
+use juniper::FieldResult;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use super::errors::{self, Code};
+use crate::quote::structs::Quote;
+use crate::quote::Service as quote_service;
+
+/// Outcome of a batch shared by all of its keys, the error is logged once by the batch.
+type Loaded<T> = Result<T, Arc<String>>;
+
+/// Fills every key with the same error when the whole batch has failed.
+fn failed<K: Clone + Eq + std::hash::Hash, T>(
+    keys: &[K],
+    action: &str,
+    err: anyhow::Error,
+) -> HashMap<K, Loaded<T>> {
+    log::error!("failed to {action}: {err:#}");
+    let message = Arc::new(format!("failed to {action}"));
+    keys.iter()
+        .map(|key| (key.clone(), Err(message.clone())))
+        .collect()
+}
+
+fn to_field_result<T>(loaded: Result<Loaded<T>, std::io::Error>) -> FieldResult<T> {
+    match loaded {
+        Ok(Ok(value)) => Ok(value),
+        Ok(Err(message)) => Err(errors::new(Code::Internal, message)),
+        Err(err) => Err(errors::new(Code::Internal, err)),
+    }
+}
+
+pub struct QuoteBatcher {
+    quotes: quote_service,
+}
+
+impl BatchFn<String, Loaded<Option<Quote>>> for QuoteBatcher {
+    async fn load(&mut self, keys: &[String]) -> HashMap<String, Loaded<Option<Quote>>> {
+        let quotes = match self.quotes.get_quotes_by_ids(keys).await {
+            Ok(quotes) => quotes,
+            Err(err) => return failed(keys, "load quotes", err),
+        };
+
+        let mut loaded: HashMap<String, Loaded<Option<Quote>>> =
+            keys.iter().map(|key| (key.clone(), Ok(None))).collect();
+        for quote in quotes {
+            loaded.insert(quote.id.clone(), Ok(Some(quote)));
+        }
+
+        loaded
+    }
+}
+
+pub struct LikedBatcher {
+    quotes: quote_service,
+}
+
+impl BatchFn<(String, String), Loaded<bool>> for LikedBatcher {
+    async fn load(&mut self, keys: &[(String, String)]) -> HashMap<(String, String), Loaded<bool>> {
+        match self.quotes.get_liked(keys).await {
+            Ok(liked) => keys
+                .iter()
+                .map(|key| (key.clone(), Ok(liked.contains(key))))
+                .collect(),
+            Err(err) => failed(keys, "load like statuses", err),
+        }
+    }
+}
+
+pub struct ViewCountBatcher {
+    quotes: quote_service,
+}
+
+impl BatchFn<String, Loaded<i64>> for ViewCountBatcher {
+    async fn load(&mut self, keys: &[String]) -> HashMap<String, Loaded<i64>> {
+        match self.quotes.get_view_counts(keys).await {
+            Ok(counts) => keys
+                .iter()
+                .map(|key| {
+                    (
+                        key.clone(),
+                        Ok(counts.get(key).copied().unwrap_or_default()),
+                    )
+                })
+                .collect(),
+            Err(err) => failed(keys, "load view counts", err),
+        }
+    }
+}
+
+/// Request scoped loaders: the lookups made by the resolvers of one request are
+/// gathered into a single query per loader and their results are cached until
+/// the request ends.
+#[derive(Clone)]
+pub struct Loaders {
+    quotes: Loader<String, Loaded<Option<Quote>>, QuoteBatcher>,
+    liked: Loader<(String, String), Loaded<bool>, LikedBatcher>,
+    view_counts: Loader<String, Loaded<i64>, ViewCountBatcher>,
+}
+
+impl Loaders {
+    pub fn new(quotes: &quote_service) -> Self {
+        Loaders {
+            quotes: Loader::new(QuoteBatcher {
+                quotes: quotes.clone(),
+            }),
+            liked: Loader::new(LikedBatcher {
+                quotes: quotes.clone(),
+            }),
+            view_counts: Loader::new(ViewCountBatcher {
+                quotes: quotes.clone(),
+            }),
+        }
+    }
+
+    pub async fn quote(&self, quote_id: &str) -> FieldResult<Option<Quote>> {
+        to_field_result(self.quotes.try_load(quote_id.to_string()).await)
+    }
+
+    pub async fn liked(&self, user_id: &str, quote_id: &str) -> FieldResult<bool> {
+        to_field_result(
+            self.liked
+                .try_load((user_id.to_string(), quote_id.to_string()))
+                .await,
+        )
+    }
+
+    pub async fn view_count(&self, quote_id: &str) -> FieldResult<i64> {
+        to_field_result(self.view_counts.try_load(quote_id.to_string()).await)
+    }
+}
@@ -1,2 +1,12 @@
+mod errors;
+pub mod guard;
+mod legacy;
+mod limits;
+mod loaders;
+mod persisted;
 mod quotes;
 pub mod quotes_resolver;
+pub mod subscriptions;
+
+pub const GRAPHQL_ENDPOINT: &str = "/graphql";
+pub const SUBSCRIPTIONS_ENDPOINT: &str = "/subscriptions";
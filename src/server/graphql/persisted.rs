@@ -0,0 +1,197 @@
+use anyhow::{anyhow, Context, Result};
+use juniper::FieldResult;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+use super::errors::{self, Code};
+use crate::config::GraphQLConfig;
+
+/// Number of queries registered by the clients kept in memory, the ones sent after
+/// that are executed without being registered.
+const MAX_REGISTERED_QUERIES: usize = 1000;
+
+/// Queries referenced by the sha256 hash of their text. They are loaded from a manifest,
+/// and registered by the clients sending a query along with its hash unless only the
+/// manifest ones are accepted.
+#[derive(Debug, Default)]
+pub struct PersistedQueries {
+    queries: HashMap<String, String>,
+    registered: RwLock<HashMap<String, String>>,
+    only: bool,
+}
+
+pub fn hash(query: &str) -> String {
+    format!("{:x}", Sha256::digest(query))
+}
+
+impl PersistedQueries {
+    pub fn new(cfg: &GraphQLConfig) -> Result<Self> {
+        let queries = if cfg.persisted_queries.is_empty() {
+            HashMap::new()
+        } else {
+            let manifest = std::fs::read_to_string(&cfg.persisted_queries)
+                .context("failed to read persisted queries")?;
+            parse_manifest(&manifest)?
+        };
+
+        Ok(PersistedQueries {
+            queries,
+            registered: RwLock::default(),
+            only: cfg.persisted_queries_only,
+        })
+    }
+
+    /// Query to execute for the given hash and query, at least one of them is required.
+    pub fn resolve(&self, query_hash: Option<&str>, query: Option<String>) -> FieldResult<String> {
+        match (query_hash, query) {
+            (None, None) => Err(errors::new(
+                Code::BadRequest,
+                "query or persisted query hash is required",
+            )),
+            (None, Some(_)) if self.only => Err(errors::new(
+                Code::PersistedQueryRequired,
+                "only persisted queries are accepted",
+            )),
+            (None, Some(query)) => Ok(query),
+            (Some(query_hash), Some(query)) => {
+                if hash(&query) != query_hash.to_lowercase() {
+                    return Err(errors::new(
+                        Code::BadRequest,
+                        "persisted query hash does not match the query",
+                    ));
+                }
+                if self.only && self.get(query_hash).is_none() {
+                    return Err(errors::new(
+                        Code::PersistedQueryRequired,
+                        "only persisted queries are accepted",
+                    ));
+                }
+
+                Ok(query)
+            }
+            (Some(query_hash), None) => self.get(query_hash).ok_or_else(|| {
+                errors::new(
+                    Code::PersistedQueryNotFound,
+                    format!("persisted query {query_hash} not found"),
+                )
+            }),
+        }
+    }
+
+    /// Remembers a query resolved with its hash so that the client can send only the hash
+    /// afterwards, should be called once the query is known to be acceptable.
+    pub fn register(&self, hash: &str, query: &str) {
+        if self.only || self.get(hash).is_some() {
+            return;
+        }
+
+        let mut registered = self.registered.write().unwrap();
+        if registered.len() < MAX_REGISTERED_QUERIES {
+            registered.insert(hash.to_lowercase(), query.to_string());
+        }
+    }
+
+    fn get(&self, hash: &str) -> Option<String> {
+        let hash = hash.to_lowercase();
+        let query = self.queries.get(&hash).cloned();
+
+        query.or_else(|| self.registered.read().unwrap().get(&hash).cloned())
+    }
+}
+
+fn parse_manifest(manifest: &str) -> Result<HashMap<String, String>> {
+    let queries: HashMap<String, String> =
+        serde_json::from_str(manifest).context("failed to parse persisted queries")?;
+
+    queries
+        .into_iter()
+        .map(|(query_hash, query)| {
+            let query_hash = query_hash.to_lowercase();
+            if hash(&query) != query_hash {
+                return Err(anyhow!(
+                    "persisted query {query_hash} does not match its hash"
+                ));
+            }
+            Ok((query_hash, query))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const QUERY: &str = "{ version }";
+
+    fn new_queries(only: bool) -> PersistedQueries {
+        PersistedQueries {
+            queries: HashMap::from([(hash(QUERY), QUERY.to_string())]),
+            only,
+            ..PersistedQueries::default()
+        }
+    }
+
+    #[test]
+    fn test_resolve_by_hash() {
+        let queries = new_queries(true);
+
+        assert_eq!(
+            queries.resolve(Some(&hash(QUERY)), None).unwrap(),
+            QUERY.to_string()
+        );
+        assert_eq!(
+            queries
+                .resolve(Some(&hash("{ heartbeat }")), None)
+                .unwrap_err()
+                .extensions(),
+            &juniper::graphql_value!({ "code": "PERSISTED_QUERY_NOT_FOUND" })
+        );
+    }
+
+    #[test]
+    fn test_resolve_only_persisted() {
+        let queries = new_queries(true);
+
+        let err = queries
+            .resolve(None, Some("{ heartbeat }".to_string()))
+            .unwrap_err();
+        assert_eq!(err.message(), "only persisted queries are accepted");
+
+        queries.register(&hash("{ heartbeat }"), "{ heartbeat }");
+        assert!(queries.resolve(Some(&hash("{ heartbeat }")), None).is_err());
+    }
+
+    #[test]
+    fn test_resolve_registers_queries() {
+        let queries = new_queries(false);
+        let query = "{ heartbeat { success } }";
+
+        let err = queries
+            .resolve(Some(&hash(query)), Some("{ version }".to_string()))
+            .unwrap_err();
+        assert_eq!(
+            err.message(),
+            "persisted query hash does not match the query"
+        );
+
+        let resolved = queries
+            .resolve(Some(&hash(query)), Some(query.to_string()))
+            .unwrap();
+        queries.register(&hash(query), &resolved);
+
+        assert_eq!(
+            queries.resolve(Some(&hash(query)), None).unwrap(),
+            query.to_string()
+        );
+    }
+
+    #[test]
+    fn test_parse_manifest_checks_hashes() {
+        let manifest = serde_json::json!({ hash(QUERY): QUERY }).to_string();
+        assert_eq!(parse_manifest(&manifest).unwrap().len(), 1);
+
+        let manifest = serde_json::json!({ hash(QUERY): "{ heartbeat }" }).to_string();
+        assert!(parse_manifest(&manifest).is_err());
+    }
+}
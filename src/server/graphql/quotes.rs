@@ -1,23 +1,361 @@
-use juniper::GraphQLObject;
+use juniper::{graphql_object, FieldResult, GraphQLEnum, GraphQLInputObject, GraphQLObject};
+
+use super::errors;
+use super::quotes_resolver::Context;
+use crate::catalogue::structs as catalogue_structs;
+use crate::quote::structs::{self as quote_structs, parse_batch_count, QuoteFilter as Filter};
+
+/// Validates the pagination of nested quote lists, the limit follows the batch bounds.
+fn parse_page(limit: Option<i32>, offset: Option<i32>) -> FieldResult<(u64, u64)> {
+    let limit =
+        parse_batch_count(limit.map(|limit| limit.max(0) as u64)).map_err(errors::bad_request)?;
+
+    Ok((limit as u64, offset.unwrap_or_default().max(0) as u64))
+}
+
+pub struct Quote(pub quote_structs::Quote);
+
+impl From<quote_structs::Quote> for Quote {
+    fn from(quote: quote_structs::Quote) -> Self {
+        Quote(quote)
+    }
+}
+
+#[graphql_object]
+#[graphql(context = Context)]
+impl Quote {
+    fn id(&self) -> &str {
+        &self.0.id
+    }
+
+    fn text(&self) -> &str {
+        &self.0.quote
+    }
+
+    fn author(&self) -> Author {
+        Author::named(&self.0.author)
+    }
+
+    fn tags(&self) -> Vec<Tag> {
+        self.0.tags.iter().map(|tag| Tag::named(tag)).collect()
+    }
+
+    fn likes(&self) -> i32 {
+        self.0.likes
+    }
+
+    /// Number of users who have seen the quote.
+    async fn views(&self, ctx: &Context) -> FieldResult<i32> {
+        Ok(ctx.loaders.view_count(&self.0.id).await? as i32)
+    }
+
+    /// Whether the user has liked the quote.
+    async fn liked(&self, ctx: &Context, user_id: String) -> FieldResult<bool> {
+        ctx.loaders.liked(&user_id, &self.0.id).await
+    }
+
+    /// Provider the quote was received from.
+    fn source(&self) -> &str {
+        &self.0.source
+    }
+
+    /// Id of the quote at its provider.
+    fn external_id(&self) -> Option<&str> {
+        self.0.external_id.as_deref()
+    }
+}
+
+/// Author of quotes, the counters are loaded only when they are requested.
+pub struct Author {
+    name: String,
+    stats: Option<catalogue_structs::Author>,
+}
+
+impl Author {
+    pub fn named(name: &str) -> Self {
+        Author {
+            name: name.to_string(),
+            stats: None,
+        }
+    }
+
+    async fn stats(&self, ctx: &Context) -> FieldResult<catalogue_structs::Author> {
+        match &self.stats {
+            Some(stats) => Ok(stats.clone()),
+            None => ctx
+                .catalogue
+                .get_author(&self.name)
+                .await
+                .map_err(|err| errors::from_service(err, "get author")),
+        }
+    }
+}
+
+impl From<catalogue_structs::Author> for Author {
+    fn from(author: catalogue_structs::Author) -> Self {
+        Author {
+            name: author.name.clone(),
+            stats: Some(author),
+        }
+    }
+}
+
+#[graphql_object]
+#[graphql(context = Context)]
+impl Author {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn quote_count(&self, ctx: &Context) -> FieldResult<i32> {
+        Ok(self.stats(ctx).await?.quotes as i32)
+    }
+
+    /// Sum of the likes of all quotes of the author.
+    async fn likes(&self, ctx: &Context) -> FieldResult<i32> {
+        Ok(self.stats(ctx).await?.likes as i32)
+    }
+
+    /// Quotes of the author, most liked first.
+    async fn quotes(
+        &self,
+        ctx: &Context,
+        limit: Option<i32>,
+        offset: Option<i32>,
+    ) -> FieldResult<Vec<Quote>> {
+        let (limit, offset) = parse_page(limit, offset)?;
+        let filter = Filter::new(Some(&self.name), &[]);
+
+        let quotes = ctx
+            .quotes
+            .find_quotes(&filter, limit, offset)
+            .await
+            .map_err(|err| errors::from_service(err, "get quotes of author"))?;
+
+        Ok(quotes.into_iter().map(Into::into).collect())
+    }
+}
+
+/// Tag of quotes, the counter is loaded only when it is requested.
+pub struct Tag {
+    name: String,
+    stats: Option<catalogue_structs::Tag>,
+}
+
+impl Tag {
+    pub fn named(name: &str) -> Self {
+        Tag {
+            name: name.to_string(),
+            stats: None,
+        }
+    }
+
+    async fn stats(&self, ctx: &Context) -> FieldResult<catalogue_structs::Tag> {
+        match &self.stats {
+            Some(stats) => Ok(stats.clone()),
+            None => ctx
+                .catalogue
+                .get_tag(&self.name)
+                .await
+                .map_err(|err| errors::from_service(err, "get tag")),
+        }
+    }
+}
+
+impl From<catalogue_structs::Tag> for Tag {
+    fn from(tag: catalogue_structs::Tag) -> Self {
+        Tag {
+            name: tag.name.clone(),
+            stats: Some(tag),
+        }
+    }
+}
+
+#[graphql_object]
+#[graphql(context = Context)]
+impl Tag {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn quote_count(&self, ctx: &Context) -> FieldResult<i32> {
+        Ok(self.stats(ctx).await?.quotes as i32)
+    }
+
+    /// Quotes having the tag, most liked first.
+    async fn quotes(
+        &self,
+        ctx: &Context,
+        limit: Option<i32>,
+        offset: Option<i32>,
+    ) -> FieldResult<Vec<Quote>> {
+        let (limit, offset) = parse_page(limit, offset)?;
+        let filter = Filter::new(None, std::slice::from_ref(&self.name));
+
+        let quotes = ctx
+            .quotes
+            .find_quotes(&filter, limit, offset)
+            .await
+            .map_err(|err| errors::from_service(err, "get quotes of tag"))?;
+
+        Ok(quotes.into_iter().map(Into::into).collect())
+    }
+}
 
 #[derive(GraphQLObject)]
-pub struct Quote {
-    pub id: String,
-    pub quote: String,
-    pub author: String,
-    pub tags: Vec<String>,
+#[graphql(context = Context)]
+pub struct TagPage {
+    pub items: Vec<Tag>,
+    pub total: i32,
+}
+
+impl From<catalogue_structs::Page<catalogue_structs::Tag>> for TagPage {
+    fn from(page: catalogue_structs::Page<catalogue_structs::Tag>) -> Self {
+        TagPage {
+            items: page.items.into_iter().map(Into::into).collect(),
+            total: page.total as i32,
+        }
+    }
+}
+
+#[derive(GraphQLObject)]
+#[graphql(context = Context)]
+pub struct AuthorPage {
+    pub items: Vec<Author>,
+    pub total: i32,
+}
+
+impl From<catalogue_structs::Page<catalogue_structs::Author>> for AuthorPage {
+    fn from(page: catalogue_structs::Page<catalogue_structs::Author>) -> Self {
+        AuthorPage {
+            items: page.items.into_iter().map(Into::into).collect(),
+            total: page.total as i32,
+        }
+    }
+}
+
+/// Narrows random quotes down, a quote matches when it is written by the author
+/// and has at least one of the tags.
+#[derive(GraphQLInputObject, Default)]
+pub struct QuoteFilter {
+    pub author: Option<String>,
+    pub tags: Option<Vec<String>>,
+}
+
+impl From<QuoteFilter> for Filter {
+    fn from(filter: QuoteFilter) -> Self {
+        Filter::new(filter.author.as_deref(), &filter.tags.unwrap_or_default())
+    }
+}
+
+#[derive(GraphQLObject)]
+pub struct LikeCount {
+    pub quote_id: String,
     pub likes: i32,
 }
 
+#[derive(GraphQLEnum, Clone, Copy)]
+pub enum ModerationStatus {
+    Pending,
+    Approved,
+    Hidden,
+}
+
+impl From<quote_structs::ModerationStatus> for ModerationStatus {
+    fn from(status: quote_structs::ModerationStatus) -> Self {
+        match status {
+            quote_structs::ModerationStatus::Pending => ModerationStatus::Pending,
+            quote_structs::ModerationStatus::Approved => ModerationStatus::Approved,
+            quote_structs::ModerationStatus::Hidden => ModerationStatus::Hidden,
+        }
+    }
+}
+
+impl From<ModerationStatus> for quote_structs::ModerationStatus {
+    fn from(status: ModerationStatus) -> Self {
+        match status {
+            ModerationStatus::Pending => quote_structs::ModerationStatus::Pending,
+            ModerationStatus::Approved => quote_structs::ModerationStatus::Approved,
+            ModerationStatus::Hidden => quote_structs::ModerationStatus::Hidden,
+        }
+    }
+}
+
+#[derive(GraphQLObject)]
+pub struct Report {
+    pub user_id: String,
+    pub reason: String,
+    /// RFC 3339 time of the report.
+    pub created_at: String,
+}
+
+impl From<quote_structs::Report> for Report {
+    fn from(report: quote_structs::Report) -> Self {
+        Report {
+            user_id: report.user_id,
+            reason: report.reason,
+            created_at: report.created_at,
+        }
+    }
+}
+
+/// Quote waiting for a review with its open reports, newest first.
 #[derive(GraphQLObject)]
-pub struct QuoteResult {
-    pub success: bool,
-    pub errors: Vec<String>,
-    pub quote: Option<Quote>,
+#[graphql(context = Context)]
+pub struct ReviewItem {
+    pub quote: Quote,
+    pub status: ModerationStatus,
+    pub reports: Vec<Report>,
 }
 
+impl From<quote_structs::ReviewItem> for ReviewItem {
+    fn from(item: quote_structs::ReviewItem) -> Self {
+        ReviewItem {
+            quote: item.quote.into(),
+            status: item.status.into(),
+            reports: item.reports.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+/// Change of the moderation status of a quote.
+#[derive(GraphQLObject)]
+pub struct AuditEntry {
+    pub id: String,
+    pub quote_id: String,
+    pub previous_status: ModerationStatus,
+    pub status: ModerationStatus,
+    pub moderator: String,
+    pub note: String,
+    /// RFC 3339 time of the change.
+    pub created_at: String,
+}
+
+impl From<quote_structs::AuditEntry> for AuditEntry {
+    fn from(entry: quote_structs::AuditEntry) -> Self {
+        AuditEntry {
+            id: entry.id,
+            quote_id: entry.quote_id,
+            previous_status: entry.previous_status.into(),
+            status: entry.status.into(),
+            moderator: entry.moderator,
+            note: entry.note,
+            created_at: entry.created_at,
+        }
+    }
+}
+
+/// Counters of the service since it started.
 #[derive(GraphQLObject)]
-pub struct EmptyResult {
-    pub success: bool,
-    pub errors: Vec<String>,
+pub struct Stats {
+    /// Quotes of the site or the corpus rejected by the validation.
+    pub rejected_quotes: i32,
+}
+
+impl From<quote_structs::Stats> for Stats {
+    fn from(stats: quote_structs::Stats) -> Self {
+        Stats {
+            rejected_quotes: stats.rejected_quotes as i32,
+        }
+    }
 }
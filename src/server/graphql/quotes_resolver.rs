@@ -1,21 +1,104 @@
-use juniper::{graphql_object, EmptySubscription, FieldResult, RootNode};
+use futures::future;
+use juniper::{graphql_object, graphql_subscription, FieldError, FieldResult, RootNode};
+use std::pin::Pin;
+use tokio_stream::wrappers::BroadcastStream;
+use tokio_stream::{Stream, StreamExt};
 
-use super::quotes::{EmptyResult, Quote, QuoteResult};
+use super::errors;
+use super::legacy::{
+    AuthorsResult, EmptyResult, LegacyAuthor, LegacyQuote, LegacyTag, QuoteResult, QuotesResult,
+    TagsResult,
+};
+use super::loaders::Loaders;
+use super::quotes::{
+    AuditEntry, Author, AuthorPage, LikeCount, ModerationStatus, Quote, QuoteFilter, ReviewItem,
+    Stats, Tag, TagPage,
+};
+use crate::catalogue::structs::{ListParams, Sort};
+use crate::catalogue::Service as catalogue_service;
+use crate::database::canonical::slugify;
+use crate::events::{Bus, Event};
 use crate::heartbeat::Heartbeat as heartbeat_service;
+use crate::quote::structs::{
+    self as quote_structs, parse_batch_count, DailyQuoteOverride, DailyQuoteParams, Feedback,
+    ModerationDecision, QuoteReport, ReviewPage,
+};
 use crate::quote::Service as quote_service;
 
+/// Bumped on breaking changes of the schema, the deprecated fields are removed
+/// with the next bump.
+pub const SCHEMA_VERSION: &str = "2.0";
+
+#[derive(Clone)]
 pub struct Context {
     pub quotes: quote_service,
     pub heartbeat: heartbeat_service,
+    pub catalogue: catalogue_service,
+    pub events: Bus,
+    /// Whether the request carried the admin token.
+    pub admin: bool,
+    pub loaders: Loaders,
 }
 
 impl juniper::Context for Context {}
 
+impl Context {
+    /// Builds the context shared by the transports, every request gets its own copy
+    /// from `for_request`.
+    pub fn new(
+        quotes: quote_service,
+        heartbeat: heartbeat_service,
+        catalogue: catalogue_service,
+        events: Bus,
+    ) -> Self {
+        Context {
+            loaders: Loaders::new(&quotes),
+            quotes,
+            heartbeat,
+            catalogue,
+            events,
+            admin: false,
+        }
+    }
+
+    /// Copy of the shared context for one request (or one subscription connection)
+    /// with empty loader caches.
+    pub fn for_request(&self, admin: bool) -> Self {
+        Context {
+            admin,
+            loaders: Loaders::new(&self.quotes),
+            ..self.clone()
+        }
+    }
+}
+
+fn parse_count(count: Option<i32>) -> anyhow::Result<usize> {
+    parse_batch_count(count.map(|count| count.max(0) as u64))
+}
+
+fn parse_list_params<S: Sort>(
+    sort: Option<String>,
+    order: Option<String>,
+    limit: Option<i32>,
+    offset: Option<i32>,
+) -> anyhow::Result<ListParams<S>> {
+    ListParams::parse(
+        sort.as_deref(),
+        order.as_deref(),
+        limit.map(|limit| limit.max(0) as u64),
+        offset.map(|offset| offset.max(0) as u64),
+    )
+}
+
 pub struct Query;
 
 #[graphql_object]
 #[graphql(context = Context)]
 impl Query {
+    fn version() -> &'static str {
+        SCHEMA_VERSION
+    }
+
     async fn heartbeat(ctx: &Context) -> FieldResult<EmptyResult> {
         match ctx.heartbeat.ping_database().await {
             Ok(_) => Ok(EmptyResult {
@@ -29,67 +112,352 @@ impl Query {
         }
     }
 
-    #[graphql(name = "get_quote_handler")]
+    async fn quote(ctx: &Context, id: String) -> FieldResult<Quote> {
+        match ctx.loaders.quote(&id).await? {
+            Some(quote) => Ok(quote.into()),
+            None => Err(errors::new(errors::Code::NotFound, "not found")),
+        }
+    }
+
+    /// Quotes seen by the user.
+    async fn history(
+        ctx: &Context,
+        user_id: String,
+        limit: Option<i32>,
+        offset: Option<i32>,
+    ) -> FieldResult<Vec<Quote>> {
+        let limit = parse_count(limit).map_err(errors::bad_request)?;
+
+        let quote_ids = ctx
+            .quotes
+            .get_history(
+                &user_id,
+                limit as u64,
+                offset.unwrap_or_default().max(0) as u64,
+            )
+            .await
+            .map_err(|err| errors::from_service(err, "get history"))?;
+
+        let quotes =
+            future::try_join_all(quote_ids.iter().map(|quote_id| ctx.loaders.quote(quote_id)))
+                .await?;
+
+        Ok(quotes.into_iter().flatten().map(Into::into).collect())
+    }
+
+    /// Unseen quote weighted by likes, marked as viewed for the user.
+    async fn random_quote(
+        ctx: &Context,
+        user_id: String,
+        filter: Option<QuoteFilter>,
+    ) -> FieldResult<Quote> {
+        ctx.quotes
+            .get_filtered_quote(&user_id, &filter.unwrap_or_default().into())
+            .await
+            .map(Into::into)
+            .map_err(|err| errors::from_service(err, "get random quote"))
+    }
+
+    /// Several distinct unseen quotes, marked as viewed for the user.
+    async fn random_quotes(
+        ctx: &Context,
+        user_id: String,
+        count: Option<i32>,
+    ) -> FieldResult<Vec<Quote>> {
+        let count = parse_count(count).map_err(errors::bad_request)?;
+
+        let quotes = ctx
+            .quotes
+            .get_quotes(&user_id, count)
+            .await
+            .map_err(|err| errors::from_service(err, "get random quotes"))?;
+
+        Ok(quotes.into_iter().map(Into::into).collect())
+    }
+
+    /// Unseen quotes sharing the most tags with the given one, then the same author.
+    async fn similar_quotes(
+        ctx: &Context,
+        user_id: String,
+        quote_id: String,
+        count: Option<i32>,
+    ) -> FieldResult<Vec<Quote>> {
+        let count = parse_count(count).map_err(errors::bad_request)?;
+
+        let quotes = ctx
+            .quotes
+            .get_similar_quotes(&user_id, &quote_id, count)
+            .await
+            .map_err(|err| errors::from_service(err, "get similar quotes"))?;
+
+        Ok(quotes.into_iter().map(Into::into).collect())
+    }
+
+    async fn quote_of_the_day(
+        ctx: &Context,
+        timezone: Option<String>,
+        tag: Option<String>,
+    ) -> FieldResult<Quote> {
+        let params = DailyQuoteParams::parse(timezone.as_deref(), tag.as_deref())
+            .map_err(errors::bad_request)?;
+
+        ctx.quotes
+            .get_quote_of_the_day(&params)
+            .await
+            .map(Into::into)
+            .map_err(|err| errors::from_service(err, "get quote of the day"))
+    }
+
+    async fn tag(ctx: &Context, name: String) -> FieldResult<Tag> {
+        ctx.catalogue
+            .get_tag(&name)
+            .await
+            .map(Into::into)
+            .map_err(|err| errors::from_service(err, "get tag"))
+    }
+
+    async fn tags(
+        ctx: &Context,
+        sort: Option<String>,
+        order: Option<String>,
+        limit: Option<i32>,
+        offset: Option<i32>,
+    ) -> FieldResult<TagPage> {
+        let params = parse_list_params(sort, order, limit, offset).map_err(errors::bad_request)?;
+
+        ctx.catalogue
+            .get_tags(params)
+            .await
+            .map(Into::into)
+            .map_err(|err| errors::from_service(err, "get tags"))
+    }
+
+    async fn author(ctx: &Context, name: String) -> FieldResult<Author> {
+        ctx.catalogue
+            .get_author(&name)
+            .await
+            .map(Into::into)
+            .map_err(|err| errors::from_service(err, "get author"))
+    }
+
+    async fn authors(
+        ctx: &Context,
+        sort: Option<String>,
+        order: Option<String>,
+        limit: Option<i32>,
+        offset: Option<i32>,
+    ) -> FieldResult<AuthorPage> {
+        let params = parse_list_params(sort, order, limit, offset).map_err(errors::bad_request)?;
+
+        ctx.catalogue
+            .get_authors(params)
+            .await
+            .map(Into::into)
+            .map_err(|err| errors::from_service(err, "get authors"))
+    }
+
+    /// Pending quotes and quotes with open reports, most reported first, requires the
+    /// admin token.
+    async fn review_queue(
+        ctx: &Context,
+        limit: Option<i32>,
+        offset: Option<i32>,
+    ) -> FieldResult<Vec<ReviewItem>> {
+        if !ctx.admin {
+            return Err(errors::unauthorized());
+        }
+
+        let page = ReviewPage::parse(
+            limit.map(|limit| limit.max(0) as u64),
+            offset.map(|offset| offset.max(0) as u64),
+        )
+        .map_err(errors::bad_request)?;
+
+        let queue = ctx
+            .quotes
+            .get_review_queue(&page)
+            .await
+            .map_err(|err| errors::from_service(err, "get review queue"))?;
+
+        Ok(queue.into_iter().map(Into::into).collect())
+    }
+
+    /// Status changes of the quote, oldest first, requires the admin token.
+    async fn moderation_audit(ctx: &Context, quote_id: String) -> FieldResult<Vec<AuditEntry>> {
+        if !ctx.admin {
+            return Err(errors::unauthorized());
+        }
+
+        let entries = ctx
+            .quotes
+            .get_moderation_audit(&quote_id)
+            .await
+            .map_err(|err| errors::from_service(err, "get moderation audit"))?;
+
+        Ok(entries.into_iter().map(Into::into).collect())
+    }
+
+    /// Counters of the service since it started, requires the admin token.
+    fn stats(ctx: &Context) -> FieldResult<Stats> {
+        if !ctx.admin {
+            return Err(errors::unauthorized());
+        }
+
+        Ok(ctx.quotes.get_stats().into())
+    }
+
+    #[graphql(name = "get_quote_handler", deprecated = "Use `randomQuote`.")]
     async fn get_quote_handler(
         ctx: &Context,
         #[graphql(name = "user_id")] user_id: String,
     ) -> FieldResult<QuoteResult> {
-        let quote = match ctx.quotes.get_quote(user_id.as_str()).await {
-            Ok(quote) => quote,
-            Err(err) => {
-                return Ok(QuoteResult {
-                    success: false,
-                    errors: vec![err.to_string()],
-                    quote: None,
-                })
-            }
+        match ctx.quotes.get_quote(user_id.as_str()).await {
+            Ok(quote) => Ok(QuoteResult {
+                success: true,
+                errors: vec![],
+                quote: Some(quote.into()),
+            }),
+            Err(err) => Ok(QuoteResult {
+                success: false,
+                errors: vec![err.to_string()],
+                quote: None,
+            }),
+        }
+    }
+
+    #[graphql(name = "get_quotes_handler", deprecated = "Use `randomQuotes`.")]
+    async fn get_quotes_handler(
+        ctx: &Context,
+        #[graphql(name = "user_id")] user_id: String,
+        count: Option<i32>,
+    ) -> FieldResult<QuotesResult> {
+        let quotes = match parse_count(count) {
+            Ok(count) => ctx.quotes.get_quotes(user_id.as_str(), count).await,
+            Err(err) => Err(err),
         };
 
-        Ok(QuoteResult {
-            success: true,
-            errors: vec![],
-            quote: Some(Quote {
-                id: quote.id,
-                quote: quote.quote,
-                author: quote.author,
-                tags: quote.tags,
-                likes: quote.likes,
+        match quotes {
+            Ok(quotes) => Ok(QuotesResult {
+                success: true,
+                errors: vec![],
+                quotes: quotes.into_iter().map(LegacyQuote::from).collect(),
+            }),
+            Err(err) => Ok(QuotesResult {
+                success: false,
+                errors: vec![err.to_string()],
+                quotes: vec![],
             }),
-        })
+        }
     }
 
-    #[graphql(name = "get_same_quote_handler")]
+    #[graphql(name = "get_same_quote_handler", deprecated = "Use `similarQuotes`.")]
     async fn get_same_quote_handler(
         ctx: &Context,
         #[graphql(name = "user_id")] user_id: String,
         #[graphql(name = "quote_id")] quote_id: String,
     ) -> FieldResult<QuoteResult> {
-        let quote = match ctx
+        match ctx
             .quotes
             .get_same_quote(user_id.as_str(), quote_id.as_str())
             .await
         {
-            Ok(quote) => quote,
-            Err(err) => {
-                return Ok(QuoteResult {
-                    success: false,
-                    errors: vec![err.to_string()],
-                    quote: None,
-                })
-            }
+            Ok(quote) => Ok(QuoteResult {
+                success: true,
+                errors: vec![],
+                quote: Some(quote.into()),
+            }),
+            Err(err) => Ok(QuoteResult {
+                success: false,
+                errors: vec![err.to_string()],
+                quote: None,
+            }),
+        }
+    }
+
+    #[graphql(
+        name = "get_quote_of_the_day_handler",
+        deprecated = "Use `quoteOfTheDay`."
+    )]
+    async fn get_quote_of_the_day_handler(
+        ctx: &Context,
+        timezone: Option<String>,
+        tag: Option<String>,
+    ) -> FieldResult<QuoteResult> {
+        let quote = match DailyQuoteParams::parse(timezone.as_deref(), tag.as_deref()) {
+            Ok(params) => ctx.quotes.get_quote_of_the_day(&params).await,
+            Err(err) => Err(err),
         };
 
-        Ok(QuoteResult {
-            success: true,
-            errors: vec![],
-            quote: Some(Quote {
-                id: quote.id,
-                quote: quote.quote,
-                author: quote.author,
-                tags: quote.tags,
-                likes: quote.likes,
+        match quote {
+            Ok(quote) => Ok(QuoteResult {
+                success: true,
+                errors: vec![],
+                quote: Some(quote.into()),
+            }),
+            Err(err) => Ok(QuoteResult {
+                success: false,
+                errors: vec![err.to_string()],
+                quote: None,
+            }),
+        }
+    }
+
+    #[graphql(name = "get_tags_handler", deprecated = "Use `tags`.")]
+    async fn get_tags_handler(
+        ctx: &Context,
+        sort: Option<String>,
+        order: Option<String>,
+        limit: Option<i32>,
+        offset: Option<i32>,
+    ) -> FieldResult<TagsResult> {
+        let page = match parse_list_params(sort, order, limit, offset) {
+            Ok(params) => ctx.catalogue.get_tags(params).await,
+            Err(err) => Err(err),
+        };
+
+        match page {
+            Ok(page) => Ok(TagsResult {
+                success: true,
+                errors: vec![],
+                tags: page.items.into_iter().map(LegacyTag::from).collect(),
+                total: page.total as i32,
+            }),
+            Err(err) => Ok(TagsResult {
+                success: false,
+                errors: vec![err.to_string()],
+                tags: vec![],
+                total: 0,
+            }),
+        }
+    }
+
+    #[graphql(name = "get_authors_handler", deprecated = "Use `authors`.")]
+    async fn get_authors_handler(
+        ctx: &Context,
+        sort: Option<String>,
+        order: Option<String>,
+        limit: Option<i32>,
+        offset: Option<i32>,
+    ) -> FieldResult<AuthorsResult> {
+        let page = match parse_list_params(sort, order, limit, offset) {
+            Ok(params) => ctx.catalogue.get_authors(params).await,
+            Err(err) => Err(err),
+        };
+
+        match page {
+            Ok(page) => Ok(AuthorsResult {
+                success: true,
+                errors: vec![],
+                authors: page.items.into_iter().map(LegacyAuthor::from).collect(),
+                total: page.total as i32,
+            }),
+            Err(err) => Ok(AuthorsResult {
+                success: false,
+                errors: vec![err.to_string()],
+                authors: vec![],
+                total: 0,
             }),
-        })
+        }
     }
 }
 
@@ -98,7 +466,107 @@ pub struct Mutation;
 #[graphql_object]
 #[graphql(context = Context)]
 impl Mutation {
-    #[graphql(name = "like_quote_handler")]
+    /// Likes the quote once per user, returns it with the updated number of likes.
+    async fn like_quote(ctx: &Context, user_id: String, quote_id: String) -> FieldResult<Quote> {
+        ctx.quotes
+            .like_quote(&user_id, &quote_id)
+            .await
+            .map(Into::into)
+            .map_err(|err| errors::from_service(err, "like quote"))
+    }
+
+    /// Dislikes a viewed quote, its author and tags are picked less often for the user.
+    async fn dislike_quote(ctx: &Context, user_id: String, quote_id: String) -> FieldResult<bool> {
+        ctx.quotes
+            .give_feedback(&user_id, &quote_id, Feedback::Dislike)
+            .await
+            .map(|_| true)
+            .map_err(|err| errors::from_service(err, "save feedback"))
+    }
+
+    /// Skips a viewed quote, its author and tags are picked slightly less often for the user.
+    async fn skip_quote(ctx: &Context, user_id: String, quote_id: String) -> FieldResult<bool> {
+        ctx.quotes
+            .give_feedback(&user_id, &quote_id, Feedback::Skip)
+            .await
+            .map(|_| true)
+            .map_err(|err| errors::from_service(err, "save feedback"))
+    }
+
+    /// Replaces the quote of the day, requires the admin token.
+    async fn set_quote_of_the_day(
+        ctx: &Context,
+        date: Option<String>,
+        timezone: Option<String>,
+        tag: Option<String>,
+        quote_id: String,
+    ) -> FieldResult<Quote> {
+        if !ctx.admin {
+            return Err(errors::unauthorized());
+        }
+
+        let daily_override = DailyQuoteOverride::parse(
+            date.as_deref(),
+            timezone.as_deref(),
+            tag.as_deref(),
+            &quote_id,
+        )
+        .map_err(errors::bad_request)?;
+
+        ctx.quotes
+            .set_quote_of_the_day(&daily_override)
+            .await
+            .map(Into::into)
+            .map_err(|err| errors::from_service(err, "set quote of the day"))
+    }
+
+    /// Reports an offensive or incorrect quote, a quote reported by enough users is hidden
+    /// until an admin reviews it.
+    async fn report_quote(
+        ctx: &Context,
+        user_id: String,
+        quote_id: String,
+        reason: Option<String>,
+    ) -> FieldResult<bool> {
+        let report = QuoteReport::parse(&user_id, &quote_id, reason.as_deref())
+            .map_err(errors::bad_request)?;
+
+        ctx.quotes
+            .report_quote(&report)
+            .await
+            .map(|_| true)
+            .map_err(|err| errors::from_service(err, "report quote"))
+    }
+
+    /// Approves or hides the quote and resolves its open reports, requires the admin token.
+    async fn moderate_quote(
+        ctx: &Context,
+        quote_id: String,
+        status: ModerationStatus,
+        moderator: Option<String>,
+        note: Option<String>,
+    ) -> FieldResult<AuditEntry> {
+        if !ctx.admin {
+            return Err(errors::unauthorized());
+        }
+
+        let status = quote_structs::ModerationStatus::from(status);
+        let decision = ModerationDecision::parse(
+            &quote_id,
+            status.as_str(),
+            moderator.as_deref(),
+            note.as_deref(),
+        )
+        .map_err(errors::bad_request)?;
+
+        ctx.quotes
+            .moderate_quote(&decision)
+            .await
+            .map(Into::into)
+            .map_err(|err| errors::from_service(err, "moderate quote"))
+    }
+
+    #[graphql(name = "like_quote_handler", deprecated = "Use `likeQuote`.")]
     async fn like_quote_handler(
         ctx: &Context,
         #[graphql(name = "user_id")] user_id: String,
@@ -119,6 +587,262 @@ impl Mutation {
             }),
         }
     }
+
+    #[graphql(
+        name = "set_quote_of_the_day_handler",
+        deprecated = "Use `setQuoteOfTheDay`."
+    )]
+    async fn set_quote_of_the_day_handler(
+        ctx: &Context,
+        date: Option<String>,
+        timezone: Option<String>,
+        tag: Option<String>,
+        #[graphql(name = "quote_id")] quote_id: String,
+    ) -> FieldResult<QuoteResult> {
+        if !ctx.admin {
+            return Ok(QuoteResult {
+                success: false,
+                errors: vec!["unauthorized".to_string()],
+                quote: None,
+            });
+        }
+
+        let quote = match DailyQuoteOverride::parse(
+            date.as_deref(),
+            timezone.as_deref(),
+            tag.as_deref(),
+            &quote_id,
+        ) {
+            Ok(daily_override) => ctx.quotes.set_quote_of_the_day(&daily_override).await,
+            Err(err) => Err(err),
+        };
+
+        match quote {
+            Ok(quote) => Ok(QuoteResult {
+                success: true,
+                errors: vec![],
+                quote: Some(quote.into()),
+            }),
+            Err(err) => Ok(QuoteResult {
+                success: false,
+                errors: vec![err.to_string()],
+                quote: None,
+            }),
+        }
+    }
+}
+
+type LikeCountStream = Pin<Box<dyn Stream<Item = Result<LikeCount, FieldError>> + Send>>;
+type QuoteStream = Pin<Box<dyn Stream<Item = Result<Quote, FieldError>> + Send>>;
+
+pub struct Subscription;
+
+#[graphql_subscription]
+#[graphql(context = Context)]
+impl Subscription {
+    /// Sends the new number of likes every time the quote is liked.
+    async fn like_count_changed(ctx: &Context, quote_id: String) -> LikeCountStream {
+        let stream =
+            BroadcastStream::new(ctx.events.subscribe()).filter_map(move |event| match event {
+                Ok(Event::LikeCountChanged {
+                    quote_id: liked_quote_id,
+                    likes,
+                }) if liked_quote_id == quote_id => Some(Ok(LikeCount {
+                    quote_id: liked_quote_id,
+                    likes,
+                })),
+                _ => None,
+            });
+
+        Box::pin(stream)
+    }
+
+    /// Sends quotes fetched from the external api, only the ones having
+    /// at least one of the tags when they are specified.
+    async fn new_quote_added(ctx: &Context, tags: Option<Vec<String>>) -> QuoteStream {
+        let slugs: Vec<String> = tags
+            .unwrap_or_default()
+            .iter()
+            .map(|tag| slugify(tag))
+            .collect();
+
+        let stream =
+            BroadcastStream::new(ctx.events.subscribe()).filter_map(move |event| match event {
+                Ok(Event::NewQuoteAdded(quote))
+                    if slugs.is_empty() || quote.tags.iter().any(|tag| slugs.contains(tag)) =>
+                {
+                    Some(Ok(quote.into()))
+                }
+                _ => None,
+            });
+
+        Box::pin(stream)
+    }
 }
 
-pub type Schema = RootNode<'static, Query, Mutation, EmptySubscription<Context>>;
+pub type Schema = RootNode<'static, Query, Mutation, Subscription>;
+
+#[cfg(test)]
+mod tests {
+    use fake::{faker::lorem, uuid, Fake};
+    use juniper::{execute, Variables};
+    use sea_orm::{DatabaseBackend, MockDatabase, Value};
+    use std::collections::BTreeMap;
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::config::{CatalogueConfig, QuotesConfig};
+    use crate::database::seaorm::SeaORM;
+    use crate::database::structs::quotes::Model as quote_model;
+    use crate::database::structs::views::Model as view_model;
+    use crate::quote::traits::MockApi;
+
+    const USER_ID: &str = "user";
+
+    fn new_context(db: &Arc<SeaORM>) -> Context {
+        Context::new(
+            quote_service::new(
+                &QuotesConfig::default(),
+                db.clone(),
+                Arc::new(MockApi::new()),
+                Bus::new(),
+            ),
+            heartbeat_service::new(db.clone()),
+            catalogue_service::new(&CatalogueConfig::default(), db.clone()),
+            Bus::new(),
+        )
+        .for_request(false)
+    }
+
+    /// Statements issued through the context, which has to be dropped by then.
+    fn statements(db: Arc<SeaORM>) -> usize {
+        Arc::try_unwrap(db)
+            .ok()
+            .expect("context is still alive")
+            .into_transaction_log()
+            .len()
+    }
+
+    fn new_quote() -> quote_model {
+        quote_model {
+            id: uuid::UUIDv4.fake(),
+            quote: lorem::en::Sentence(5..10).fake(),
+            author: "Ada Lovelace".to_string(),
+            likes: 0,
+            tags: vec![],
+            fingerprint: None,
+            source: "manual".to_string(),
+            external_id: None,
+            status: "approved".to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_history_batches_nested_lookups() {
+        let quotes: Vec<quote_model> = (0..3).map(|_| new_quote()).collect();
+        let views: Vec<view_model> = quotes
+            .iter()
+            .enumerate()
+            .map(|(index, quote)| view_model {
+                user_id: USER_ID.to_string(),
+                quote_id: quote.id.clone(),
+                liked: index == 0,
+                feedback: None,
+            })
+            .collect();
+
+        // The like status and view count batches run concurrently, so their rows carry
+        // the columns of both queries to be readable in either order.
+        let batch_rows: Vec<BTreeMap<&str, Value>> = views
+            .iter()
+            .map(|view| {
+                BTreeMap::from([
+                    ("user_id", view.user_id.clone().into()),
+                    ("quote_id", view.quote_id.clone().into()),
+                    ("liked", view.liked.into()),
+                    ("view_count", 2i64.into()),
+                ])
+            })
+            .collect();
+
+        let db = Arc::new(SeaORM::from_connection(
+            MockDatabase::new(DatabaseBackend::Postgres)
+                .append_query_results([views.clone()])
+                .append_query_results([quotes.clone()])
+                .append_query_results([batch_rows.clone(), batch_rows])
+                .into_connection(),
+        ));
+
+        let ctx = new_context(&db);
+        let schema = Schema::new(Query, Mutation, Subscription);
+        let query = format!(
+            r#"{{ history(userId: "{USER_ID}") {{ id views liked(userId: "{USER_ID}") }} }}"#
+        );
+        let (res, errors) = execute(&query, None, &schema, &Variables::new(), &ctx)
+            .await
+            .unwrap();
+        assert!(errors.is_empty());
+
+        let res = res.as_object_value().unwrap();
+        let history = res
+            .get_field_value("history")
+            .unwrap()
+            .as_list_value()
+            .unwrap();
+        assert_eq!(history.len(), quotes.len());
+        for (quote, view) in history.iter().zip(&views) {
+            let quote = quote.as_object_value().unwrap();
+            assert_eq!(
+                quote
+                    .get_field_value("id")
+                    .unwrap()
+                    .as_scalar_value::<String>(),
+                Some(&view.quote_id)
+            );
+            assert_eq!(
+                quote
+                    .get_field_value("views")
+                    .unwrap()
+                    .as_scalar_value::<i32>(),
+                Some(&2)
+            );
+            assert_eq!(
+                quote
+                    .get_field_value("liked")
+                    .unwrap()
+                    .as_scalar_value::<bool>(),
+                Some(&view.liked)
+            );
+        }
+
+        // history ids, quotes, like statuses and view counts: one statement each
+        // whatever the number of quotes.
+        drop(ctx);
+        assert_eq!(statements(db), 4);
+    }
+
+    #[tokio::test]
+    async fn test_quote_loader_caches_by_id() {
+        let quote = new_quote();
+
+        let db = Arc::new(SeaORM::from_connection(
+            MockDatabase::new(DatabaseBackend::Postgres)
+                .append_query_results([vec![quote.clone()]])
+                .into_connection(),
+        ));
+
+        let ctx = new_context(&db);
+        let schema = Schema::new(Query, Mutation, Subscription);
+        let query = format!(
+            r#"{{ first: quote(id: "{0}") {{ id }} second: quote(id: "{0}") {{ text }} }}"#,
+            quote.id
+        );
+        let (_, errors) = execute(&query, None, &schema, &Variables::new(), &ctx)
+            .await
+            .unwrap();
+        assert!(errors.is_empty());
+
+        drop(ctx);
+        assert_eq!(statements(db), 1);
+    }
+}
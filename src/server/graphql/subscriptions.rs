@@ -0,0 +1,285 @@
+use futures::channel::mpsc;
+use futures::{stream, Sink, SinkExt, Stream, StreamExt};
+use juniper::{DefaultScalarValue, ExecutionError, FieldError, Value};
+use juniper_graphql_ws::{graphql_transport_ws, graphql_ws};
+use std::convert::Infallible;
+use std::sync::Arc;
+
+use super::guard::Guard;
+use super::quotes_resolver::Schema;
+
+type TransportInput = graphql_transport_ws::Input<DefaultScalarValue>;
+type TransportOutput = graphql_transport_ws::Output<DefaultScalarValue>;
+type LegacyClientMessage = graphql_ws::ClientMessage<DefaultScalarValue>;
+type LegacyServerMessage = graphql_ws::ServerMessage<DefaultScalarValue>;
+
+/// Client message parsed before reaching the connection, which closes on a parse error.
+pub struct Parsed<M, E>(Result<M, E>);
+
+impl<E> TryFrom<Parsed<TransportInput, E>> for TransportInput {
+    type Error = E;
+
+    fn try_from(message: Parsed<TransportInput, E>) -> Result<Self, Self::Error> {
+        message.0
+    }
+}
+
+impl<E> TryFrom<Parsed<LegacyClientMessage, E>> for LegacyClientMessage {
+    type Error = E;
+
+    fn try_from(message: Parsed<LegacyClientMessage, E>) -> Result<Self, Self::Error> {
+        message.0
+    }
+}
+
+/// Splits a `graphql-transport-ws` connection, checking the operations the client
+/// subscribes to like `Guard::execute` does. A refused operation never reaches the
+/// connection, the client gets its error followed by the completion instead.
+pub fn guard_transport_ws<C, T>(
+    connection: C,
+    guard: Arc<Guard>,
+    schema: Arc<Schema>,
+) -> (
+    impl Sink<T, Error = Infallible>,
+    impl Stream<Item = TransportOutput>,
+)
+where
+    C: Sink<Parsed<TransportInput, T::Error>, Error = Infallible> + Stream<Item = TransportOutput>,
+    T: TryInto<TransportInput>,
+{
+    let (input, output) = connection.split();
+    let (refused_tx, refused_rx) = mpsc::unbounded();
+
+    let input = input.with_flat_map(move |message: T| {
+        let message = match message.try_into() {
+            Ok(graphql_transport_ws::Input::Message(
+                graphql_transport_ws::ClientMessage::Subscribe { id, mut payload },
+            )) => {
+                let hash = payload
+                    .extensions
+                    .get("persistedQuery")
+                    .and_then(|extension| extension.to_object_value())
+                    .and_then(|extension| extension.get("sha256Hash").copied())
+                    .and_then(|hash| hash.as_string_value())
+                    .map(str::to_string);
+                let prepared = guard.prepare_operation(
+                    &schema,
+                    hash.as_deref(),
+                    Some(payload.query).filter(|query| !query.is_empty()),
+                    payload.operation_name.as_deref(),
+                    &payload.variables,
+                );
+
+                match prepared {
+                    Ok(query) => {
+                        payload.query = query;
+                        Some(Ok(graphql_transport_ws::Input::Message(
+                            graphql_transport_ws::ClientMessage::Subscribe { id, payload },
+                        )))
+                    }
+                    Err(err) => {
+                        let refused = [
+                            graphql_transport_ws::ServerMessage::Next {
+                                id: id.clone(),
+                                payload: graphql_transport_ws::NextPayload {
+                                    data: Value::null(),
+                                    errors: errors(err),
+                                },
+                            },
+                            graphql_transport_ws::ServerMessage::Complete { id },
+                        ];
+                        for message in refused {
+                            let _ = refused_tx
+                                .unbounded_send(graphql_transport_ws::Output::Message(message));
+                        }
+                        None
+                    }
+                }
+            }
+            message => Some(message),
+        };
+
+        stream::iter(message.map(|message| Ok(Parsed(message))))
+    });
+
+    (input, stream::select(output, refused_rx))
+}
+
+/// Splits a legacy `graphql-ws` connection, checking the operations the client starts
+/// like `guard_transport_ws` does.
+pub fn guard_graphql_ws<C, T>(
+    connection: C,
+    guard: Arc<Guard>,
+    schema: Arc<Schema>,
+) -> (
+    impl Sink<T, Error = Infallible>,
+    impl Stream<Item = LegacyServerMessage>,
+)
+where
+    C: Sink<Parsed<LegacyClientMessage, T::Error>, Error = Infallible>
+        + Stream<Item = LegacyServerMessage>,
+    T: TryInto<LegacyClientMessage>,
+{
+    let (input, output) = connection.split();
+    let (refused_tx, refused_rx) = mpsc::unbounded();
+
+    let input = input.with_flat_map(move |message: T| {
+        let message = match message.try_into() {
+            Ok(graphql_ws::ClientMessage::Start { id, mut payload }) => {
+                let prepared = guard.prepare_operation(
+                    &schema,
+                    None,
+                    Some(payload.query).filter(|query| !query.is_empty()),
+                    payload.operation_name.as_deref(),
+                    &payload.variables,
+                );
+
+                match prepared {
+                    Ok(query) => {
+                        payload.query = query;
+                        Some(Ok(graphql_ws::ClientMessage::Start { id, payload }))
+                    }
+                    Err(err) => {
+                        let refused = [
+                            graphql_ws::ServerMessage::Data {
+                                id: id.clone(),
+                                payload: graphql_ws::DataPayload {
+                                    data: Value::null(),
+                                    errors: errors(err),
+                                },
+                            },
+                            graphql_ws::ServerMessage::Complete { id },
+                        ];
+                        for message in refused {
+                            let _ = refused_tx.unbounded_send(message);
+                        }
+                        None
+                    }
+                }
+            }
+            message => Some(message),
+        };
+
+        stream::iter(message.map(|message| Ok(Parsed(message))))
+    });
+
+    (input, stream::select(output, refused_rx))
+}
+
+/// Errors of a refused operation, answered like the refused requests of `/graphql`.
+fn errors(err: FieldError) -> Vec<ExecutionError<DefaultScalarValue>> {
+    vec![ExecutionError::at_origin(err)]
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::future;
+    use juniper::Variables;
+    use juniper_graphql_ws::graphql_transport_ws::{ClientMessage, Input, SubscribePayload};
+    use juniper_graphql_ws::{ArcSchema, ConnectionConfig};
+    use sea_orm::{DatabaseBackend, MockDatabase};
+    use std::pin::pin;
+    use std::time::Duration;
+
+    use super::super::quotes_resolver::{Context, Mutation, Query, Subscription};
+    use super::*;
+    use crate::catalogue::Service as catalogue_service;
+    use crate::config::{CatalogueConfig, GraphQLConfig, QuotesConfig};
+    use crate::database::seaorm::SeaORM;
+    use crate::events::Bus;
+    use crate::heartbeat::Heartbeat as heartbeat_service;
+    use crate::quote::traits::MockApi;
+    use crate::quote::Service as quote_service;
+
+    fn new_context() -> Context {
+        let db = Arc::new(SeaORM::from_connection(
+            MockDatabase::new(DatabaseBackend::Postgres).into_connection(),
+        ));
+
+        Context::new(
+            quote_service::new(
+                &QuotesConfig::default(),
+                db.clone(),
+                Arc::new(MockApi::new()),
+                Bus::new(),
+            ),
+            heartbeat_service::new(db.clone()),
+            catalogue_service::new(&CatalogueConfig::default(), db),
+            Bus::new(),
+        )
+        .for_request(false)
+    }
+
+    fn subscribe(id: &str, query: &str) -> TransportInput {
+        Input::Message(ClientMessage::Subscribe {
+            id: id.to_string(),
+            payload: SubscribePayload {
+                query: query.to_string(),
+                variables: Variables::new(),
+                operation_name: None,
+                extensions: Variables::new(),
+            },
+        })
+    }
+
+    #[tokio::test]
+    async fn test_refused_operation_does_not_reach_the_connection() {
+        let schema = Arc::new(Schema::new(Query, Mutation, Subscription));
+        let guard = Guard::new(&GraphQLConfig {
+            max_query_size: 20,
+            ..GraphQLConfig::default()
+        })
+        .unwrap();
+        let connection = graphql_transport_ws::Connection::new(
+            ArcSchema(schema.clone()),
+            ConnectionConfig::new(new_context()),
+        );
+        let (input, output) = guard_transport_ws(connection, Arc::new(guard), schema);
+
+        let mut input = pin!(input);
+        let messages = [
+            Input::Message(ClientMessage::ConnectionInit {
+                payload: Variables::new(),
+            }),
+            subscribe("large", "{ version version version }"),
+            subscribe("small", "{ version }"),
+        ];
+        for message in messages {
+            input.send(message).await.unwrap();
+        }
+
+        // the keep-alive pongs come at any time
+        let output = output
+            .map(|output| match output {
+                graphql_transport_ws::Output::Message(message) => {
+                    serde_json::to_value(message).unwrap()
+                }
+                graphql_transport_ws::Output::Close { code, message } => {
+                    panic!("connection closed with {code}: {message}")
+                }
+            })
+            .filter(|message| future::ready(message["type"] != "pong"))
+            .take(5);
+        let messages: Vec<serde_json::Value> =
+            tokio::time::timeout(Duration::from_secs(5), output.collect())
+                .await
+                .expect("connection did not answer");
+
+        let next = |id: &str| {
+            messages
+                .iter()
+                .find(|message| message["type"] == "next" && message["id"] == id)
+                .unwrap_or_else(|| panic!("no result for {id}"))
+        };
+        assert_eq!(
+            next("large")["payload"]["errors"][0]["extensions"]["code"],
+            "QUERY_TOO_LARGE"
+        );
+        assert!(next("small")["payload"]["data"]["version"].is_string());
+        for id in ["large", "small"] {
+            assert!(messages
+                .iter()
+                .any(|message| message["type"] == "complete" && message["id"] == id));
+        }
+    }
+}
@@ -0,0 +1,107 @@
+use std::collections::HashMap;
+use tonic::{Code, Status};
+use tonic_types::{ErrorDetails, StatusExt};
+
+use super::request_id;
+use super::structs::{is_not_found, violated_field};
+
+/// Domain of the `ErrorInfo` details attached to every error.
+const DOMAIN: &str = "quotes";
+
+/// Error with `ErrorInfo` details, the reason is the name of the code and the metadata
+/// has the id of the request.
+fn new(code: Code, message: impl Into<String>, action: &str) -> Status {
+    Status::with_error_details(code, message, error_info(code, action))
+}
+
+fn error_info(code: Code, action: &str) -> ErrorDetails {
+    let reason = format!("{code:?}")
+        .chars()
+        .fold(String::new(), |mut reason, char| {
+            if char.is_uppercase() && !reason.is_empty() {
+                reason.push('_');
+            }
+            reason.push(char.to_ascii_uppercase());
+            reason
+        });
+
+    let mut metadata = HashMap::from([("action".to_string(), action.to_string())]);
+    if let Some(id) = request_id::current() {
+        metadata.insert("request_id".to_string(), id);
+    }
+
+    ErrorDetails::with_error_info(reason, DOMAIN, metadata)
+}
+
+/// Invalid client input with a `BadRequest` violation of the field the error is about,
+/// the first of the candidates when the message does not name one of them.
+pub fn invalid_argument(fields: &[&str], err: anyhow::Error, action: &str) -> Status {
+    let description = format!("{err:#}");
+    let field = violated_field(fields, &description).or(fields.first().copied());
+
+    let mut details = error_info(Code::InvalidArgument, action);
+    if let Some(field) = field {
+        details.add_bad_request_violation(field, description.clone());
+    }
+
+    Status::with_error_details(Code::InvalidArgument, description, details)
+}
+
+pub fn unauthenticated(action: &str) -> Status {
+    new(Code::Unauthenticated, "unauthorized", action)
+}
+
+pub fn deadline_exceeded(action: &str) -> Status {
+    new(
+        Code::DeadlineExceeded,
+        format!("deadline exceeded, failed to {action}"),
+        action,
+    )
+}
+
+/// Maps a service error: missing entities become `NOT_FOUND`, anything else is logged
+/// and hidden from the client behind `INTERNAL`.
+pub fn from_service(err: anyhow::Error, action: &str) -> Status {
+    if is_not_found(&err) {
+        return new(Code::NotFound, "not found", action);
+    }
+
+    log::error!("failed to {action}: {err:#}");
+    new(Code::Internal, format!("failed to {action}"), action)
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::anyhow;
+
+    use super::*;
+
+    #[test]
+    fn test_invalid_argument_names_the_field() {
+        let status = invalid_argument(
+            &["date", "timezone", "quote_id"],
+            anyhow!("quote id must not be empty"),
+            "set quote of the day",
+        );
+
+        assert_eq!(status.code(), Code::InvalidArgument);
+        let details = status.get_error_details();
+        let violation = &details.bad_request().unwrap().field_violations[0];
+        assert_eq!(violation.field, "quote_id");
+        assert_eq!(violation.description, "quote id must not be empty");
+        assert_eq!(details.error_info().unwrap().reason, "INVALID_ARGUMENT");
+    }
+
+    #[test]
+    fn test_from_service_hides_internal_errors() {
+        let status = from_service(anyhow!("connection refused"), "get quote");
+
+        assert_eq!(status.code(), Code::Internal);
+        assert_eq!(status.message(), "failed to get quote");
+        let details = status.get_error_details();
+        let error_info = details.error_info().unwrap();
+        assert_eq!(error_info.reason, "INTERNAL");
+        assert_eq!(error_info.domain, DOMAIN);
+        assert_eq!(error_info.metadata["action"], "get quote");
+    }
+}
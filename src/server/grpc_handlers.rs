@@ -1,98 +1,631 @@
-use tonic::{Request, Response, Status};
+use std::future::Future;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio::time::Instant;
+use tokio_stream::wrappers::ReceiverStream;
+use tonic::{Request, Response, Status, Streaming};
 
+use super::admin::Admin;
+use super::grpc_errors as errors;
+use super::proto::feed_request::Action;
 use super::proto::quotes_server::Quotes;
-use super::proto::{Empty, Quote, UserAndQuoteIdRequest, UserIdRequest};
+use super::proto::{
+    AuditEntry, Author, AuthorsResponse, BatchRequest, DailyQuoteRequest, Empty, FeedRequest,
+    ListRequest, ModerateQuoteRequest, ModerationAuditResponse, Quote, QuoteIdRequest,
+    QuotesResponse, Report, ReportQuoteRequest, ReviewItem, ReviewQueueRequest,
+    ReviewQueueResponse, SetDailyQuoteRequest, StatsResponse, StreamQuotesRequest, Tag,
+    TagsResponse, UserAndQuoteIdRequest, UserIdRequest,
+};
+use crate::catalogue;
+use crate::catalogue::structs::{ListParams, Sort};
 use crate::heartbeat::Heartbeat;
+use crate::quote::structs::{
+    self as quote_structs, parse_batch_count, DailyQuoteOverride, DailyQuoteParams, Feedback,
+    ModerationDecision, QuoteReport, ReviewPage,
+};
 use crate::quote::Service;
 
+const DEFAULT_STREAM_INTERVAL: Duration = Duration::from_secs(5);
+const MIN_STREAM_INTERVAL: Duration = Duration::from_secs(1);
+/// Number of quotes buffered for a slow client before the stream waits for it.
+const STREAM_BUFFER: usize = 4;
+
 pub struct Grpc {
     heartbeat: Heartbeat,
     quotes: Service,
+    catalogue: catalogue::Service,
+    admin: Admin,
 }
 
 impl Grpc {
-    pub fn new(heartbeat: Heartbeat, quotes: Service) -> Self {
-        Grpc { heartbeat, quotes }
+    pub fn new(
+        heartbeat: Heartbeat,
+        quotes: Service,
+        catalogue: catalogue::Service,
+        admin: Admin,
+    ) -> Self {
+        Grpc {
+            heartbeat,
+            quotes,
+            catalogue,
+            admin,
+        }
+    }
+}
+
+impl From<quote_structs::Quote> for Quote {
+    fn from(quote: quote_structs::Quote) -> Self {
+        Quote {
+            id: quote.id,
+            quote: quote.quote,
+            author: quote.author,
+            tags: quote.tags,
+            likes: quote.likes,
+            source: quote.source,
+            external_id: quote.external_id,
+        }
+    }
+}
+
+impl From<quote_structs::ReviewItem> for ReviewItem {
+    fn from(item: quote_structs::ReviewItem) -> Self {
+        ReviewItem {
+            quote: Some(item.quote.into()),
+            status: item.status.as_str().to_string(),
+            reports: item
+                .reports
+                .into_iter()
+                .map(|report| Report {
+                    user_id: report.user_id,
+                    reason: report.reason,
+                    created_at: report.created_at,
+                })
+                .collect(),
+        }
+    }
+}
+
+impl From<quote_structs::AuditEntry> for AuditEntry {
+    fn from(entry: quote_structs::AuditEntry) -> Self {
+        AuditEntry {
+            id: entry.id,
+            quote_id: entry.quote_id,
+            previous_status: entry.previous_status.as_str().to_string(),
+            status: entry.status.as_str().to_string(),
+            moderator: entry.moderator,
+            note: entry.note,
+            created_at: entry.created_at,
+        }
+    }
+}
+
+/// Fields validated by `to_list_params`.
+const LIST_FIELDS: [&str; 3] = ["sort", "order", "limit"];
+
+fn to_list_params<S: Sort>(req: &ListRequest) -> anyhow::Result<ListParams<S>> {
+    ListParams::parse(
+        Some(req.sort.as_str()),
+        Some(req.order.as_str()),
+        Some(req.limit),
+        Some(req.offset),
+    )
+}
+
+fn to_stream_interval(interval_ms: u64) -> anyhow::Result<Duration> {
+    match Duration::from_millis(interval_ms) {
+        Duration::ZERO => Ok(DEFAULT_STREAM_INTERVAL),
+        interval if interval < MIN_STREAM_INTERVAL => Err(anyhow::anyhow!(
+            "interval must be at least {}ms",
+            MIN_STREAM_INTERVAL.as_millis()
+        )),
+        interval => Ok(interval),
+    }
+}
+
+/// Raw `authorization` metadata, checked against the configured admin token.
+fn authorization<T>(request: &Request<T>) -> Option<&str> {
+    request
+        .metadata()
+        .get("authorization")
+        .and_then(|value| value.to_str().ok())
+}
+
+/// Deadline of the call set by the client in the `grpc-timeout` metadata.
+fn deadline<T>(request: &Request<T>) -> Option<Instant> {
+    let timeout = request.metadata().get("grpc-timeout")?.to_str().ok()?;
+    parse_timeout(timeout).map(|timeout| Instant::now() + timeout)
+}
+
+/// Parses a `grpc-timeout` value, up to 8 digits followed by the unit.
+fn parse_timeout(value: &str) -> Option<Duration> {
+    if !value.is_ascii() || value.len() < 2 || value.len() > 9 {
+        return None;
+    }
+
+    let (amount, unit) = value.split_at(value.len() - 1);
+    let amount: u64 = amount.parse().ok()?;
+    match unit {
+        "H" => Some(Duration::from_secs(amount * 60 * 60)),
+        "M" => Some(Duration::from_secs(amount * 60)),
+        "S" => Some(Duration::from_secs(amount)),
+        "m" => Some(Duration::from_millis(amount)),
+        "u" => Some(Duration::from_micros(amount)),
+        "n" => Some(Duration::from_nanos(amount)),
+        _ => None,
     }
 }
 
+/// Output of the future, `None` when the deadline passes first.
+async fn until<F: Future>(deadline: Option<Instant>, future: F) -> Option<F::Output> {
+    match deadline {
+        Some(deadline) => tokio::time::timeout_at(deadline, future).await.ok(),
+        None => Some(future.await),
+    }
+}
+
+/// Runs a service call until the deadline, the call is dropped once the deadline passes
+/// which cancels its database queries and upstream requests.
+async fn call<T>(
+    deadline: Option<Instant>,
+    action: &str,
+    call: impl Future<Output = anyhow::Result<T>>,
+) -> Result<T, Status> {
+    until(deadline, call)
+        .await
+        .ok_or_else(|| errors::deadline_exceeded(action))?
+        .map_err(|err| errors::from_service(err, action))
+}
+
 #[tonic::async_trait]
 impl Quotes for Grpc {
-    async fn heartbeat(&self, _request: Request<Empty>) -> Result<Response<Empty>, Status> {
-        match self.heartbeat.ping_database().await {
-            Ok(_) => Ok(Response::new(Empty {})),
-            Err(err) => {
-                log::error!("failed to ping database: {err:#}");
-                Err(Status::new(
-                    tonic::Code::Internal,
-                    "failed to ping database",
-                ))
-            }
-        }
+    type StreamQuotesStream = ReceiverStream<Result<Quote, Status>>;
+    type QuoteFeedStream = ReceiverStream<Result<Quote, Status>>;
+
+    async fn heartbeat(&self, request: Request<Empty>) -> Result<Response<Empty>, Status> {
+        call(
+            deadline(&request),
+            "ping database",
+            self.heartbeat.ping_database(),
+        )
+        .await?;
+
+        Ok(Response::new(Empty {}))
     }
 
     async fn get_quote_handler(
         &self,
         request: Request<UserIdRequest>,
     ) -> Result<Response<Quote>, Status> {
+        let deadline = deadline(&request);
         let req = request.into_inner();
-        match self.quotes.get_quote(req.user_id.as_str()).await {
-            Ok(quote) => Ok(Response::new(Quote {
-                id: quote.id,
-                quote: quote.quote,
-                author: quote.author,
-                tags: quote.tags,
-                likes: quote.likes as i64,
-            })),
-            Err(err) => {
-                log::error!("failed to get quote: {err:#}");
-                Err(Status::new(tonic::Code::Internal, "failed to get quote"))
-            }
-        }
+        let quote = call(deadline, "get quote", self.quotes.get_quote(&req.user_id)).await?;
+
+        Ok(Response::new(quote.into()))
+    }
+
+    async fn get_quotes_handler(
+        &self,
+        request: Request<BatchRequest>,
+    ) -> Result<Response<QuotesResponse>, Status> {
+        let deadline = deadline(&request);
+        let req = request.into_inner();
+        let count = parse_batch_count(req.count)
+            .map_err(|err| errors::invalid_argument(&["count"], err, "get quotes"))?;
+
+        let quotes = call(
+            deadline,
+            "get quotes",
+            self.quotes.get_quotes(&req.user_id, count),
+        )
+        .await?;
+
+        Ok(Response::new(QuotesResponse {
+            quotes: quotes.into_iter().map(Quote::from).collect(),
+        }))
     }
 
     async fn get_same_quote_handler(
         &self,
         request: Request<UserAndQuoteIdRequest>,
     ) -> Result<Response<Quote>, Status> {
+        let deadline = deadline(&request);
         let req = request.into_inner();
-        match self
-            .quotes
-            .get_same_quote(req.user_id.as_str(), req.quote_id.as_str())
-            .await
-        {
-            Ok(quote) => Ok(Response::new(Quote {
-                id: quote.id,
-                quote: quote.quote,
-                author: quote.author,
-                tags: quote.tags,
-                likes: quote.likes as i64,
-            })),
-            Err(err) => {
-                log::error!("failed to get same quote: {err:#}");
-                Err(Status::new(
-                    tonic::Code::Internal,
-                    "failed to get same quote",
-                ))
-            }
-        }
+        let quote = call(
+            deadline,
+            "get same quote",
+            self.quotes.get_same_quote(&req.user_id, &req.quote_id),
+        )
+        .await?;
+
+        Ok(Response::new(quote.into()))
     }
 
     async fn like_quote_handler(
         &self,
         request: Request<UserAndQuoteIdRequest>,
     ) -> Result<Response<Empty>, Status> {
+        let deadline = deadline(&request);
         let req = request.into_inner();
-        match self
-            .quotes
-            .like_quote(req.user_id.as_str(), req.quote_id.as_str())
-            .await
-        {
-            Ok(_) => Ok(Response::new(Empty {})),
-            Err(err) => {
-                log::error!("failed to like quote: {err:#}");
-                Err(Status::new(tonic::Code::Internal, "failed to like quote"))
-            }
+        call(
+            deadline,
+            "like quote",
+            self.quotes.like_quote(&req.user_id, &req.quote_id),
+        )
+        .await?;
+
+        Ok(Response::new(Empty {}))
+    }
+
+    async fn dislike_quote(
+        &self,
+        request: Request<UserAndQuoteIdRequest>,
+    ) -> Result<Response<Empty>, Status> {
+        let deadline = deadline(&request);
+        let req = request.into_inner();
+        call(
+            deadline,
+            "save feedback",
+            self.quotes
+                .give_feedback(&req.user_id, &req.quote_id, Feedback::Dislike),
+        )
+        .await?;
+
+        Ok(Response::new(Empty {}))
+    }
+
+    async fn skip_quote(
+        &self,
+        request: Request<UserAndQuoteIdRequest>,
+    ) -> Result<Response<Empty>, Status> {
+        let deadline = deadline(&request);
+        let req = request.into_inner();
+        call(
+            deadline,
+            "save feedback",
+            self.quotes
+                .give_feedback(&req.user_id, &req.quote_id, Feedback::Skip),
+        )
+        .await?;
+
+        Ok(Response::new(Empty {}))
+    }
+
+    async fn get_tags_handler(
+        &self,
+        request: Request<ListRequest>,
+    ) -> Result<Response<TagsResponse>, Status> {
+        let params = to_list_params(request.get_ref())
+            .map_err(|err| errors::invalid_argument(&LIST_FIELDS, err, "get tags"))?;
+        let page = call(
+            deadline(&request),
+            "get tags",
+            self.catalogue.get_tags(params),
+        )
+        .await?;
+
+        Ok(Response::new(TagsResponse {
+            tags: page
+                .items
+                .into_iter()
+                .map(|tag| Tag {
+                    name: tag.name,
+                    quotes: tag.quotes,
+                })
+                .collect(),
+            total: page.total,
+        }))
+    }
+
+    async fn get_authors_handler(
+        &self,
+        request: Request<ListRequest>,
+    ) -> Result<Response<AuthorsResponse>, Status> {
+        let params = to_list_params(request.get_ref())
+            .map_err(|err| errors::invalid_argument(&LIST_FIELDS, err, "get authors"))?;
+        let page = call(
+            deadline(&request),
+            "get authors",
+            self.catalogue.get_authors(params),
+        )
+        .await?;
+
+        Ok(Response::new(AuthorsResponse {
+            authors: page
+                .items
+                .into_iter()
+                .map(|author| Author {
+                    name: author.name,
+                    quotes: author.quotes,
+                    likes: author.likes,
+                })
+                .collect(),
+            total: page.total,
+        }))
+    }
+
+    async fn get_quote_of_the_day_handler(
+        &self,
+        request: Request<DailyQuoteRequest>,
+    ) -> Result<Response<Quote>, Status> {
+        let deadline = deadline(&request);
+        let req = request.into_inner();
+        let params = DailyQuoteParams::parse(Some(req.timezone.as_str()), Some(req.tag.as_str()))
+            .map_err(|err| {
+            errors::invalid_argument(&["timezone", "tag"], err, "get quote of the day")
+        })?;
+
+        let quote = call(
+            deadline,
+            "get quote of the day",
+            self.quotes.get_quote_of_the_day(&params),
+        )
+        .await?;
+
+        Ok(Response::new(quote.into()))
+    }
+
+    async fn set_quote_of_the_day_handler(
+        &self,
+        request: Request<SetDailyQuoteRequest>,
+    ) -> Result<Response<Quote>, Status> {
+        if !self.admin.is_authorized(authorization(&request)) {
+            return Err(errors::unauthenticated("set quote of the day"));
+        }
+
+        let deadline = deadline(&request);
+        let req = request.into_inner();
+        let daily_override = DailyQuoteOverride::parse(
+            Some(req.date.as_str()),
+            Some(req.timezone.as_str()),
+            Some(req.tag.as_str()),
+            req.quote_id.as_str(),
+        )
+        .map_err(|err| {
+            errors::invalid_argument(
+                &["date", "timezone", "tag", "quote_id"],
+                err,
+                "set quote of the day",
+            )
+        })?;
+
+        let quote = call(
+            deadline,
+            "set quote of the day",
+            self.quotes.set_quote_of_the_day(&daily_override),
+        )
+        .await?;
+
+        Ok(Response::new(quote.into()))
+    }
+
+    async fn report_quote(
+        &self,
+        request: Request<ReportQuoteRequest>,
+    ) -> Result<Response<Empty>, Status> {
+        let deadline = deadline(&request);
+        let req = request.into_inner();
+        let report = QuoteReport::parse(&req.user_id, &req.quote_id, Some(req.reason.as_str()))
+            .map_err(|err| {
+                errors::invalid_argument(&["user_id", "quote_id", "reason"], err, "report quote")
+            })?;
+
+        call(deadline, "report quote", self.quotes.report_quote(&report)).await?;
+
+        Ok(Response::new(Empty {}))
+    }
+
+    async fn review_queue(
+        &self,
+        request: Request<ReviewQueueRequest>,
+    ) -> Result<Response<ReviewQueueResponse>, Status> {
+        if !self.admin.is_authorized(authorization(&request)) {
+            return Err(errors::unauthenticated("get review queue"));
         }
+
+        let deadline = deadline(&request);
+        let req = request.into_inner();
+        let page = ReviewPage::parse(Some(req.limit), Some(req.offset))
+            .map_err(|err| errors::invalid_argument(&["limit"], err, "get review queue"))?;
+
+        let queue = call(
+            deadline,
+            "get review queue",
+            self.quotes.get_review_queue(&page),
+        )
+        .await?;
+
+        Ok(Response::new(ReviewQueueResponse {
+            items: queue.into_iter().map(Into::into).collect(),
+        }))
+    }
+
+    async fn moderate_quote(
+        &self,
+        request: Request<ModerateQuoteRequest>,
+    ) -> Result<Response<AuditEntry>, Status> {
+        if !self.admin.is_authorized(authorization(&request)) {
+            return Err(errors::unauthenticated("moderate quote"));
+        }
+
+        let deadline = deadline(&request);
+        let req = request.into_inner();
+        let decision = ModerationDecision::parse(
+            &req.quote_id,
+            &req.status,
+            Some(req.moderator.as_str()),
+            Some(req.note.as_str()),
+        )
+        .map_err(|err| errors::invalid_argument(&["quote_id", "status"], err, "moderate quote"))?;
+
+        let entry = call(
+            deadline,
+            "moderate quote",
+            self.quotes.moderate_quote(&decision),
+        )
+        .await?;
+
+        Ok(Response::new(entry.into()))
+    }
+
+    async fn moderation_audit(
+        &self,
+        request: Request<QuoteIdRequest>,
+    ) -> Result<Response<ModerationAuditResponse>, Status> {
+        if !self.admin.is_authorized(authorization(&request)) {
+            return Err(errors::unauthenticated("get moderation audit"));
+        }
+
+        let deadline = deadline(&request);
+        let req = request.into_inner();
+        let entries = call(
+            deadline,
+            "get moderation audit",
+            self.quotes.get_moderation_audit(&req.quote_id),
+        )
+        .await?;
+
+        Ok(Response::new(ModerationAuditResponse {
+            entries: entries.into_iter().map(Into::into).collect(),
+        }))
+    }
+
+    async fn stats(&self, request: Request<Empty>) -> Result<Response<StatsResponse>, Status> {
+        if !self.admin.is_authorized(authorization(&request)) {
+            return Err(errors::unauthenticated("get stats"));
+        }
+
+        let stats = self.quotes.get_stats();
+
+        Ok(Response::new(StatsResponse {
+            rejected_quotes: stats.rejected_quotes,
+        }))
+    }
+
+    async fn stream_quotes(
+        &self,
+        request: Request<StreamQuotesRequest>,
+    ) -> Result<Response<Self::StreamQuotesStream>, Status> {
+        let deadline = deadline(&request);
+        let req = request.into_inner();
+        let period = to_stream_interval(req.interval_ms)
+            .map_err(|err| errors::invalid_argument(&["interval_ms"], err, "stream quotes"))?;
+
+        let quotes = self.quotes.clone();
+        let (tx, rx) = mpsc::channel(STREAM_BUFFER);
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(period);
+            let mut sent = 0;
+            while req.count == 0 || sent < req.count {
+                // the deadline covers the whole stream, waiting for the next tick included
+                let quote = call(deadline, "get quote", async {
+                    interval.tick().await;
+                    quotes.get_quote(&req.user_id).await
+                })
+                .await
+                .map(Quote::from);
+
+                let failed = quote.is_err();
+                if tx.send(quote).await.is_err() || failed {
+                    break;
+                }
+
+                sent += 1;
+            }
+        });
+
+        Ok(Response::new(ReceiverStream::new(rx)))
+    }
+
+    async fn quote_feed(
+        &self,
+        request: Request<Streaming<FeedRequest>>,
+    ) -> Result<Response<Self::QuoteFeedStream>, Status> {
+        let deadline = deadline(&request);
+        let mut stream = request.into_inner();
+
+        let quotes = self.quotes.clone();
+        let (tx, rx) = mpsc::channel(STREAM_BUFFER);
+        tokio::spawn(async move {
+            loop {
+                let req = match until(deadline, stream.message()).await {
+                    Some(Ok(Some(req))) => req,
+                    Some(_) => break,
+                    None => {
+                        let _ = tx.send(Err(errors::deadline_exceeded("read feed"))).await;
+                        break;
+                    }
+                };
+
+                let acted = match req.action() {
+                    Action::Like => call(
+                        deadline,
+                        "like quote",
+                        quotes.like_quote(&req.user_id, &req.quote_id),
+                    )
+                    .await
+                    .map(|_| ()),
+                    Action::Dislike => {
+                        call(
+                            deadline,
+                            "save feedback",
+                            quotes.give_feedback(&req.user_id, &req.quote_id, Feedback::Dislike),
+                        )
+                        .await
+                    }
+                    Action::Skip if !req.quote_id.is_empty() => {
+                        call(
+                            deadline,
+                            "save feedback",
+                            quotes.give_feedback(&req.user_id, &req.quote_id, Feedback::Skip),
+                        )
+                        .await
+                    }
+                    Action::Next | Action::Skip => Ok(()),
+                };
+
+                let quote = match acted {
+                    Ok(_) => call(deadline, "get quote", quotes.get_quote(&req.user_id))
+                        .await
+                        .map(Quote::from),
+                    Err(status) => Err(status),
+                };
+
+                let failed = quote.is_err();
+                if tx.send(quote).await.is_err() || failed {
+                    break;
+                }
+            }
+        });
+
+        Ok(Response::new(ReceiverStream::new(rx)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_timeout() {
+        assert_eq!(parse_timeout("100m"), Some(Duration::from_millis(100)));
+        assert_eq!(parse_timeout("2S"), Some(Duration::from_secs(2)));
+        assert_eq!(parse_timeout("1H"), Some(Duration::from_secs(3600)));
+        assert_eq!(parse_timeout("123456789m"), None);
+        assert_eq!(parse_timeout("10x"), None);
+        assert_eq!(parse_timeout("m"), None);
+    }
+
+    #[tokio::test]
+    async fn test_call_stops_at_deadline() {
+        let deadline = Some(Instant::now() + Duration::from_millis(10));
+
+        let status = call(deadline, "get quote", async {
+            tokio::time::sleep(Duration::from_secs(1)).await;
+            Ok(())
+        })
+        .await
+        .unwrap_err();
+
+        assert_eq!(status.code(), tonic::Code::DeadlineExceeded);
     }
 }
@@ -1,37 +1,99 @@
 mod proto {
     include!("proto/quotes.rs");
+
+    pub mod google {
+        // only the `HttpRule` annotations of the methods are read
+        #[allow(dead_code)]
+        pub mod api {
+            include!("proto/google.api.rs");
+        }
+    }
+
+    pub const FILE_DESCRIPTOR_SET: &[u8] = tonic::include_file_descriptor_set!("quotes_descriptor");
 }
 mod actix_handlers;
+mod admin;
 mod axum_handlers;
+mod cors;
 mod graphql;
+mod grpc_errors;
 mod grpc_handlers;
+mod openapi;
+mod request_id;
+mod rest;
 mod rocket_handlers;
+mod routes;
 mod structs;
+mod transcoding;
 
 use actix_web::dev::Server;
-use actix_web::middleware::Logger;
+use actix_web::middleware::{from_fn, Logger};
 use actix_web::{web, App, HttpServer};
 use anyhow::{Context, Ok, Result};
 use axum::{
-    routing::{get, patch},
-    Router,
+    middleware,
+    routing::{get, patch, post, put},
+    Extension, Router,
 };
 use env_logger::Env;
-use juniper::EmptySubscription;
 use proto::quotes_server::QuotesServer;
 use rocket::{build, Config};
+use std::io::Write;
 use std::net::SocketAddr;
+use std::sync::Arc;
 use tokio::net::TcpListener;
+use tonic::codec::CompressionEncoding;
+use tonic::service::Routes;
+use tonic_web::GrpcWebLayer;
 use tower_http::trace::{DefaultMakeSpan, DefaultOnResponse, TraceLayer};
 use tracing::Level;
 
-use crate::config::ServerConfig;
+use crate::catalogue;
+use crate::config::{CorsConfig, GraphQLConfig, ServerConfig};
+use crate::events::Bus;
 use crate::heartbeat::Heartbeat;
 use crate::quote::Service;
-use crate::server::graphql::quotes_resolver::{Mutation, Query, Schema};
+use crate::server::admin::Admin;
+use crate::server::cors::Cors;
+use crate::server::graphql::guard::Guard;
+use crate::server::graphql::quotes_resolver::{
+    Context as graphql_context, Mutation, Query, Schema, Subscription,
+};
+use crate::server::graphql::{GRAPHQL_ENDPOINT, SUBSCRIPTIONS_ENDPOINT};
 use crate::server::grpc_handlers::Grpc;
+use crate::server::openapi::{OPENAPI_ENDPOINT, SWAGGER_UI_ENDPOINT};
+use crate::server::rest::Rest;
 
-pub async fn start_rocket(cfg: &ServerConfig, heartbeat: Heartbeat, quotes: Service) -> Result<()> {
+/// Installs the logger of the servers, the lines logged while handling a request carry its id.
+/// Tracing events, the axum request traces included, are forwarded to it.
+pub fn init_logger() {
+    // the logger may already be installed when several servers share a process
+    let _ = env_logger::Builder::from_env(Env::default().default_filter_or("info"))
+        .format(|buf, record| {
+            let request_id = request_id::current()
+                .map(|id| format!(" request_id={id}"))
+                .unwrap_or_default();
+            writeln!(
+                buf,
+                "[{} {} {}{request_id}] {}",
+                buf.timestamp(),
+                record.level(),
+                record.target(),
+                record.args()
+            )
+        })
+        .try_init();
+}
+
+pub async fn start_rocket(
+    cfg: &ServerConfig,
+    graphql_cfg: &GraphQLConfig,
+    cors_cfg: &CorsConfig,
+    heartbeat: Heartbeat,
+    quotes: Service,
+    catalogue: catalogue::Service,
+    events: Bus,
+) -> Result<()> {
     let addr: SocketAddr = cfg.addr.parse().context("failed to parse address")?;
 
     let config = Config {
@@ -41,8 +103,19 @@ pub async fn start_rocket(cfg: &ServerConfig, heartbeat: Heartbeat, quotes: Serv
     };
 
     let rocket = build().configure(&config);
-    rocket_handlers::register_routes(rocket, heartbeat, quotes)
-        .context("failed to register fairings")?
+    let admin = Admin::new(&cfg.admin_token);
+    let guard = Guard::new(graphql_cfg).context("failed to create graphql guard")?;
+    let cors = Cors::new(cors_cfg).context("failed to create cors policy")?;
+    let context =
+        graphql_context::new(quotes.clone(), heartbeat.clone(), catalogue.clone(), events);
+    let rest = Rest::new(
+        heartbeat,
+        quotes,
+        catalogue,
+        admin.clone(),
+        cfg.http_cache_max_age,
+    );
+    rocket_handlers::register_routes(rocket, rest, context, admin, guard, cors)
         .launch()
         .await
         .context("failed to start server")?;
@@ -50,46 +123,147 @@ pub async fn start_rocket(cfg: &ServerConfig, heartbeat: Heartbeat, quotes: Serv
     Ok(())
 }
 
+/// Default format of the actix logger with the id of the request.
+const ACTIX_LOG_FORMAT: &str =
+    r#"%a "%r" %s %b "%{Referer}i" "%{User-Agent}i" %T request_id=%{x-request-id}o"#;
+
 pub async fn start_actix(
     cfg: &ServerConfig,
+    graphql_cfg: &GraphQLConfig,
+    cors_cfg: &CorsConfig,
     heartbeat: Heartbeat,
     quotes: Service,
+    catalogue: catalogue::Service,
+    events: Bus,
 ) -> Result<Server> {
     let addr: SocketAddr = cfg.addr.parse().context("failed to parse address")?;
 
-    let heartbeat = web::Data::new(heartbeat);
-    let quotes = web::Data::new(quotes);
+    let context = web::Data::new(graphql_context::new(
+        quotes.clone(),
+        heartbeat.clone(),
+        catalogue.clone(),
+        events,
+    ));
+    let admin = Admin::new(&cfg.admin_token);
+    let rest = web::Data::new(Rest::new(
+        heartbeat,
+        quotes,
+        catalogue,
+        admin.clone(),
+        cfg.http_cache_max_age,
+    ));
+    let admin = web::Data::new(admin);
+    let guard = web::Data::new(Guard::new(graphql_cfg).context("failed to create graphql guard")?);
+    let cors = web::Data::new(Cors::new(cors_cfg).context("failed to create cors policy")?);
 
-    env_logger::init_from_env(Env::default().default_filter_or("info"));
     Ok(HttpServer::new(move || {
-        let schema = web::Data::new(Schema::new(Query, Mutation, EmptySubscription::new()));
+        let schema = web::Data::new(Schema::new(Query, Mutation, Subscription));
         App::new()
-            .wrap(Logger::default())
-            .app_data(heartbeat.clone())
-            .app_data(quotes.clone())
+            .wrap(from_fn(actix_handlers::cors_middleware))
+            .wrap(from_fn(actix_handlers::request_id_middleware))
+            // the access line is written once the body is sent, out of the request id scope
+            .wrap(Logger::new(ACTIX_LOG_FORMAT))
+            .app_data(cors.clone())
+            .app_data(rest.clone())
+            .app_data(context.clone())
+            .app_data(admin.clone())
+            .app_data(guard.clone())
             .app_data(schema)
             .service(actix_handlers::heartbeat_handler)
             .service(actix_handlers::get_quote_handler)
+            .service(actix_handlers::get_quotes_handler)
+            .service(actix_handlers::get_quote_by_id_handler)
+            .service(actix_handlers::like_quote_by_id_handler)
             .service(actix_handlers::like_quote_handler)
+            .service(actix_handlers::dislike_quote_handler)
+            .service(actix_handlers::skip_quote_handler)
+            .service(actix_handlers::get_similar_quote_handler)
             .service(actix_handlers::get_same_quote_handler)
+            .service(actix_handlers::get_tags_handler)
+            .service(actix_handlers::get_authors_handler)
+            .service(actix_handlers::get_quote_of_the_day_handler)
+            .service(actix_handlers::set_quote_of_the_day_handler)
+            .service(actix_handlers::report_quote_handler)
+            .service(actix_handlers::get_review_queue_handler)
+            .service(actix_handlers::moderate_quote_handler)
+            .service(actix_handlers::get_moderation_audit_handler)
+            .service(actix_handlers::get_stats_handler)
+            .service(
+                actix_resource(GRAPHQL_ENDPOINT)
+                    .route(web::post().to(actix_handlers::post_graphql))
+                    .route(web::get().to(actix_handlers::get_graphql)),
+            )
+            .service(
+                actix_resource(SUBSCRIPTIONS_ENDPOINT)
+                    .route(web::get().to(actix_handlers::graphql_subscriptions)),
+            )
+            .service(actix_resource("/graphiql").route(web::get().to(actix_handlers::graphiql)))
+            .service(actix_resource("/playground").route(web::get().to(actix_handlers::playground)))
             .service(
-                web::resource("/graphql")
-                    .route(web::post().to(actix_handlers::graphql))
-                    .route(web::get().to(actix_handlers::graphql)),
+                actix_resource(OPENAPI_ENDPOINT)
+                    .route(web::get().to(actix_handlers::openapi_handler)),
             )
+            .service(
+                actix_resource(SWAGGER_UI_ENDPOINT)
+                    .route(web::get().to(actix_handlers::swagger_ui_handler)),
+            )
+            .default_service(web::to(actix_handlers::not_found_handler))
     })
     .bind(addr)?
     .run())
 }
 
-pub async fn start_grpc(cfg: &ServerConfig, heartbeat: Heartbeat, quotes: Service) -> Result<()> {
+/// Resource answering the other methods like the unknown routes, not with the actix 405.
+fn actix_resource(path: &str) -> actix_web::Resource {
+    web::resource(path).default_service(web::to(actix_handlers::not_found_handler))
+}
+
+pub async fn start_grpc(
+    cfg: &ServerConfig,
+    cors_cfg: &CorsConfig,
+    heartbeat: Heartbeat,
+    quotes: Service,
+    catalogue: catalogue::Service,
+) -> Result<()> {
     let addr: SocketAddr = cfg.addr.parse().context("failed to parse address")?;
-    let srv = Grpc::new(heartbeat, quotes);
+    let srv = Arc::new(Grpc::new(
+        heartbeat,
+        quotes,
+        catalogue,
+        Admin::new(&cfg.admin_token),
+    ));
+    let reflection = tonic_reflection::server::Builder::configure()
+        .register_encoded_file_descriptor_set(proto::FILE_DESCRIPTOR_SET)
+        .build_v1()
+        .context("failed to build reflection service")?;
+    let transcoding = transcoding::router(proto::FILE_DESCRIPTOR_SET, srv.clone())
+        .context("failed to build json transcoding")?;
+
+    // the gRPC-Web and JSON routes follow the same policy as the HTTP servers
+    let cors = Cors::new(cors_cfg)
+        .and_then(|cors| cors.layer(&GRPC_WEB_EXPOSED_HEADERS))
+        .context("failed to create cors policy")?;
 
     println!("GreeterServer listening on {addr}");
 
+    let quotes = QuotesServer::from_arc(srv)
+        .accept_compressed(CompressionEncoding::Gzip)
+        .accept_compressed(CompressionEncoding::Zstd)
+        .send_compressed(CompressionEncoding::Gzip)
+        .send_compressed(CompressionEncoding::Zstd);
+    // gRPC-Web is translated before the gRPC services only, it refuses the other HTTP/1.1
+    // requests, the transcoded JSON ones included
+    let routes = Routes::new(quotes)
+        .add_service(reflection)
+        .into_axum_router()
+        .layer(GrpcWebLayer::new())
+        .merge(transcoding)
+        .layer(middleware::from_fn(axum_handlers::request_id_middleware));
+
     tonic::transport::Server::builder()
-        .add_service(QuotesServer::new(srv))
+        .accept_http1(true)
+        .layer(cors)
+        .add_routes(Routes::from(routes))
         .serve(addr)
         .await
         .context("Failed to start grpc server")?;
@@ -97,25 +271,149 @@ pub async fn start_grpc(cfg: &ServerConfig, heartbeat: Heartbeat, quotes: Servic
     Ok(())
 }
 
+/// Headers the browsers let the gRPC-Web clients read.
+const GRPC_WEB_EXPOSED_HEADERS: [&str; 4] = [
+    "grpc-status",
+    "grpc-message",
+    "grpc-status-details-bin",
+    request_id::REQUEST_ID_HEADER,
+];
+
 pub async fn start_axum(
     cfg: &ServerConfig,
+    graphql_cfg: &GraphQLConfig,
+    cors_cfg: &CorsConfig,
     heartbeat: Heartbeat,
     quotes: Service,
+    catalogue: catalogue::Service,
+    events: Bus,
 ) -> Result<(TcpListener, Router)> {
     let addr: SocketAddr = cfg.addr.parse().context("failed to parse address")?;
+    let context =
+        graphql_context::new(quotes.clone(), heartbeat.clone(), catalogue.clone(), events);
+    let guard = Guard::new(graphql_cfg).context("failed to create graphql guard")?;
+    let cors = Cors::new(cors_cfg).context("failed to create cors policy")?;
 
-    let app = Router::new()
+    let rest = Rest::new(
+        heartbeat,
+        quotes,
+        catalogue,
+        Admin::new(&cfg.admin_token),
+        cfg.http_cache_max_age,
+    );
+
+    let routes = Router::new()
+        .route("/v1/heartbeat", get(axum_handlers::heartbeat_handler))
+        .route("/v1/quotes/random", get(axum_handlers::get_quote_handler))
+        .route("/v1/quotes", get(axum_handlers::get_quotes_handler))
+        .route(
+            "/v1/quotes/{id}",
+            get(axum_handlers::get_quote_by_id_handler),
+        )
+        .route(
+            "/v1/quotes/{id}/like",
+            patch(axum_handlers::like_quote_by_id_handler),
+        )
+        .route(
+            "/v1/quotes/{id}/dislike",
+            patch(axum_handlers::dislike_quote_handler),
+        )
+        .route(
+            "/v1/quotes/{id}/skip",
+            patch(axum_handlers::skip_quote_handler),
+        )
+        .route(
+            "/v1/quotes/{id}/similar",
+            get(axum_handlers::get_similar_quote_handler),
+        )
+        .route("/v1/tags", get(axum_handlers::get_tags_handler))
+        .route("/v1/authors", get(axum_handlers::get_authors_handler))
+        .route(
+            "/v1/daily",
+            get(axum_handlers::get_quote_of_the_day_handler),
+        )
+        .route(
+            "/v1/admin/daily",
+            put(axum_handlers::set_quote_of_the_day_handler),
+        )
+        .route(
+            "/v1/quotes/{id}/report",
+            post(axum_handlers::report_quote_handler),
+        )
+        .route(
+            "/v1/admin/reports",
+            get(axum_handlers::get_review_queue_handler),
+        )
+        .route(
+            "/v1/admin/quotes/{id}/moderation",
+            put(axum_handlers::moderate_quote_handler),
+        )
+        .route(
+            "/v1/admin/quotes/{id}/audit",
+            get(axum_handlers::get_moderation_audit_handler),
+        )
+        .route("/v1/admin/stats", get(axum_handlers::get_stats_handler))
         .route("/heartbeat", get(axum_handlers::heartbeat_handler))
-        .with_state(heartbeat)
         .route("/", get(axum_handlers::get_quote_handler))
+        .route("/batch", get(axum_handlers::get_quotes_handler))
         .route("/like", patch(axum_handlers::like_quote_handler))
         .route("/same", get(axum_handlers::get_same_quote_handler))
-        .with_state(quotes)
+        .route("/tags", get(axum_handlers::get_tags_handler))
+        .route("/authors", get(axum_handlers::get_authors_handler))
+        .route("/daily", get(axum_handlers::get_quote_of_the_day_handler))
+        .route(
+            "/admin/daily",
+            put(axum_handlers::set_quote_of_the_day_handler),
+        )
+        .with_state(rest)
+        .route(OPENAPI_ENDPOINT, get(axum_handlers::openapi_handler))
+        .route(SWAGGER_UI_ENDPOINT, get(axum_handlers::swagger_ui_handler))
+        .route(
+            GRAPHQL_ENDPOINT,
+            get(axum_handlers::get_graphql).post(axum_handlers::post_graphql),
+        )
+        .route(
+            SUBSCRIPTIONS_ENDPOINT,
+            get(axum_handlers::graphql_subscriptions),
+        )
+        .route(
+            "/graphiql",
+            get(juniper_axum::graphiql(
+                GRAPHQL_ENDPOINT,
+                SUBSCRIPTIONS_ENDPOINT,
+            )),
+        )
+        .route(
+            "/playground",
+            get(juniper_axum::playground(
+                GRAPHQL_ENDPOINT,
+                SUBSCRIPTIONS_ENDPOINT,
+            )),
+        )
+        .layer(Extension(Arc::new(Schema::new(
+            Query,
+            Mutation,
+            Subscription,
+        ))))
+        .fallback(axum_handlers::not_found_handler)
+        .method_not_allowed_fallback(axum_handlers::method_not_allowed_handler)
+        .layer(Extension(context))
+        .layer(Extension(Arc::new(guard)))
+        .layer(Extension(Admin::new(&cfg.admin_token)));
+
+    // preflights are answered before the routing, the routes don't list the OPTIONS method
+    let app = Router::new()
+        .fallback_service(routes)
+        .layer(middleware::from_fn_with_state(
+            Arc::new(cors),
+            axum_handlers::cors_middleware,
+        ))
         .layer(
             TraceLayer::new_for_http()
                 .make_span_with(DefaultMakeSpan::new().level(Level::INFO))
                 .on_response(DefaultOnResponse::new().level(Level::INFO)),
-        );
+        )
+        .layer(middleware::from_fn(axum_handlers::request_id_middleware));
 
     Ok((tokio::net::TcpListener::bind(addr).await?, app))
 }
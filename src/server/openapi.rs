@@ -0,0 +1,124 @@
+use utoipa::openapi::security::{HttpAuthScheme, HttpBuilder, SecurityScheme};
+use utoipa::{Modify, OpenApi};
+
+use super::axum_handlers;
+
+pub const OPENAPI_ENDPOINT: &str = "/openapi.json";
+pub const SWAGGER_UI_ENDPOINT: &str = "/swagger-ui";
+
+/// OpenAPI document of the REST and GraphQL endpoints, the same for every HTTP server.
+#[derive(OpenApi)]
+#[openapi(
+    info(
+        title = "Quotes service",
+        description = "It's a service that shows random quotes, allows to like them and show \
+            quote that is pretty similar to a specified one.\n\n\
+            Legacy routes without the `/v1` prefix are still served: `/` for a random quote, \
+            `/batch`, `/like`, `/same`, `/tags`, `/authors`, `/daily` and `/admin/daily`, they \
+            take the quote ID as the `quote_id` query parameter."
+    ),
+    paths(
+        axum_handlers::heartbeat_handler,
+        axum_handlers::get_quote_handler,
+        axum_handlers::get_quotes_handler,
+        axum_handlers::get_quote_by_id_handler,
+        axum_handlers::like_quote_by_id_handler,
+        axum_handlers::dislike_quote_handler,
+        axum_handlers::skip_quote_handler,
+        axum_handlers::get_similar_quote_handler,
+        axum_handlers::get_quote_of_the_day_handler,
+        axum_handlers::get_tags_handler,
+        axum_handlers::get_authors_handler,
+        axum_handlers::report_quote_handler,
+        axum_handlers::set_quote_of_the_day_handler,
+        axum_handlers::get_review_queue_handler,
+        axum_handlers::moderate_quote_handler,
+        axum_handlers::get_moderation_audit_handler,
+        axum_handlers::get_stats_handler,
+        axum_handlers::get_graphql,
+        axum_handlers::post_graphql,
+    ),
+    modifiers(&AdminToken),
+    tags(
+        (name = "quotes", description = "Random, similar and daily quotes"),
+        (name = "catalogue", description = "Tags and authors of the quotes"),
+        (name = "admin", description = "Requires the `ADMIN_TOKEN` bearer token"),
+        (name = "graphql", description = "GraphQL API over the same services"),
+        (name = "service", description = "Health of the service"),
+    )
+)]
+struct ApiDoc;
+
+struct AdminToken;
+
+impl Modify for AdminToken {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let components = openapi.components.get_or_insert_with(Default::default);
+        components.add_security_scheme(
+            "admin_token",
+            SecurityScheme::Http(HttpBuilder::new().scheme(HttpAuthScheme::Bearer).build()),
+        );
+    }
+}
+
+/// OpenAPI document as JSON.
+pub fn document() -> String {
+    // the document is built from static annotations, serializing it can not fail
+    ApiDoc::openapi()
+        .to_json()
+        .expect("failed to serialize openapi document")
+}
+
+/// Swagger UI page exploring the OpenAPI document, its assets are loaded from a CDN.
+pub fn swagger_ui() -> String {
+    format!(
+        r##"<!DOCTYPE html>
+<html lang="en">
+<head>
+  <meta charset="utf-8" />
+  <title>Quotes service API</title>
+  <link rel="stylesheet" href="https://unpkg.com/swagger-ui-dist@5/swagger-ui.css" />
+</head>
+<body>
+  <div id="swagger-ui"></div>
+  <script src="https://unpkg.com/swagger-ui-dist@5/swagger-ui-bundle.js" crossorigin></script>
+  <script>
+    window.onload = () => {{
+      window.ui = SwaggerUIBundle({{ url: "{OPENAPI_ENDPOINT}", dom_id: "#swagger-ui" }});
+    }};
+  </script>
+</body>
+</html>"##
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_document_describes_v1_routes() {
+        let document: serde_json::Value = serde_json::from_str(&document()).unwrap();
+
+        let paths = document["paths"].as_object().unwrap();
+        for path in [
+            "/v1/quotes/random",
+            "/v1/quotes/{id}/like",
+            "/v1/quotes/{id}/similar",
+            "/v1/quotes/{id}/report",
+            "/v1/admin/reports",
+            "/graphql",
+        ] {
+            assert!(paths.contains_key(path), "{path} is not documented");
+        }
+        assert!(paths["/graphql"]["post"].is_object());
+        assert_eq!(
+            document["info"]["version"],
+            env!("CARGO_PKG_VERSION").to_string()
+        );
+
+        let schemas = document["components"]["schemas"].as_object().unwrap();
+        assert!(schemas.contains_key("Quote"));
+        assert!(document["components"]["securitySchemes"]["admin_token"].is_object());
+    }
+}
@@ -0,0 +1,114 @@
+// This file is @generated by prost-build.
+/// Defines the HTTP configuration for an API service. It contains a list of
+/// [HttpRule][google.api.HttpRule], each specifying the mapping of an RPC method
+/// to one or more HTTP REST API methods.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Http {
+    /// A list of HTTP configuration rules that apply to individual API methods.
+    ///
+    /// **NOTE:** All service configuration rules follow "last one wins" order.
+    #[prost(message, repeated, tag = "1")]
+    pub rules: ::prost::alloc::vec::Vec<HttpRule>,
+    /// When set to true, URL path parameters will be fully URI-decoded except in
+    /// cases of single segment matches in reserved expansion, where "%2F" will be
+    /// left encoded.
+    ///
+    /// The default behavior is to not decode RFC 6570 reserved characters in multi
+    /// segment matches.
+    #[prost(bool, tag = "2")]
+    pub fully_decode_reserved_expansion: bool,
+}
+/// gRPC Transcoding is a feature for mapping between a gRPC method and one or
+/// more HTTP REST endpoints. It allows developers to build a single API service
+/// that supports both gRPC APIs and REST APIs.
+///
+/// Each mapping specifies a URL path template and an HTTP method. The path
+/// template may refer to one or more fields in the gRPC request message, as long
+/// as each field is a non-repeated field with a primitive (non-message) type.
+/// The path template controls how fields of the request message are mapped to
+/// the URL path.
+///
+/// Any fields in the request message which are not bound by the path template
+/// automatically become HTTP query parameters if there is no HTTP request body.
+///
+/// The special name `*` can be used in the body mapping to define that every
+/// field not bound by the path template should be mapped to the request body.
+///
+/// See <https://github.com/googleapis/googleapis/blob/master/google/api/http.proto>
+/// for the complete specification.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct HttpRule {
+    /// Selects a method to which this rule applies.
+    ///
+    /// Refer to [selector][google.api.DocumentationRule.selector] for syntax
+    /// details.
+    #[prost(string, tag = "1")]
+    pub selector: ::prost::alloc::string::String,
+    /// The name of the request field whose value is mapped to the HTTP request
+    /// body, or `*` for mapping all request fields not captured by the path
+    /// pattern to the HTTP body, or omitted for not having any HTTP request body.
+    ///
+    /// NOTE: the referred field must be present at the top-level of the request
+    /// message type.
+    #[prost(string, tag = "7")]
+    pub body: ::prost::alloc::string::String,
+    /// Optional. The name of the response field whose value is mapped to the HTTP
+    /// response body. When omitted, the entire response message will be used
+    /// as the HTTP response body.
+    ///
+    /// NOTE: The referred field must be present at the top-level of the response
+    /// message type.
+    #[prost(string, tag = "12")]
+    pub response_body: ::prost::alloc::string::String,
+    /// Additional HTTP bindings for the selector. Nested bindings must
+    /// not contain an `additional_bindings` field themselves (that is,
+    /// the nesting may only be one level deep).
+    #[prost(message, repeated, tag = "11")]
+    pub additional_bindings: ::prost::alloc::vec::Vec<HttpRule>,
+    /// Determines the URL pattern is matched by this rules. This pattern can be
+    /// used with any of the {get|put|post|delete|patch} methods. A custom method
+    /// can be defined using the 'custom' field.
+    #[prost(oneof = "http_rule::Pattern", tags = "2, 3, 4, 5, 6, 8")]
+    pub pattern: ::core::option::Option<http_rule::Pattern>,
+}
+/// Nested message and enum types in `HttpRule`.
+pub mod http_rule {
+    /// Determines the URL pattern is matched by this rules. This pattern can be
+    /// used with any of the {get|put|post|delete|patch} methods. A custom method
+    /// can be defined using the 'custom' field.
+    #[derive(Clone, PartialEq, ::prost::Oneof)]
+    pub enum Pattern {
+        /// Maps to HTTP GET. Used for listing and getting information about
+        /// resources.
+        #[prost(string, tag = "2")]
+        Get(::prost::alloc::string::String),
+        /// Maps to HTTP PUT. Used for replacing a resource.
+        #[prost(string, tag = "3")]
+        Put(::prost::alloc::string::String),
+        /// Maps to HTTP POST. Used for creating a resource or performing an action.
+        #[prost(string, tag = "4")]
+        Post(::prost::alloc::string::String),
+        /// Maps to HTTP DELETE. Used for deleting a resource.
+        #[prost(string, tag = "5")]
+        Delete(::prost::alloc::string::String),
+        /// Maps to HTTP PATCH. Used for updating a resource.
+        #[prost(string, tag = "6")]
+        Patch(::prost::alloc::string::String),
+        /// The custom pattern is used for specifying an HTTP method that is not
+        /// included in the `pattern` field, such as HEAD, or "*" to leave the
+        /// HTTP method unspecified for this rule. The wild-card rule is useful
+        /// for services that provide content to Web (HTML) clients.
+        #[prost(message, tag = "8")]
+        Custom(super::CustomHttpPattern),
+    }
+}
+/// A custom pattern is used for defining custom HTTP verb.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CustomHttpPattern {
+    /// The name of this custom HTTP verb.
+    #[prost(string, tag = "1")]
+    pub kind: ::prost::alloc::string::String,
+    /// The path matched by this custom verb.
+    #[prost(string, tag = "2")]
+    pub path: ::prost::alloc::string::String,
+}
@@ -1,9 +1,13 @@
 // This file is @generated by prost-build.
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase", default)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct UserIdRequest {
     #[prost(string, tag = "1")]
     pub user_id: ::prost::alloc::string::String,
 }
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase", default)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct UserAndQuoteIdRequest {
     #[prost(string, tag = "1")]
@@ -11,8 +15,169 @@ pub struct UserAndQuoteIdRequest {
     #[prost(string, tag = "2")]
     pub quote_id: ::prost::alloc::string::String,
 }
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct BatchRequest {
+    #[prost(string, tag = "1")]
+    pub user_id: ::prost::alloc::string::String,
+    #[prost(uint64, optional, tag = "2")]
+    pub count: ::core::option::Option<u64>,
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct StreamQuotesRequest {
+    #[prost(string, tag = "1")]
+    pub user_id: ::prost::alloc::string::String,
+    /// Delay between quotes, 5 seconds when not set.
+    #[prost(uint64, tag = "2")]
+    pub interval_ms: u64,
+    /// Number of quotes to send before closing the stream, unlimited when not set.
+    #[prost(uint64, tag = "3")]
+    pub count: u64,
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct FeedRequest {
+    #[prost(string, tag = "1")]
+    pub user_id: ::prost::alloc::string::String,
+    #[prost(enumeration = "feed_request::Action", tag = "2")]
+    pub action: i32,
+    /// Quote to like, skip or dislike, the last sent one. A skip without it only asks for the
+    /// next quote.
+    #[prost(string, tag = "3")]
+    pub quote_id: ::prost::alloc::string::String,
+}
+/// Nested message and enum types in `FeedRequest`.
+pub mod feed_request {
+    #[derive(
+        Clone,
+        Copy,
+        Debug,
+        PartialEq,
+        Eq,
+        Hash,
+        PartialOrd,
+        Ord,
+        ::prost::Enumeration
+    )]
+    #[repr(i32)]
+    pub enum Action {
+        Next = 0,
+        Like = 1,
+        Skip = 2,
+        Dislike = 3,
+    }
+    impl Action {
+        /// String value of the enum field names used in the ProtoBuf definition.
+        ///
+        /// The values are not transformed in any way and thus are considered stable
+        /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+        pub fn as_str_name(&self) -> &'static str {
+            match self {
+                Self::Next => "NEXT",
+                Self::Like => "LIKE",
+                Self::Skip => "SKIP",
+                Self::Dislike => "DISLIKE",
+            }
+        }
+        /// Creates an enum from field names used in the ProtoBuf definition.
+        pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+            match value {
+                "NEXT" => Some(Self::Next),
+                "LIKE" => Some(Self::Like),
+                "SKIP" => Some(Self::Skip),
+                "DISLIKE" => Some(Self::Dislike),
+                _ => None,
+            }
+        }
+    }
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ListRequest {
+    #[prost(string, tag = "1")]
+    pub sort: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub order: ::prost::alloc::string::String,
+    #[prost(uint64, tag = "3")]
+    pub limit: u64,
+    #[prost(uint64, tag = "4")]
+    pub offset: u64,
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct DailyQuoteRequest {
+    #[prost(string, tag = "1")]
+    pub timezone: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub tag: ::prost::alloc::string::String,
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SetDailyQuoteRequest {
+    #[prost(string, tag = "1")]
+    pub date: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub timezone: ::prost::alloc::string::String,
+    #[prost(string, tag = "3")]
+    pub tag: ::prost::alloc::string::String,
+    #[prost(string, tag = "4")]
+    pub quote_id: ::prost::alloc::string::String,
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ReportQuoteRequest {
+    #[prost(string, tag = "1")]
+    pub user_id: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub quote_id: ::prost::alloc::string::String,
+    #[prost(string, tag = "3")]
+    pub reason: ::prost::alloc::string::String,
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct ReviewQueueRequest {
+    #[prost(uint64, tag = "1")]
+    pub limit: u64,
+    #[prost(uint64, tag = "2")]
+    pub offset: u64,
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ModerateQuoteRequest {
+    #[prost(string, tag = "1")]
+    pub quote_id: ::prost::alloc::string::String,
+    /// pending, approved or hidden.
+    #[prost(string, tag = "2")]
+    pub status: ::prost::alloc::string::String,
+    /// admin when not set.
+    #[prost(string, tag = "3")]
+    pub moderator: ::prost::alloc::string::String,
+    #[prost(string, tag = "4")]
+    pub note: ::prost::alloc::string::String,
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct QuoteIdRequest {
+    #[prost(string, tag = "1")]
+    pub quote_id: ::prost::alloc::string::String,
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase", default)]
 #[derive(Clone, Copy, PartialEq, ::prost::Message)]
 pub struct Empty {}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase", default)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct Quote {
     #[prost(string, tag = "1")]
@@ -23,9 +188,124 @@ pub struct Quote {
     pub author: ::prost::alloc::string::String,
     #[prost(string, repeated, tag = "4")]
     pub tags: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
-    #[prost(int64, tag = "5")]
+    #[prost(int32, tag = "5")]
+    pub likes: i32,
+    #[prost(string, tag = "6")]
+    pub source: ::prost::alloc::string::String,
+    #[prost(string, optional, tag = "7")]
+    pub external_id: ::core::option::Option<::prost::alloc::string::String>,
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct QuotesResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub quotes: ::prost::alloc::vec::Vec<Quote>,
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Tag {
+    #[prost(string, tag = "1")]
+    pub name: ::prost::alloc::string::String,
+    #[prost(int64, tag = "2")]
+    pub quotes: i64,
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct TagsResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub tags: ::prost::alloc::vec::Vec<Tag>,
+    #[prost(uint64, tag = "2")]
+    pub total: u64,
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Author {
+    #[prost(string, tag = "1")]
+    pub name: ::prost::alloc::string::String,
+    #[prost(int64, tag = "2")]
+    pub quotes: i64,
+    #[prost(int64, tag = "3")]
     pub likes: i64,
 }
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct AuthorsResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub authors: ::prost::alloc::vec::Vec<Author>,
+    #[prost(uint64, tag = "2")]
+    pub total: u64,
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Report {
+    #[prost(string, tag = "1")]
+    pub user_id: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub reason: ::prost::alloc::string::String,
+    /// RFC 3339 time of the report.
+    #[prost(string, tag = "3")]
+    pub created_at: ::prost::alloc::string::String,
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ReviewItem {
+    #[prost(message, optional, tag = "1")]
+    pub quote: ::core::option::Option<Quote>,
+    #[prost(string, tag = "2")]
+    pub status: ::prost::alloc::string::String,
+    #[prost(message, repeated, tag = "3")]
+    pub reports: ::prost::alloc::vec::Vec<Report>,
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ReviewQueueResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub items: ::prost::alloc::vec::Vec<ReviewItem>,
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct AuditEntry {
+    #[prost(string, tag = "1")]
+    pub id: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub quote_id: ::prost::alloc::string::String,
+    #[prost(string, tag = "3")]
+    pub previous_status: ::prost::alloc::string::String,
+    #[prost(string, tag = "4")]
+    pub status: ::prost::alloc::string::String,
+    #[prost(string, tag = "5")]
+    pub moderator: ::prost::alloc::string::String,
+    #[prost(string, tag = "6")]
+    pub note: ::prost::alloc::string::String,
+    /// RFC 3339 time of the change.
+    #[prost(string, tag = "7")]
+    pub created_at: ::prost::alloc::string::String,
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ModerationAuditResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub entries: ::prost::alloc::vec::Vec<AuditEntry>,
+}
+/// Counters of the service since it started.
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct StatsResponse {
+    /// Quotes of the site or the corpus rejected by the validation.
+    #[prost(uint64, tag = "1")]
+    pub rejected_quotes: u64,
+}
 /// Generated client implementations.
 pub mod quotes_client {
     #![allow(
@@ -37,6 +317,14 @@ pub mod quotes_client {
     )]
     use tonic::codegen::*;
     use tonic::codegen::http::Uri;
+    /// Failed calls carry `google.rpc.Status` details: an `ErrorInfo` in the `quotes` domain whose
+    /// reason is the name of the code, and a `BadRequest` naming the field of invalid arguments.
+    /// The `grpc-timeout` deadline covers the service calls, the `x-request-id` metadata is echoed
+    /// back and generated when missing.
+    ///
+    /// The server also speaks gRPC-Web, and the unary methods are transcoded from HTTP/JSON following
+    /// their `google.api.http` rules: path and query parameters fill the request fields, JSON uses the
+    /// lowerCamelCase field names.
     #[derive(Debug, Clone)]
     pub struct QuotesClient<T> {
         inner: tonic::client::Grpc<T>,
@@ -156,6 +444,72 @@ pub mod quotes_client {
                 .insert(GrpcMethod::new("quotes.Quotes", "GetQuoteHandler"));
             self.inner.unary(req, path, codec).await
         }
+        pub async fn get_quotes_handler(
+            &mut self,
+            request: impl tonic::IntoRequest<super::BatchRequest>,
+        ) -> std::result::Result<tonic::Response<super::QuotesResponse>, tonic::Status> {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/quotes.Quotes/GetQuotesHandler",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("quotes.Quotes", "GetQuotesHandler"));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn stream_quotes(
+            &mut self,
+            request: impl tonic::IntoRequest<super::StreamQuotesRequest>,
+        ) -> std::result::Result<
+            tonic::Response<tonic::codec::Streaming<super::Quote>>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/quotes.Quotes/StreamQuotes",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("quotes.Quotes", "StreamQuotes"));
+            self.inner.server_streaming(req, path, codec).await
+        }
+        pub async fn quote_feed(
+            &mut self,
+            request: impl tonic::IntoStreamingRequest<Message = super::FeedRequest>,
+        ) -> std::result::Result<
+            tonic::Response<tonic::codec::Streaming<super::Quote>>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/quotes.Quotes/QuoteFeed");
+            let mut req = request.into_streaming_request();
+            req.extensions_mut().insert(GrpcMethod::new("quotes.Quotes", "QuoteFeed"));
+            self.inner.streaming(req, path, codec).await
+        }
         pub async fn get_same_quote_handler(
             &mut self,
             request: impl tonic::IntoRequest<super::UserAndQuoteIdRequest>,
@@ -198,87 +552,408 @@ pub mod quotes_client {
                 .insert(GrpcMethod::new("quotes.Quotes", "LikeQuoteHandler"));
             self.inner.unary(req, path, codec).await
         }
-    }
-}
-/// Generated server implementations.
-pub mod quotes_server {
-    #![allow(
-        unused_variables,
-        dead_code,
-        missing_docs,
-        clippy::wildcard_imports,
-        clippy::let_unit_value,
-    )]
-    use tonic::codegen::*;
-    /// Generated trait containing gRPC methods that should be implemented for use with QuotesServer.
-    #[async_trait]
-    pub trait Quotes: std::marker::Send + std::marker::Sync + 'static {
-        async fn heartbeat(
-            &self,
-            request: tonic::Request<super::Empty>,
-        ) -> std::result::Result<tonic::Response<super::Empty>, tonic::Status>;
-        async fn get_quote_handler(
-            &self,
-            request: tonic::Request<super::UserIdRequest>,
-        ) -> std::result::Result<tonic::Response<super::Quote>, tonic::Status>;
-        async fn get_same_quote_handler(
-            &self,
-            request: tonic::Request<super::UserAndQuoteIdRequest>,
-        ) -> std::result::Result<tonic::Response<super::Quote>, tonic::Status>;
-        async fn like_quote_handler(
-            &self,
-            request: tonic::Request<super::UserAndQuoteIdRequest>,
-        ) -> std::result::Result<tonic::Response<super::Empty>, tonic::Status>;
-    }
-    #[derive(Debug)]
-    pub struct QuotesServer<T> {
-        inner: Arc<T>,
-        accept_compression_encodings: EnabledCompressionEncodings,
-        send_compression_encodings: EnabledCompressionEncodings,
-        max_decoding_message_size: Option<usize>,
-        max_encoding_message_size: Option<usize>,
-    }
-    impl<T> QuotesServer<T> {
-        pub fn new(inner: T) -> Self {
-            Self::from_arc(Arc::new(inner))
-        }
-        pub fn from_arc(inner: Arc<T>) -> Self {
-            Self {
-                inner,
-                accept_compression_encodings: Default::default(),
-                send_compression_encodings: Default::default(),
-                max_decoding_message_size: None,
-                max_encoding_message_size: None,
-            }
-        }
-        pub fn with_interceptor<F>(
-            inner: T,
-            interceptor: F,
-        ) -> InterceptedService<Self, F>
-        where
-            F: tonic::service::Interceptor,
-        {
-            InterceptedService::new(Self::new(inner), interceptor)
+        /// Disliked and skipped quotes make their authors and tags picked less often for the user.
+        pub async fn dislike_quote(
+            &mut self,
+            request: impl tonic::IntoRequest<super::UserAndQuoteIdRequest>,
+        ) -> std::result::Result<tonic::Response<super::Empty>, tonic::Status> {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/quotes.Quotes/DislikeQuote",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("quotes.Quotes", "DislikeQuote"));
+            self.inner.unary(req, path, codec).await
         }
-        /// Enable decompressing requests with the given encoding.
-        #[must_use]
-        pub fn accept_compressed(mut self, encoding: CompressionEncoding) -> Self {
-            self.accept_compression_encodings.enable(encoding);
-            self
+        pub async fn skip_quote(
+            &mut self,
+            request: impl tonic::IntoRequest<super::UserAndQuoteIdRequest>,
+        ) -> std::result::Result<tonic::Response<super::Empty>, tonic::Status> {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/quotes.Quotes/SkipQuote");
+            let mut req = request.into_request();
+            req.extensions_mut().insert(GrpcMethod::new("quotes.Quotes", "SkipQuote"));
+            self.inner.unary(req, path, codec).await
         }
-        /// Compress responses with the given encoding, if the client supports it.
-        #[must_use]
-        pub fn send_compressed(mut self, encoding: CompressionEncoding) -> Self {
-            self.send_compression_encodings.enable(encoding);
-            self
+        pub async fn get_tags_handler(
+            &mut self,
+            request: impl tonic::IntoRequest<super::ListRequest>,
+        ) -> std::result::Result<tonic::Response<super::TagsResponse>, tonic::Status> {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/quotes.Quotes/GetTagsHandler",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("quotes.Quotes", "GetTagsHandler"));
+            self.inner.unary(req, path, codec).await
         }
-        /// Limits the maximum size of a decoded message.
-        ///
-        /// Default: `4MB`
-        #[must_use]
-        pub fn max_decoding_message_size(mut self, limit: usize) -> Self {
-            self.max_decoding_message_size = Some(limit);
-            self
+        pub async fn get_authors_handler(
+            &mut self,
+            request: impl tonic::IntoRequest<super::ListRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::AuthorsResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/quotes.Quotes/GetAuthorsHandler",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("quotes.Quotes", "GetAuthorsHandler"));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn get_quote_of_the_day_handler(
+            &mut self,
+            request: impl tonic::IntoRequest<super::DailyQuoteRequest>,
+        ) -> std::result::Result<tonic::Response<super::Quote>, tonic::Status> {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/quotes.Quotes/GetQuoteOfTheDayHandler",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("quotes.Quotes", "GetQuoteOfTheDayHandler"));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn set_quote_of_the_day_handler(
+            &mut self,
+            request: impl tonic::IntoRequest<super::SetDailyQuoteRequest>,
+        ) -> std::result::Result<tonic::Response<super::Quote>, tonic::Status> {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/quotes.Quotes/SetQuoteOfTheDayHandler",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("quotes.Quotes", "SetQuoteOfTheDayHandler"));
+            self.inner.unary(req, path, codec).await
+        }
+        /// A quote reported by enough users is hidden until an admin reviews it.
+        pub async fn report_quote(
+            &mut self,
+            request: impl tonic::IntoRequest<super::ReportQuoteRequest>,
+        ) -> std::result::Result<tonic::Response<super::Empty>, tonic::Status> {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/quotes.Quotes/ReportQuote",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut().insert(GrpcMethod::new("quotes.Quotes", "ReportQuote"));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn review_queue(
+            &mut self,
+            request: impl tonic::IntoRequest<super::ReviewQueueRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ReviewQueueResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/quotes.Quotes/ReviewQueue",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut().insert(GrpcMethod::new("quotes.Quotes", "ReviewQueue"));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn moderate_quote(
+            &mut self,
+            request: impl tonic::IntoRequest<super::ModerateQuoteRequest>,
+        ) -> std::result::Result<tonic::Response<super::AuditEntry>, tonic::Status> {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/quotes.Quotes/ModerateQuote",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("quotes.Quotes", "ModerateQuote"));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn moderation_audit(
+            &mut self,
+            request: impl tonic::IntoRequest<super::QuoteIdRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ModerationAuditResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/quotes.Quotes/ModerationAudit",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("quotes.Quotes", "ModerationAudit"));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn stats(
+            &mut self,
+            request: impl tonic::IntoRequest<super::Empty>,
+        ) -> std::result::Result<tonic::Response<super::StatsResponse>, tonic::Status> {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/quotes.Quotes/Stats");
+            let mut req = request.into_request();
+            req.extensions_mut().insert(GrpcMethod::new("quotes.Quotes", "Stats"));
+            self.inner.unary(req, path, codec).await
+        }
+    }
+}
+/// Generated server implementations.
+pub mod quotes_server {
+    #![allow(
+        unused_variables,
+        dead_code,
+        missing_docs,
+        clippy::wildcard_imports,
+        clippy::let_unit_value,
+    )]
+    use tonic::codegen::*;
+    /// Generated trait containing gRPC methods that should be implemented for use with QuotesServer.
+    #[async_trait]
+    pub trait Quotes: std::marker::Send + std::marker::Sync + 'static {
+        async fn heartbeat(
+            &self,
+            request: tonic::Request<super::Empty>,
+        ) -> std::result::Result<tonic::Response<super::Empty>, tonic::Status>;
+        async fn get_quote_handler(
+            &self,
+            request: tonic::Request<super::UserIdRequest>,
+        ) -> std::result::Result<tonic::Response<super::Quote>, tonic::Status>;
+        async fn get_quotes_handler(
+            &self,
+            request: tonic::Request<super::BatchRequest>,
+        ) -> std::result::Result<tonic::Response<super::QuotesResponse>, tonic::Status>;
+        /// Server streaming response type for the StreamQuotes method.
+        type StreamQuotesStream: tonic::codegen::tokio_stream::Stream<
+                Item = std::result::Result<super::Quote, tonic::Status>,
+            >
+            + std::marker::Send
+            + 'static;
+        async fn stream_quotes(
+            &self,
+            request: tonic::Request<super::StreamQuotesRequest>,
+        ) -> std::result::Result<
+            tonic::Response<Self::StreamQuotesStream>,
+            tonic::Status,
+        >;
+        /// Server streaming response type for the QuoteFeed method.
+        type QuoteFeedStream: tonic::codegen::tokio_stream::Stream<
+                Item = std::result::Result<super::Quote, tonic::Status>,
+            >
+            + std::marker::Send
+            + 'static;
+        async fn quote_feed(
+            &self,
+            request: tonic::Request<tonic::Streaming<super::FeedRequest>>,
+        ) -> std::result::Result<tonic::Response<Self::QuoteFeedStream>, tonic::Status>;
+        async fn get_same_quote_handler(
+            &self,
+            request: tonic::Request<super::UserAndQuoteIdRequest>,
+        ) -> std::result::Result<tonic::Response<super::Quote>, tonic::Status>;
+        async fn like_quote_handler(
+            &self,
+            request: tonic::Request<super::UserAndQuoteIdRequest>,
+        ) -> std::result::Result<tonic::Response<super::Empty>, tonic::Status>;
+        /// Disliked and skipped quotes make their authors and tags picked less often for the user.
+        async fn dislike_quote(
+            &self,
+            request: tonic::Request<super::UserAndQuoteIdRequest>,
+        ) -> std::result::Result<tonic::Response<super::Empty>, tonic::Status>;
+        async fn skip_quote(
+            &self,
+            request: tonic::Request<super::UserAndQuoteIdRequest>,
+        ) -> std::result::Result<tonic::Response<super::Empty>, tonic::Status>;
+        async fn get_tags_handler(
+            &self,
+            request: tonic::Request<super::ListRequest>,
+        ) -> std::result::Result<tonic::Response<super::TagsResponse>, tonic::Status>;
+        async fn get_authors_handler(
+            &self,
+            request: tonic::Request<super::ListRequest>,
+        ) -> std::result::Result<tonic::Response<super::AuthorsResponse>, tonic::Status>;
+        async fn get_quote_of_the_day_handler(
+            &self,
+            request: tonic::Request<super::DailyQuoteRequest>,
+        ) -> std::result::Result<tonic::Response<super::Quote>, tonic::Status>;
+        async fn set_quote_of_the_day_handler(
+            &self,
+            request: tonic::Request<super::SetDailyQuoteRequest>,
+        ) -> std::result::Result<tonic::Response<super::Quote>, tonic::Status>;
+        /// A quote reported by enough users is hidden until an admin reviews it.
+        async fn report_quote(
+            &self,
+            request: tonic::Request<super::ReportQuoteRequest>,
+        ) -> std::result::Result<tonic::Response<super::Empty>, tonic::Status>;
+        async fn review_queue(
+            &self,
+            request: tonic::Request<super::ReviewQueueRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ReviewQueueResponse>,
+            tonic::Status,
+        >;
+        async fn moderate_quote(
+            &self,
+            request: tonic::Request<super::ModerateQuoteRequest>,
+        ) -> std::result::Result<tonic::Response<super::AuditEntry>, tonic::Status>;
+        async fn moderation_audit(
+            &self,
+            request: tonic::Request<super::QuoteIdRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ModerationAuditResponse>,
+            tonic::Status,
+        >;
+        async fn stats(
+            &self,
+            request: tonic::Request<super::Empty>,
+        ) -> std::result::Result<tonic::Response<super::StatsResponse>, tonic::Status>;
+    }
+    /// Failed calls carry `google.rpc.Status` details: an `ErrorInfo` in the `quotes` domain whose
+    /// reason is the name of the code, and a `BadRequest` naming the field of invalid arguments.
+    /// The `grpc-timeout` deadline covers the service calls, the `x-request-id` metadata is echoed
+    /// back and generated when missing.
+    ///
+    /// The server also speaks gRPC-Web, and the unary methods are transcoded from HTTP/JSON following
+    /// their `google.api.http` rules: path and query parameters fill the request fields, JSON uses the
+    /// lowerCamelCase field names.
+    #[derive(Debug)]
+    pub struct QuotesServer<T> {
+        inner: Arc<T>,
+        accept_compression_encodings: EnabledCompressionEncodings,
+        send_compression_encodings: EnabledCompressionEncodings,
+        max_decoding_message_size: Option<usize>,
+        max_encoding_message_size: Option<usize>,
+    }
+    impl<T> QuotesServer<T> {
+        pub fn new(inner: T) -> Self {
+            Self::from_arc(Arc::new(inner))
+        }
+        pub fn from_arc(inner: Arc<T>) -> Self {
+            Self {
+                inner,
+                accept_compression_encodings: Default::default(),
+                send_compression_encodings: Default::default(),
+                max_decoding_message_size: None,
+                max_encoding_message_size: None,
+            }
+        }
+        pub fn with_interceptor<F>(
+            inner: T,
+            interceptor: F,
+        ) -> InterceptedService<Self, F>
+        where
+            F: tonic::service::Interceptor,
+        {
+            InterceptedService::new(Self::new(inner), interceptor)
+        }
+        /// Enable decompressing requests with the given encoding.
+        #[must_use]
+        pub fn accept_compressed(mut self, encoding: CompressionEncoding) -> Self {
+            self.accept_compression_encodings.enable(encoding);
+            self
+        }
+        /// Compress responses with the given encoding, if the client supports it.
+        #[must_use]
+        pub fn send_compressed(mut self, encoding: CompressionEncoding) -> Self {
+            self.send_compression_encodings.enable(encoding);
+            self
+        }
+        /// Limits the maximum size of a decoded message.
+        ///
+        /// Default: `4MB`
+        #[must_use]
+        pub fn max_decoding_message_size(mut self, limit: usize) -> Self {
+            self.max_decoding_message_size = Some(limit);
+            self
         }
         /// Limits the maximum size of an encoded message.
         ///
@@ -392,25 +1067,23 @@ pub mod quotes_server {
                     };
                     Box::pin(fut)
                 }
-                "/quotes.Quotes/GetSameQuoteHandler" => {
+                "/quotes.Quotes/GetQuotesHandler" => {
                     #[allow(non_camel_case_types)]
-                    struct GetSameQuoteHandlerSvc<T: Quotes>(pub Arc<T>);
-                    impl<
-                        T: Quotes,
-                    > tonic::server::UnaryService<super::UserAndQuoteIdRequest>
-                    for GetSameQuoteHandlerSvc<T> {
-                        type Response = super::Quote;
+                    struct GetQuotesHandlerSvc<T: Quotes>(pub Arc<T>);
+                    impl<T: Quotes> tonic::server::UnaryService<super::BatchRequest>
+                    for GetQuotesHandlerSvc<T> {
+                        type Response = super::QuotesResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::UserAndQuoteIdRequest>,
+                            request: tonic::Request<super::BatchRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as Quotes>::get_same_quote_handler(&inner, request).await
+                                <T as Quotes>::get_quotes_handler(&inner, request).await
                             };
                             Box::pin(fut)
                         }
@@ -421,7 +1094,7 @@ pub mod quotes_server {
                     let max_encoding_message_size = self.max_encoding_message_size;
                     let inner = self.inner.clone();
                     let fut = async move {
-                        let method = GetSameQuoteHandlerSvc(inner);
+                        let method = GetQuotesHandlerSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -437,25 +1110,26 @@ pub mod quotes_server {
                     };
                     Box::pin(fut)
                 }
-                "/quotes.Quotes/LikeQuoteHandler" => {
+                "/quotes.Quotes/StreamQuotes" => {
                     #[allow(non_camel_case_types)]
-                    struct LikeQuoteHandlerSvc<T: Quotes>(pub Arc<T>);
+                    struct StreamQuotesSvc<T: Quotes>(pub Arc<T>);
                     impl<
                         T: Quotes,
-                    > tonic::server::UnaryService<super::UserAndQuoteIdRequest>
-                    for LikeQuoteHandlerSvc<T> {
-                        type Response = super::Empty;
+                    > tonic::server::ServerStreamingService<super::StreamQuotesRequest>
+                    for StreamQuotesSvc<T> {
+                        type Response = super::Quote;
+                        type ResponseStream = T::StreamQuotesStream;
                         type Future = BoxFuture<
-                            tonic::Response<Self::Response>,
+                            tonic::Response<Self::ResponseStream>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::UserAndQuoteIdRequest>,
+                            request: tonic::Request<super::StreamQuotesRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as Quotes>::like_quote_handler(&inner, request).await
+                                <T as Quotes>::stream_quotes(&inner, request).await
                             };
                             Box::pin(fut)
                         }
@@ -466,7 +1140,628 @@ pub mod quotes_server {
                     let max_encoding_message_size = self.max_encoding_message_size;
                     let inner = self.inner.clone();
                     let fut = async move {
-                        let method = LikeQuoteHandlerSvc(inner);
+                        let method = StreamQuotesSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.server_streaming(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/quotes.Quotes/QuoteFeed" => {
+                    #[allow(non_camel_case_types)]
+                    struct QuoteFeedSvc<T: Quotes>(pub Arc<T>);
+                    impl<T: Quotes> tonic::server::StreamingService<super::FeedRequest>
+                    for QuoteFeedSvc<T> {
+                        type Response = super::Quote;
+                        type ResponseStream = T::QuoteFeedStream;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::ResponseStream>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<tonic::Streaming<super::FeedRequest>>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as Quotes>::quote_feed(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = QuoteFeedSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.streaming(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/quotes.Quotes/GetSameQuoteHandler" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetSameQuoteHandlerSvc<T: Quotes>(pub Arc<T>);
+                    impl<
+                        T: Quotes,
+                    > tonic::server::UnaryService<super::UserAndQuoteIdRequest>
+                    for GetSameQuoteHandlerSvc<T> {
+                        type Response = super::Quote;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::UserAndQuoteIdRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as Quotes>::get_same_quote_handler(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = GetSameQuoteHandlerSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/quotes.Quotes/LikeQuoteHandler" => {
+                    #[allow(non_camel_case_types)]
+                    struct LikeQuoteHandlerSvc<T: Quotes>(pub Arc<T>);
+                    impl<
+                        T: Quotes,
+                    > tonic::server::UnaryService<super::UserAndQuoteIdRequest>
+                    for LikeQuoteHandlerSvc<T> {
+                        type Response = super::Empty;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::UserAndQuoteIdRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as Quotes>::like_quote_handler(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = LikeQuoteHandlerSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/quotes.Quotes/DislikeQuote" => {
+                    #[allow(non_camel_case_types)]
+                    struct DislikeQuoteSvc<T: Quotes>(pub Arc<T>);
+                    impl<
+                        T: Quotes,
+                    > tonic::server::UnaryService<super::UserAndQuoteIdRequest>
+                    for DislikeQuoteSvc<T> {
+                        type Response = super::Empty;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::UserAndQuoteIdRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as Quotes>::dislike_quote(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = DislikeQuoteSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/quotes.Quotes/SkipQuote" => {
+                    #[allow(non_camel_case_types)]
+                    struct SkipQuoteSvc<T: Quotes>(pub Arc<T>);
+                    impl<
+                        T: Quotes,
+                    > tonic::server::UnaryService<super::UserAndQuoteIdRequest>
+                    for SkipQuoteSvc<T> {
+                        type Response = super::Empty;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::UserAndQuoteIdRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as Quotes>::skip_quote(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = SkipQuoteSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/quotes.Quotes/GetTagsHandler" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetTagsHandlerSvc<T: Quotes>(pub Arc<T>);
+                    impl<T: Quotes> tonic::server::UnaryService<super::ListRequest>
+                    for GetTagsHandlerSvc<T> {
+                        type Response = super::TagsResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ListRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as Quotes>::get_tags_handler(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = GetTagsHandlerSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/quotes.Quotes/GetAuthorsHandler" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetAuthorsHandlerSvc<T: Quotes>(pub Arc<T>);
+                    impl<T: Quotes> tonic::server::UnaryService<super::ListRequest>
+                    for GetAuthorsHandlerSvc<T> {
+                        type Response = super::AuthorsResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ListRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as Quotes>::get_authors_handler(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = GetAuthorsHandlerSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/quotes.Quotes/GetQuoteOfTheDayHandler" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetQuoteOfTheDayHandlerSvc<T: Quotes>(pub Arc<T>);
+                    impl<T: Quotes> tonic::server::UnaryService<super::DailyQuoteRequest>
+                    for GetQuoteOfTheDayHandlerSvc<T> {
+                        type Response = super::Quote;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::DailyQuoteRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as Quotes>::get_quote_of_the_day_handler(&inner, request)
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = GetQuoteOfTheDayHandlerSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/quotes.Quotes/SetQuoteOfTheDayHandler" => {
+                    #[allow(non_camel_case_types)]
+                    struct SetQuoteOfTheDayHandlerSvc<T: Quotes>(pub Arc<T>);
+                    impl<
+                        T: Quotes,
+                    > tonic::server::UnaryService<super::SetDailyQuoteRequest>
+                    for SetQuoteOfTheDayHandlerSvc<T> {
+                        type Response = super::Quote;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::SetDailyQuoteRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as Quotes>::set_quote_of_the_day_handler(&inner, request)
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = SetQuoteOfTheDayHandlerSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/quotes.Quotes/ReportQuote" => {
+                    #[allow(non_camel_case_types)]
+                    struct ReportQuoteSvc<T: Quotes>(pub Arc<T>);
+                    impl<
+                        T: Quotes,
+                    > tonic::server::UnaryService<super::ReportQuoteRequest>
+                    for ReportQuoteSvc<T> {
+                        type Response = super::Empty;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ReportQuoteRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as Quotes>::report_quote(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = ReportQuoteSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/quotes.Quotes/ReviewQueue" => {
+                    #[allow(non_camel_case_types)]
+                    struct ReviewQueueSvc<T: Quotes>(pub Arc<T>);
+                    impl<
+                        T: Quotes,
+                    > tonic::server::UnaryService<super::ReviewQueueRequest>
+                    for ReviewQueueSvc<T> {
+                        type Response = super::ReviewQueueResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ReviewQueueRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as Quotes>::review_queue(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = ReviewQueueSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/quotes.Quotes/ModerateQuote" => {
+                    #[allow(non_camel_case_types)]
+                    struct ModerateQuoteSvc<T: Quotes>(pub Arc<T>);
+                    impl<
+                        T: Quotes,
+                    > tonic::server::UnaryService<super::ModerateQuoteRequest>
+                    for ModerateQuoteSvc<T> {
+                        type Response = super::AuditEntry;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ModerateQuoteRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as Quotes>::moderate_quote(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = ModerateQuoteSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/quotes.Quotes/ModerationAudit" => {
+                    #[allow(non_camel_case_types)]
+                    struct ModerationAuditSvc<T: Quotes>(pub Arc<T>);
+                    impl<T: Quotes> tonic::server::UnaryService<super::QuoteIdRequest>
+                    for ModerationAuditSvc<T> {
+                        type Response = super::ModerationAuditResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::QuoteIdRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as Quotes>::moderation_audit(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = ModerationAuditSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/quotes.Quotes/Stats" => {
+                    #[allow(non_camel_case_types)]
+                    struct StatsSvc<T: Quotes>(pub Arc<T>);
+                    impl<T: Quotes> tonic::server::UnaryService<super::Empty>
+                    for StatsSvc<T> {
+                        type Response = super::StatsResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::Empty>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as Quotes>::stats(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = StatsSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -0,0 +1,71 @@
+use std::future::Future;
+
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+/// Longest incoming request id kept, longer ones are replaced.
+const MAX_LENGTH: usize = 128;
+
+tokio::task_local! {
+    static REQUEST_ID: String;
+}
+
+/// Keeps the id sent by the client or a proxy when it's safe to echo and log, generates one otherwise.
+pub fn from_header(value: Option<&str>) -> String {
+    match value {
+        Some(id)
+            if !id.is_empty()
+                && id.len() <= MAX_LENGTH
+                && id
+                    .chars()
+                    .all(|char| char.is_ascii_alphanumeric() || "-_.:".contains(char)) =>
+        {
+            id.to_string()
+        }
+        _ => uuid::Uuid::new_v4().to_string(),
+    }
+}
+
+/// Runs the handling of a request, the logs and errors within it carry its id.
+pub async fn scope<F: Future>(request_id: String, f: F) -> F::Output {
+    REQUEST_ID.scope(request_id, f).await
+}
+
+pub fn sync_scope<R>(request_id: String, f: impl FnOnce() -> R) -> R {
+    REQUEST_ID.sync_scope(request_id, f)
+}
+
+/// Id of the request being handled, if any.
+pub fn current() -> Option<String> {
+    REQUEST_ID.try_with(Clone::clone).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_header() {
+        assert_eq!(from_header(Some("abc-123_x.y:z")), "abc-123_x.y:z");
+
+        for value in [None, Some(""), Some("new\nline"), Some("a b")] {
+            let id = from_header(value);
+            assert!(uuid::Uuid::parse_str(&id).is_ok(), "{value:?} is kept");
+        }
+        assert_ne!(
+            from_header(Some(&"a".repeat(MAX_LENGTH + 1))).len(),
+            MAX_LENGTH + 1
+        );
+    }
+
+    #[tokio::test]
+    async fn test_scope() {
+        assert_eq!(current(), None);
+
+        let id = scope("abc".to_string(), async { current() }).await;
+        assert_eq!(id.as_deref(), Some("abc"));
+        assert_eq!(
+            sync_scope("def".to_string(), current).as_deref(),
+            Some("def")
+        );
+    }
+}
@@ -0,0 +1,748 @@
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use utoipa::ToSchema;
+
+use super::admin::Admin;
+use super::graphql::guard::GetRequest;
+use super::request_id;
+use super::routes;
+use super::structs::{
+    is_not_found, violated_field, BatchQuery, DailyOverrideQuery, DailyQuery, ListQuery,
+    ModerationQuery, ReportQuery, ReviewQuery, UserAndQuoteID, UserID,
+};
+use crate::catalogue;
+use crate::heartbeat::Heartbeat;
+use crate::quote::{
+    structs::{parse_batch_count, Feedback},
+    Service,
+};
+
+pub const JSON: &str = "application/json";
+
+/// Response of a REST endpoint, converted as is by the actix, axum and rocket adapters
+/// so every server answers with the same status, content type and body.
+#[derive(Debug, PartialEq)]
+pub struct Reply {
+    pub status: u16,
+    pub content_type: Option<&'static str>,
+    pub body: String,
+    pub headers: Vec<(&'static str, String)>,
+}
+
+/// Body of every failed request.
+#[derive(Serialize, ToSchema)]
+pub struct ErrorBody<'a> {
+    /// `BAD_REQUEST`, `UNAUTHORIZED`, `NOT_FOUND`, `INTERNAL`, ...
+    code: &'a str,
+    message: &'a str,
+    /// Invalid query parameter, if the error is about one
+    details: Vec<FieldViolation<'a>>,
+    /// Id of the request, the one sent in `X-Request-Id` or a generated one
+    request_id: Option<String>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct FieldViolation<'a> {
+    field: &'a str,
+    description: &'a str,
+}
+
+impl Reply {
+    /// Successful reply without a body, failures go through [`Reply::from_status`].
+    pub fn status(status: u16) -> Self {
+        Reply {
+            status,
+            content_type: None,
+            body: String::new(),
+            headers: Vec::new(),
+        }
+    }
+
+    pub fn json<T: Serialize>(value: &T) -> Self {
+        match serde_json::to_string(value) {
+            Ok(body) => Reply {
+                status: 200,
+                content_type: Some(JSON),
+                body,
+                headers: Vec::new(),
+            },
+            Err(err) => Reply::internal("serialize response", err.into()),
+        }
+    }
+
+    /// Error envelope with the id of the request being handled.
+    pub fn error(status: u16, message: &str, field: Option<&str>) -> Self {
+        let details = field
+            .map(|field| FieldViolation {
+                field,
+                description: message,
+            })
+            .into_iter()
+            .collect();
+        let body = ErrorBody {
+            code: error_code(status),
+            message,
+            details,
+            request_id: request_id::current(),
+        };
+
+        Reply {
+            status,
+            content_type: Some(JSON),
+            // a struct of strings always serializes
+            body: serde_json::to_string(&body).unwrap_or_default(),
+            headers: Vec::new(),
+        }
+    }
+
+    /// Tags a successful reply with the `ETag` of its body and the `Cache-Control` policy,
+    /// a client already having the same body gets an empty `304`.
+    pub fn cacheable(self, if_none_match: Option<&str>, cache_control: &str) -> Self {
+        if self.status != 200 {
+            return self;
+        }
+
+        let etag = format!("\"{:x}\"", Sha256::digest(&self.body));
+        let headers = vec![
+            ("etag", etag.clone()),
+            ("cache-control", cache_control.to_string()),
+        ];
+        if if_none_match.is_some_and(|tags| matches_etag(tags, &etag)) {
+            return Reply {
+                headers,
+                ..Reply::status(304)
+            };
+        }
+
+        Reply { headers, ..self }
+    }
+
+    /// Reply to the requests the handlers don't answer themselves: preflights and
+    /// the statuses set by the frameworks.
+    pub fn from_status(status: u16) -> Self {
+        if status < 400 {
+            return Reply::status(status);
+        }
+
+        let message = error_code(status).replace('_', " ").to_lowercase();
+        Reply::error(status, &message, None)
+    }
+
+    /// Invalid parameter, named in the details when the message is about one of the fields.
+    pub fn invalid(fields: &[&str], err: impl ToString) -> Self {
+        let message = err.to_string();
+        Reply::error(400, &message, violated_field(fields, &message))
+    }
+
+    /// Logs the error and hides it from the client.
+    pub fn internal(action: &str, err: anyhow::Error) -> Self {
+        log::error!("failed to {action}: {err:#}");
+        Reply::error(500, &format!("failed to {action}"), None)
+    }
+
+    /// Answer to the missing quotes and the requests that don't match any route.
+    pub fn not_found() -> Self {
+        Reply::error(404, "not found", None)
+    }
+
+    /// Answer to the requests no route takes, `405` with the `Allow` header when the path
+    /// is served with other methods.
+    pub fn unrouted(path: &str) -> Self {
+        let methods = routes::allowed_methods(path);
+        if methods.is_empty() {
+            return Reply::not_found();
+        }
+
+        Reply {
+            headers: vec![("allow", methods.join(", "))],
+            ..Reply::error(405, "method not allowed", None)
+        }
+    }
+}
+
+/// Weak comparison of `If-None-Match` with the current tag, as required for `GET` requests.
+fn matches_etag(if_none_match: &str, etag: &str) -> bool {
+    if_none_match
+        .split(',')
+        .map(str::trim)
+        .any(|tag| tag == "*" || tag.trim_start_matches("W/") == etag)
+}
+
+fn error_code(status: u16) -> &'static str {
+    match status {
+        400 => "BAD_REQUEST",
+        401 => "UNAUTHORIZED",
+        403 => "FORBIDDEN",
+        404 => "NOT_FOUND",
+        405 => "METHOD_NOT_ALLOWED",
+        413 => "PAYLOAD_TOO_LARGE",
+        422 => "UNPROCESSABLE_ENTITY",
+        500.. => "INTERNAL",
+        _ => "BAD_REQUEST",
+    }
+}
+
+/// Query parameters of the endpoints, the errors about them name the field.
+const USER: &[&str] = &["user_id"];
+const USER_AND_QUOTE: &[&str] = &["user_id", "quote_id"];
+const BATCH: &[&str] = &["user_id", "count"];
+const LIST: &[&str] = &["sort", "order", "limit", "offset"];
+const DAILY: &[&str] = &["timezone", "tag"];
+const DAILY_OVERRIDE: &[&str] = &["date", "timezone", "tag", "quote_id"];
+const REPORT: &[&str] = &["user_id", "reason"];
+const REVIEW: &[&str] = &["limit", "offset"];
+const MODERATION: &[&str] = &["status", "moderator", "note"];
+const GRAPHQL: &[&str] = &["query", "id", "operationName", "variables", "extensions"];
+
+/// Framework agnostic REST handlers, the adapters only pass them the raw query string,
+/// path parameters and headers of the request.
+#[derive(Clone)]
+pub struct Rest {
+    heartbeat: Heartbeat,
+    quotes: Service,
+    catalogue: catalogue::Service,
+    admin: Admin,
+    /// `Cache-Control` of the cacheable reads.
+    cache_control: String,
+}
+
+impl Rest {
+    /// The cacheable reads may be reused for `cache_max_age` seconds, zero makes clients
+    /// revalidate them every time.
+    pub fn new(
+        heartbeat: Heartbeat,
+        quotes: Service,
+        catalogue: catalogue::Service,
+        admin: Admin,
+        cache_max_age: u64,
+    ) -> Self {
+        let cache_control = match cache_max_age {
+            0 => "no-cache".to_string(),
+            max_age => format!("public, max-age={max_age}"),
+        };
+
+        Rest {
+            heartbeat,
+            quotes,
+            catalogue,
+            admin,
+            cache_control,
+        }
+    }
+
+    pub async fn heartbeat(&self) -> Reply {
+        match self.heartbeat.ping_database().await {
+            Ok(_) => Reply::status(200),
+            Err(err) => Reply::internal("ping database", err),
+        }
+    }
+
+    pub async fn get_quote(&self, query: Option<&str>) -> Reply {
+        let query: UserID = match parse_query(query, USER) {
+            Ok(query) => query,
+            Err(reply) => return reply,
+        };
+
+        match self.quotes.get_quote(&query.user_id).await {
+            Ok(quote) => Reply::json(&quote),
+            Err(err) => Reply::internal("get quote", err),
+        }
+    }
+
+    /// Quote by its ID, `if_none_match` is the raw `If-None-Match` header.
+    pub async fn get_quote_by_id(&self, quote_id: &str, if_none_match: Option<&str>) -> Reply {
+        match self.quotes.get_quote_by_id(quote_id).await {
+            Ok(quote) => Reply::json(&quote).cacheable(if_none_match, &self.cache_control),
+            Err(err) if is_not_found(&err) => Reply::not_found(),
+            Err(err) => Reply::internal("get quote", err),
+        }
+    }
+
+    pub async fn get_quotes(&self, query: Option<&str>) -> Reply {
+        let query: BatchQuery = match parse_query(query, BATCH) {
+            Ok(query) => query,
+            Err(reply) => return reply,
+        };
+        let count = match parse_batch_count(query.count) {
+            Ok(count) => count,
+            Err(err) => return Reply::invalid(BATCH, err),
+        };
+
+        match self.quotes.get_quotes(&query.user_id, count).await {
+            Ok(quotes) => Reply::json(&quotes),
+            Err(err) => Reply::internal("get quotes", err),
+        }
+    }
+
+    /// Legacy `/like` route, the quote ID is a query parameter.
+    pub async fn like_quote(&self, query: Option<&str>) -> Reply {
+        match parse_query::<UserAndQuoteID>(query, USER_AND_QUOTE) {
+            Ok(query) => self.like(&query.user_id, &query.quote_id).await,
+            Err(reply) => reply,
+        }
+    }
+
+    pub async fn like_quote_by_id(&self, quote_id: &str, query: Option<&str>) -> Reply {
+        match parse_query::<UserID>(query, USER) {
+            Ok(query) => self.like(&query.user_id, quote_id).await,
+            Err(reply) => reply,
+        }
+    }
+
+    async fn like(&self, user_id: &str, quote_id: &str) -> Reply {
+        match self.quotes.like_quote(user_id, quote_id).await {
+            Ok(_) => Reply::status(200),
+            Err(err) if is_not_found(&err) => Reply::not_found(),
+            Err(err) => Reply::internal("like quote", err),
+        }
+    }
+
+    pub async fn dislike_quote(&self, quote_id: &str, query: Option<&str>) -> Reply {
+        self.feedback(quote_id, query, Feedback::Dislike).await
+    }
+
+    pub async fn skip_quote(&self, quote_id: &str, query: Option<&str>) -> Reply {
+        self.feedback(quote_id, query, Feedback::Skip).await
+    }
+
+    async fn feedback(&self, quote_id: &str, query: Option<&str>, feedback: Feedback) -> Reply {
+        let query: UserID = match parse_query(query, USER) {
+            Ok(query) => query,
+            Err(reply) => return reply,
+        };
+
+        match self
+            .quotes
+            .give_feedback(&query.user_id, quote_id, feedback)
+            .await
+        {
+            Ok(_) => Reply::status(200),
+            Err(err) if is_not_found(&err) => Reply::not_found(),
+            Err(err) => Reply::internal("save feedback", err),
+        }
+    }
+
+    /// Legacy `/same` route, the quote ID is a query parameter.
+    pub async fn get_same_quote(&self, query: Option<&str>) -> Reply {
+        match parse_query::<UserAndQuoteID>(query, USER_AND_QUOTE) {
+            Ok(query) => self.same(&query.user_id, &query.quote_id).await,
+            Err(reply) => reply,
+        }
+    }
+
+    pub async fn get_similar_quote(&self, quote_id: &str, query: Option<&str>) -> Reply {
+        match parse_query::<UserID>(query, USER) {
+            Ok(query) => self.same(&query.user_id, quote_id).await,
+            Err(reply) => reply,
+        }
+    }
+
+    async fn same(&self, user_id: &str, quote_id: &str) -> Reply {
+        match self.quotes.get_same_quote(user_id, quote_id).await {
+            Ok(quote) => Reply::json(&quote),
+            Err(err) if is_not_found(&err) => Reply::not_found(),
+            Err(err) => Reply::internal("get same quote", err),
+        }
+    }
+
+    pub async fn get_tags(&self, query: Option<&str>, if_none_match: Option<&str>) -> Reply {
+        let query: ListQuery = match parse_query(query, LIST) {
+            Ok(query) => query,
+            Err(reply) => return reply,
+        };
+        let params = match query.to_params() {
+            Ok(params) => params,
+            Err(err) => return Reply::invalid(LIST, err),
+        };
+
+        match self.catalogue.get_tags(params).await {
+            Ok(tags) => Reply::json(&tags).cacheable(if_none_match, &self.cache_control),
+            Err(err) => Reply::internal("get tags", err),
+        }
+    }
+
+    pub async fn get_authors(&self, query: Option<&str>, if_none_match: Option<&str>) -> Reply {
+        let query: ListQuery = match parse_query(query, LIST) {
+            Ok(query) => query,
+            Err(reply) => return reply,
+        };
+        let params = match query.to_params() {
+            Ok(params) => params,
+            Err(err) => return Reply::invalid(LIST, err),
+        };
+
+        match self.catalogue.get_authors(params).await {
+            Ok(authors) => Reply::json(&authors).cacheable(if_none_match, &self.cache_control),
+            Err(err) => Reply::internal("get authors", err),
+        }
+    }
+
+    pub async fn get_quote_of_the_day(
+        &self,
+        query: Option<&str>,
+        if_none_match: Option<&str>,
+    ) -> Reply {
+        let query: DailyQuery = match parse_query(query, DAILY) {
+            Ok(query) => query,
+            Err(reply) => return reply,
+        };
+        let params = match query.to_params() {
+            Ok(params) => params,
+            Err(err) => return Reply::invalid(DAILY, err),
+        };
+
+        match self.quotes.get_quote_of_the_day(&params).await {
+            Ok(quote) => Reply::json(&quote).cacheable(if_none_match, &self.cache_control),
+            Err(err) if is_not_found(&err) => Reply::not_found(),
+            Err(err) => Reply::internal("get quote of the day", err),
+        }
+    }
+
+    /// Admin route, `authorization` is the raw `Authorization` header.
+    pub async fn set_quote_of_the_day(
+        &self,
+        authorization: Option<&str>,
+        query: Option<&str>,
+    ) -> Reply {
+        if !self.admin.is_authorized(authorization) {
+            return Reply::error(401, "unauthorized", None);
+        }
+
+        let query: DailyOverrideQuery = match parse_query(query, DAILY_OVERRIDE) {
+            Ok(query) => query,
+            Err(reply) => return reply,
+        };
+        let daily_override = match query.to_override() {
+            Ok(daily_override) => daily_override,
+            Err(err) => return Reply::invalid(DAILY_OVERRIDE, err),
+        };
+
+        match self.quotes.set_quote_of_the_day(&daily_override).await {
+            Ok(quote) => Reply::json(&quote),
+            Err(err) if is_not_found(&err) => Reply::not_found(),
+            Err(err) => Reply::internal("set quote of the day", err),
+        }
+    }
+
+    pub async fn report_quote(&self, quote_id: &str, query: Option<&str>) -> Reply {
+        let query: ReportQuery = match parse_query(query, REPORT) {
+            Ok(query) => query,
+            Err(reply) => return reply,
+        };
+        let report = match query.to_report(quote_id) {
+            Ok(report) => report,
+            Err(err) => return Reply::invalid(REPORT, err),
+        };
+
+        match self.quotes.report_quote(&report).await {
+            Ok(_) => Reply::status(200),
+            Err(err) if is_not_found(&err) => Reply::not_found(),
+            Err(err) => Reply::internal("report quote", err),
+        }
+    }
+
+    /// Admin route, `authorization` is the raw `Authorization` header.
+    pub async fn get_review_queue(
+        &self,
+        authorization: Option<&str>,
+        query: Option<&str>,
+    ) -> Reply {
+        if !self.admin.is_authorized(authorization) {
+            return Reply::error(401, "unauthorized", None);
+        }
+
+        let query: ReviewQuery = match parse_query(query, REVIEW) {
+            Ok(query) => query,
+            Err(reply) => return reply,
+        };
+        let page = match query.to_page() {
+            Ok(page) => page,
+            Err(err) => return Reply::invalid(REVIEW, err),
+        };
+
+        match self.quotes.get_review_queue(&page).await {
+            Ok(queue) => Reply::json(&queue),
+            Err(err) => Reply::internal("get review queue", err),
+        }
+    }
+
+    /// Admin route, `authorization` is the raw `Authorization` header.
+    pub async fn moderate_quote(
+        &self,
+        authorization: Option<&str>,
+        quote_id: &str,
+        query: Option<&str>,
+    ) -> Reply {
+        if !self.admin.is_authorized(authorization) {
+            return Reply::error(401, "unauthorized", None);
+        }
+
+        let query: ModerationQuery = match parse_query(query, MODERATION) {
+            Ok(query) => query,
+            Err(reply) => return reply,
+        };
+        let decision = match query.to_decision(quote_id) {
+            Ok(decision) => decision,
+            Err(err) => return Reply::invalid(MODERATION, err),
+        };
+
+        match self.quotes.moderate_quote(&decision).await {
+            Ok(entry) => Reply::json(&entry),
+            Err(err) if is_not_found(&err) => Reply::not_found(),
+            Err(err) => Reply::internal("moderate quote", err),
+        }
+    }
+
+    /// Admin route, `authorization` is the raw `Authorization` header.
+    pub async fn get_moderation_audit(&self, authorization: Option<&str>, quote_id: &str) -> Reply {
+        if !self.admin.is_authorized(authorization) {
+            return Reply::error(401, "unauthorized", None);
+        }
+
+        match self.quotes.get_moderation_audit(quote_id).await {
+            Ok(entries) => Reply::json(&entries),
+            Err(err) => Reply::internal("get moderation audit", err),
+        }
+    }
+
+    /// Admin route, `authorization` is the raw `Authorization` header.
+    pub async fn get_stats(&self, authorization: Option<&str>) -> Reply {
+        if !self.admin.is_authorized(authorization) {
+            return Reply::error(401, "unauthorized", None);
+        }
+
+        Reply::json(&self.quotes.get_stats())
+    }
+}
+
+/// Query string of a GraphQL GET request, a malformed one gets the same error envelope
+/// as the REST endpoints rather than the text of the framework extractor.
+pub fn parse_graphql_query(query: Option<&str>) -> Result<GetRequest, Reply> {
+    parse_query(query, GRAPHQL)
+}
+
+/// Parses the raw query string, each framework has its own extractor with its own error messages.
+fn parse_query<T: DeserializeOwned>(query: Option<&str>, fields: &[&str]) -> Result<T, Reply> {
+    serde_urlencoded::from_str(query.unwrap_or_default())
+        .map_err(|err| Reply::invalid(fields, format!("invalid query: {err}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::{anyhow, Result};
+    use async_trait::async_trait;
+    use serde_json::json;
+    use std::sync::Arc;
+
+    use crate::catalogue::traits::MockDatabase as MockCatalogueDatabase;
+    use crate::config::{CatalogueConfig, QuotesConfig};
+    use crate::database::errors::Error as DatabaseErrors;
+    use crate::database::structs::quotes::Model as Quotes;
+    use crate::events::Bus;
+    use crate::heartbeat;
+    use crate::quote::traits::{MockApi, MockDatabase};
+
+    struct Unreachable;
+
+    #[async_trait]
+    impl heartbeat::Database for Unreachable {
+        async fn ping(&self) -> Result<()> {
+            Err(anyhow!("connection refused"))
+        }
+    }
+
+    fn new_rest(db: MockDatabase) -> Rest {
+        new_rest_with_api(db, MockApi::new())
+    }
+
+    fn new_rest_with_api(db: MockDatabase, api: MockApi) -> Rest {
+        Rest::new(
+            Heartbeat::new(Arc::new(Unreachable)),
+            Service::new(
+                &QuotesConfig::default(),
+                Arc::new(db),
+                Arc::new(api),
+                Bus::new(),
+            ),
+            catalogue::Service::new(
+                &CatalogueConfig::default(),
+                Arc::new(MockCatalogueDatabase::new()),
+            ),
+            Admin::new("secret"),
+            60,
+        )
+    }
+
+    fn error_body(reply: &Reply) -> serde_json::Value {
+        assert_eq!(reply.content_type, Some(JSON));
+        serde_json::from_str(&reply.body).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_invalid_query() {
+        let rest = new_rest(MockDatabase::new());
+
+        let reply = request_id::scope("abc".to_string(), rest.get_quote(None)).await;
+        assert_eq!(reply.status, 400);
+        assert_eq!(
+            error_body(&reply),
+            json!({
+                "code": "BAD_REQUEST",
+                "message": "invalid query: missing field `user_id`",
+                "details": [{
+                    "field": "user_id",
+                    "description": "invalid query: missing field `user_id`",
+                }],
+                "request_id": "abc",
+            })
+        );
+
+        let reply = rest.get_quotes(Some("user_id=1&count=0")).await;
+        assert_eq!(error_body(&reply)["details"][0]["field"], "count");
+        assert_eq!(rest.get_tags(Some("limit=-1"), None).await.status, 400);
+        assert_eq!(
+            rest.like_quote_by_id("1", Some("quote_id=1")).await.status,
+            400
+        );
+
+        let reply = rest
+            .report_quote("1", Some(&format!("user_id=1&reason={}", "a".repeat(501))))
+            .await;
+        assert_eq!(error_body(&reply)["details"][0]["field"], "reason");
+        assert_eq!(rest.dislike_quote("1", None).await.status, 400);
+        let reply = rest
+            .moderate_quote(Some("Bearer secret"), "1", Some("status=deleted"))
+            .await;
+        assert_eq!(error_body(&reply)["details"][0]["field"], "status");
+        let reply = rest.get_review_queue(Some("Bearer wrong"), None).await;
+        assert_eq!(error_body(&reply)["code"], "UNAUTHORIZED");
+    }
+
+    #[tokio::test]
+    async fn test_get_stats() {
+        let mut api = MockApi::new();
+        api.expect_rejected_quotes().times(1).return_const(3u64);
+        let rest = new_rest_with_api(MockDatabase::new(), api);
+
+        let reply = rest.get_stats(None).await;
+        assert_eq!(error_body(&reply)["code"], "UNAUTHORIZED");
+
+        let reply = rest.get_stats(Some("Bearer secret")).await;
+        assert_eq!(reply.status, 200);
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&reply.body).unwrap(),
+            json!({"rejected_quotes": 3})
+        );
+    }
+
+    #[tokio::test]
+    async fn test_errors_are_hidden() {
+        let mut db = MockDatabase::new();
+        db.expect_get_quote()
+            .returning(|_| Err(anyhow!(DatabaseErrors::ErrNotFound)));
+        db.expect_get_view()
+            .returning(|_, _| Err(anyhow!(DatabaseErrors::ErrNotFound)));
+        let rest = new_rest(db);
+
+        let reply = rest.heartbeat().await;
+        assert_eq!(reply.status, 500);
+        assert_eq!(
+            error_body(&reply),
+            json!({
+                "code": "INTERNAL",
+                "message": "failed to ping database",
+                "details": [],
+                "request_id": null,
+            })
+        );
+
+        let reply = rest
+            .set_quote_of_the_day(Some("Bearer wrong"), Some("quote_id=1"))
+            .await;
+        assert_eq!(error_body(&reply)["code"], "UNAUTHORIZED");
+        let reply = rest
+            .set_quote_of_the_day(Some("Bearer secret"), Some("quote_id=1"))
+            .await;
+        assert_eq!(error_body(&reply)["code"], "NOT_FOUND");
+
+        // unknown or unseen quotes of the resource routes
+        let reply = rest.like_quote_by_id("1", Some("user_id=1")).await;
+        assert_eq!(error_body(&reply)["code"], "NOT_FOUND");
+        let reply = rest.get_similar_quote("1", Some("user_id=1")).await;
+        assert_eq!(error_body(&reply)["code"], "NOT_FOUND");
+    }
+
+    #[test]
+    fn test_unrouted() {
+        let reply = Reply::unrouted("/graphql");
+        assert_eq!(error_body(&reply)["code"], "METHOD_NOT_ALLOWED");
+        assert_eq!(reply.headers, vec![("allow", "GET, POST".to_string())]);
+
+        assert_eq!(Reply::unrouted("/v1/unknown"), Reply::not_found());
+    }
+
+    #[test]
+    fn test_parse_graphql_query() {
+        assert!(parse_graphql_query(Some("query=%7Bheartbeat%7D&operationName=a")).is_ok());
+        assert!(parse_graphql_query(None).is_ok());
+
+        let reply = parse_graphql_query(Some("query=a&query=b")).unwrap_err();
+        assert_eq!(reply.status, 400);
+        let body = error_body(&reply);
+        assert_eq!(body["code"], "BAD_REQUEST");
+        assert_eq!(body["details"][0]["field"], "query");
+    }
+
+    #[tokio::test]
+    async fn test_conditional_get() {
+        let mut db = MockDatabase::new();
+        db.expect_get_quote().returning(|quote_id| {
+            Ok(Quotes {
+                id: quote_id.to_string(),
+                quote: "Simplicity is prerequisite for reliability.".to_string(),
+                author: "Edsger W. Dijkstra".to_string(),
+                likes: 1,
+                tags: vec![],
+                fingerprint: None,
+                source: "manual".to_string(),
+                external_id: None,
+                status: "approved".to_string(),
+            })
+        });
+        let rest = new_rest(db);
+
+        let reply = rest.get_quote_by_id("1", None).await;
+        assert_eq!(reply.status, 200);
+        let etag = reply.headers[0].1.clone();
+        assert_eq!(
+            reply.headers,
+            vec![
+                ("etag", etag.clone()),
+                ("cache-control", "public, max-age=60".to_string()),
+            ]
+        );
+
+        let revalidated = rest
+            .get_quote_by_id("1", Some(&format!("\"stale\", W/{etag}")))
+            .await;
+        assert_eq!(
+            revalidated,
+            Reply {
+                headers: reply.headers,
+                ..Reply::status(304)
+            }
+        );
+        assert_eq!(
+            rest.get_quote_by_id("1", Some("\"stale\"")).await.status,
+            200
+        );
+    }
+
+    #[test]
+    fn test_from_status() {
+        assert_eq!(Reply::from_status(204), Reply::status(204));
+        assert_eq!(error_body(&Reply::from_status(403))["message"], "forbidden");
+    }
+}
@@ -1,139 +1,764 @@
-use anyhow::{Context, Result};
-use juniper::EmptySubscription;
-use juniper_rocket::{GraphQLRequest, GraphQLResponse};
-use rocket::http::{Method, Status};
-use rocket::response::status;
-use rocket::serde::json::Json;
-use rocket::{catch, catchers, get, patch, post, routes, Build, Request, Rocket, State};
-use rocket_cors::{AllowedHeaders, AllowedOrigins};
-use std::collections::HashSet;
-
-use super::graphql::quotes_resolver::{Context as graphql_context, Mutation, Query, Schema};
-use crate::heartbeat::Heartbeat;
-use crate::quote::structs::Quote;
-use crate::quote::Service;
+use anyhow::Result;
+use futures::{future, SinkExt, StreamExt};
+use juniper::{FieldResult, ScalarValue};
+use juniper_graphql_ws::{graphql_transport_ws, graphql_ws, ArcSchema, ConnectionConfig};
+use juniper_rocket::GraphQLResponse;
+use rocket::data::{self, Data, FromData, Limits};
+use rocket::fairing::{Fairing, Info, Kind};
+use rocket::http::{ContentType, Status};
+use rocket::request::{FromRequest, Outcome};
+use rocket::response::content::{RawHtml, RawJson};
+use rocket::response::{self, Responder};
+use rocket::{
+    catch, catchers, get, patch, post, put, routes, Build, Request, Response, Rocket, State,
+};
+use rocket_ws::frame::{CloseCode, CloseFrame};
+use rocket_ws::stream::DuplexStream;
+use rocket_ws::{Channel, Message, WebSocket};
+use std::convert::Infallible;
+use std::fmt;
+use std::io::Cursor;
+use std::sync::Arc;
+
+use super::admin::Admin;
+use super::cors::{
+    Cors, CorsReply, CorsRequest, ACCESS_CONTROL_REQUEST_HEADERS, ACCESS_CONTROL_REQUEST_METHOD,
+    ORIGIN,
+};
+use super::graphql::guard::{BatchRequest, Guard, Reply as GraphQLReply};
+use super::graphql::quotes_resolver::{
+    Context as graphql_context, Mutation, Query, Schema, Subscription,
+};
+use super::graphql::subscriptions::{guard_graphql_ws, guard_transport_ws};
+use super::graphql::{GRAPHQL_ENDPOINT, SUBSCRIPTIONS_ENDPOINT};
+use super::openapi;
+use super::request_id::{self, REQUEST_ID_HEADER};
+use super::rest::{self, Reply, Rest};
 
 pub fn register_routes(
     builder: Rocket<Build>,
-    heartbeat: Heartbeat,
-    quotes: Service,
-) -> Result<Rocket<Build>> {
-    let cors = rocket_cors::CorsOptions {
-        allowed_origins: AllowedOrigins::all(),
-        allowed_methods: vec![Method::Get, Method::Patch, Method::Options]
-            .into_iter()
-            .map(From::from)
-            .collect(),
-        allowed_headers: AllowedHeaders::all(),
-        expose_headers: HashSet::from(["Link".to_string()]),
-        max_age: Some(300),
-        ..Default::default()
-    }
-    .to_cors()
-    .context("failed to make cors")?;
-
-    Ok(builder
-        .attach(cors)
-        .manage(heartbeat)
-        .manage(quotes)
-        .manage(Schema::new(Query, Mutation, EmptySubscription::new()))
-        .register("/", catchers![catch_default])
+    rest: Rest,
+    context: graphql_context,
+    admin: Admin,
+    guard: Guard,
+    cors: Cors,
+) -> Rocket<Build> {
+    builder
+        .attach(RequestIdFairing)
+        .attach(CorsFairing(cors))
+        .manage(rest)
+        .manage(context)
+        .manage(admin)
+        .manage(Arc::new(guard))
+        .manage(Arc::new(Schema::new(Query, Mutation, Subscription)))
+        .register("/", catchers![catch_not_found, catch_default])
         .mount("/heartbeat", routes![heartbeat_handler])
+        .mount("/v1/heartbeat", routes![heartbeat_handler])
         .mount("/", routes![get_quote_handler])
+        .mount("/v1/quotes/random", routes![get_quote_handler])
+        .mount("/", routes![get_quotes_handler])
         .mount("/", routes![like_quote_handler])
         .mount("/", routes![get_same_quote_handler])
+        .mount("/v1/quotes", routes![get_quotes_by_user_handler])
+        .mount("/v1/quotes", routes![get_quote_by_id_handler])
+        .mount("/v1/quotes", routes![like_quote_by_id_handler])
+        .mount("/v1/quotes", routes![dislike_quote_handler])
+        .mount("/v1/quotes", routes![skip_quote_handler])
+        .mount("/v1/quotes", routes![get_similar_quote_handler])
+        .mount("/v1/quotes", routes![report_quote_handler])
+        .mount("/", routes![get_tags_handler])
+        .mount("/", routes![get_authors_handler])
+        .mount("/", routes![get_quote_of_the_day_handler])
+        .mount("/", routes![set_quote_of_the_day_handler])
+        .mount(
+            "/v1",
+            routes![
+                get_tags_handler,
+                get_authors_handler,
+                get_quote_of_the_day_handler,
+                set_quote_of_the_day_handler,
+                get_review_queue_handler,
+                moderate_quote_handler,
+                get_moderation_audit_handler,
+                get_stats_handler
+            ],
+        )
+        .mount("/", routes![openapi_handler])
+        .mount("/", routes![swagger_ui_handler])
         .mount("/", routes![get_graphql])
-        .mount("/", routes![post_graphql]))
+        .mount("/", routes![post_graphql])
+        .mount("/", routes![graphql_subscriptions])
+        .mount("/", routes![graphiql])
+        .mount("/", routes![playground])
+}
+
+/// Raw `Authorization` header, checked against the configured admin token by the handlers.
+struct Authorization(Option<String>);
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for Authorization {
+    type Error = Infallible;
+
+    async fn from_request(req: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        Outcome::Success(Authorization(
+            req.headers().get_one("Authorization").map(str::to_owned),
+        ))
+    }
+}
+
+/// Builds the request context from the shared one, the admin flag depends on the request headers.
+fn new_graphql_context(
+    authorization: &Authorization,
+    admin: &Admin,
+    context: &graphql_context,
+) -> graphql_context {
+    context.for_request(admin.is_authorized(authorization.0.as_deref()))
+}
+
+/// Unknown paths and wrong methods, rocket answers both with a 404.
+#[catch(404)]
+fn catch_not_found(req: &Request) -> Reply {
+    request_id::sync_scope(request_id_of(req), || {
+        Reply::unrouted(req.uri().path().as_str())
+    })
 }
 
 #[catch(default)]
-fn catch_default(status: Status, req: &Request) -> String {
-    format!("{}: ({})", status, req.uri())
+fn catch_default(status: Status, req: &Request) -> Reply {
+    request_id::sync_scope(request_id_of(req), || Reply::from_status(status.code))
 }
 
-#[get("/")]
-async fn heartbeat_handler(heartbeat: &State<Heartbeat>) -> Status {
-    match heartbeat.ping_database().await {
-        Ok(_) => Status::Ok,
-        Err(err) => {
-            log::error!("failed to ping database: {err:#}");
-            Status::InternalServerError
-        }
+/// Id of the request, taken from its `X-Request-Id` header or generated on first use.
+struct RequestId(String);
+
+fn request_id_of(req: &Request) -> String {
+    req.local_cache(|| {
+        RequestId(request_id::from_header(
+            req.headers().get_one(REQUEST_ID_HEADER),
+        ))
+    })
+    .0
+    .clone()
+}
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for RequestId {
+    type Error = Infallible;
+
+    async fn from_request(req: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        Outcome::Success(RequestId(request_id_of(req)))
     }
 }
 
-#[get("/?<user_id>")]
-async fn get_quote_handler(
-    user_id: String,
-    quotes: &State<Service>,
-) -> status::Custom<Option<Json<Quote>>> {
-    match quotes.get_quote(&user_id).await {
-        Ok(quote) => status::Custom(Status::Ok, Some(Json(quote))),
-        Err(err) => {
-            log::error!("failed to get quote: {err:#}");
-            status::Custom(Status::InternalServerError, None)
+/// Returns the request id and logs the outcome of the request with it, rocket fairings
+/// can't wrap the handlers so these run within [`request_id::scope`] themselves.
+struct RequestIdFairing;
+
+#[rocket::async_trait]
+impl Fairing for RequestIdFairing {
+    fn info(&self) -> Info {
+        Info {
+            name: "Request id",
+            kind: Kind::Response,
         }
     }
+
+    async fn on_response<'r>(&self, req: &'r Request<'_>, res: &mut Response<'r>) {
+        let id = request_id_of(req);
+        res.set_raw_header(REQUEST_ID_HEADER, id.clone());
+        request_id::sync_scope(id, || {
+            log::info!("{} {} {}", req.method(), req.uri(), res.status().code)
+        });
+    }
 }
 
-#[patch("/like?<user_id>&<quote_id>")]
-async fn like_quote_handler(quote_id: String, user_id: String, quotes: &State<Service>) -> Status {
-    match quotes.like_quote(&user_id, &quote_id).await {
-        Ok(_) => Status::Ok,
-        Err(err) => {
-            log::error!("failed to like quote: {err:#}");
-            Status::InternalServerError
+/// Applies the shared CORS policy, preflight requests have no route so their 404 is replaced.
+struct CorsFairing(Cors);
+
+#[rocket::async_trait]
+impl Fairing for CorsFairing {
+    fn info(&self) -> Info {
+        Info {
+            name: "CORS",
+            kind: Kind::Response,
+        }
+    }
+
+    async fn on_response<'r>(&self, req: &'r Request<'_>, res: &mut Response<'r>) {
+        let header = |name| req.headers().get_one(name);
+        let reply = self.0.handle(&CorsRequest {
+            method: req.method().as_str(),
+            origin: header(ORIGIN),
+            request_method: header(ACCESS_CONTROL_REQUEST_METHOD),
+            request_headers: header(ACCESS_CONTROL_REQUEST_HEADERS),
+        });
+
+        let headers = match reply {
+            CorsReply::Preflight { status, headers } => {
+                let preflight =
+                    request_id::sync_scope(request_id_of(req), || Reply::from_status(status));
+                res.set_status(Status::new(preflight.status));
+                res.remove_header("Content-Type");
+                if let Some(content_type) = preflight.content_type {
+                    res.set_raw_header("Content-Type", content_type);
+                }
+                res.set_sized_body(preflight.body.len(), Cursor::new(preflight.body));
+                headers
+            }
+            CorsReply::Headers(headers) => headers,
+        };
+        for (name, value) in headers {
+            res.adjoin_raw_header(name, value);
         }
     }
 }
 
-#[get("/same?<user_id>&<quote_id>")]
-async fn get_same_quote_handler(
-    quote_id: String,
-    user_id: String,
-    quotes: &State<Service>,
-) -> status::Custom<Option<Json<Quote>>> {
-    match quotes.get_same_quote(&user_id, &quote_id).await {
-        Ok(quote) => status::Custom(Status::Ok, Some(Json(quote))),
-        Err(err) => {
-            log::error!("failed to get same quote: {err:#}");
-            status::Custom(Status::InternalServerError, None)
+impl<'r> Responder<'r, 'static> for Reply {
+    fn respond_to(self, _req: &'r Request<'_>) -> response::Result<'static> {
+        let mut response = Response::build();
+        response
+            .status(Status::new(self.status))
+            .sized_body(self.body.len(), Cursor::new(self.body));
+        if let Some(content_type) = self.content_type {
+            response.raw_header("Content-Type", content_type);
+        }
+        for (name, value) in self.headers {
+            response.raw_header(name, value);
         }
+
+        Ok(response.finalize())
+    }
+}
+
+/// Raw `If-None-Match` header, compared with the `ETag` of the reply by the handlers.
+struct IfNoneMatch(Option<String>);
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for IfNoneMatch {
+    type Error = Infallible;
+
+    async fn from_request(req: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        Outcome::Success(IfNoneMatch(
+            req.headers().get_one("If-None-Match").map(str::to_owned),
+        ))
+    }
+}
+
+/// Raw query string, parsed by the shared REST handlers.
+struct RawQuery(Option<String>);
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for RawQuery {
+    type Error = Infallible;
+
+    async fn from_request(req: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        Outcome::Success(RawQuery(
+            req.uri().query().map(|query| query.as_str().to_owned()),
+        ))
     }
 }
 
-#[get("/graphql?<request..>")]
+#[get("/")]
+async fn heartbeat_handler(id: RequestId, rest: &State<Rest>) -> Reply {
+    request_id::scope(id.0, rest.heartbeat()).await
+}
+
+#[get("/")]
+async fn get_quote_handler(query: RawQuery, id: RequestId, rest: &State<Rest>) -> Reply {
+    request_id::scope(id.0, rest.get_quote(query.0.as_deref())).await
+}
+
+#[get("/batch")]
+async fn get_quotes_handler(query: RawQuery, id: RequestId, rest: &State<Rest>) -> Reply {
+    request_id::scope(id.0, rest.get_quotes(query.0.as_deref())).await
+}
+
+#[get("/")]
+async fn get_quotes_by_user_handler(query: RawQuery, id: RequestId, rest: &State<Rest>) -> Reply {
+    request_id::scope(id.0, rest.get_quotes(query.0.as_deref())).await
+}
+
+#[get("/<quote_id>")]
+async fn get_quote_by_id_handler(
+    quote_id: &str,
+    if_none_match: IfNoneMatch,
+    id: RequestId,
+    rest: &State<Rest>,
+) -> Reply {
+    request_id::scope(
+        id.0,
+        rest.get_quote_by_id(quote_id, if_none_match.0.as_deref()),
+    )
+    .await
+}
+
+#[patch("/<quote_id>/like")]
+async fn like_quote_by_id_handler(
+    quote_id: &str,
+    query: RawQuery,
+    id: RequestId,
+    rest: &State<Rest>,
+) -> Reply {
+    request_id::scope(id.0, rest.like_quote_by_id(quote_id, query.0.as_deref())).await
+}
+
+#[patch("/<quote_id>/dislike")]
+async fn dislike_quote_handler(
+    quote_id: &str,
+    query: RawQuery,
+    id: RequestId,
+    rest: &State<Rest>,
+) -> Reply {
+    request_id::scope(id.0, rest.dislike_quote(quote_id, query.0.as_deref())).await
+}
+
+#[patch("/<quote_id>/skip")]
+async fn skip_quote_handler(
+    quote_id: &str,
+    query: RawQuery,
+    id: RequestId,
+    rest: &State<Rest>,
+) -> Reply {
+    request_id::scope(id.0, rest.skip_quote(quote_id, query.0.as_deref())).await
+}
+
+#[patch("/like")]
+async fn like_quote_handler(query: RawQuery, id: RequestId, rest: &State<Rest>) -> Reply {
+    request_id::scope(id.0, rest.like_quote(query.0.as_deref())).await
+}
+
+#[get("/<quote_id>/similar")]
+async fn get_similar_quote_handler(
+    quote_id: &str,
+    query: RawQuery,
+    id: RequestId,
+    rest: &State<Rest>,
+) -> Reply {
+    request_id::scope(id.0, rest.get_similar_quote(quote_id, query.0.as_deref())).await
+}
+
+#[get("/same")]
+async fn get_same_quote_handler(query: RawQuery, id: RequestId, rest: &State<Rest>) -> Reply {
+    request_id::scope(id.0, rest.get_same_quote(query.0.as_deref())).await
+}
+
+#[get("/tags")]
+async fn get_tags_handler(
+    query: RawQuery,
+    if_none_match: IfNoneMatch,
+    id: RequestId,
+    rest: &State<Rest>,
+) -> Reply {
+    request_id::scope(
+        id.0,
+        rest.get_tags(query.0.as_deref(), if_none_match.0.as_deref()),
+    )
+    .await
+}
+
+#[get("/authors")]
+async fn get_authors_handler(
+    query: RawQuery,
+    if_none_match: IfNoneMatch,
+    id: RequestId,
+    rest: &State<Rest>,
+) -> Reply {
+    request_id::scope(
+        id.0,
+        rest.get_authors(query.0.as_deref(), if_none_match.0.as_deref()),
+    )
+    .await
+}
+
+#[get("/daily")]
+async fn get_quote_of_the_day_handler(
+    query: RawQuery,
+    if_none_match: IfNoneMatch,
+    id: RequestId,
+    rest: &State<Rest>,
+) -> Reply {
+    request_id::scope(
+        id.0,
+        rest.get_quote_of_the_day(query.0.as_deref(), if_none_match.0.as_deref()),
+    )
+    .await
+}
+
+#[put("/admin/daily")]
+async fn set_quote_of_the_day_handler(
+    authorization: Authorization,
+    query: RawQuery,
+    id: RequestId,
+    rest: &State<Rest>,
+) -> Reply {
+    request_id::scope(
+        id.0,
+        rest.set_quote_of_the_day(authorization.0.as_deref(), query.0.as_deref()),
+    )
+    .await
+}
+
+#[post("/<quote_id>/report")]
+async fn report_quote_handler(
+    quote_id: &str,
+    query: RawQuery,
+    id: RequestId,
+    rest: &State<Rest>,
+) -> Reply {
+    request_id::scope(id.0, rest.report_quote(quote_id, query.0.as_deref())).await
+}
+
+#[get("/admin/reports")]
+async fn get_review_queue_handler(
+    authorization: Authorization,
+    query: RawQuery,
+    id: RequestId,
+    rest: &State<Rest>,
+) -> Reply {
+    request_id::scope(
+        id.0,
+        rest.get_review_queue(authorization.0.as_deref(), query.0.as_deref()),
+    )
+    .await
+}
+
+#[put("/admin/quotes/<quote_id>/moderation")]
+async fn moderate_quote_handler(
+    authorization: Authorization,
+    quote_id: &str,
+    query: RawQuery,
+    id: RequestId,
+    rest: &State<Rest>,
+) -> Reply {
+    request_id::scope(
+        id.0,
+        rest.moderate_quote(authorization.0.as_deref(), quote_id, query.0.as_deref()),
+    )
+    .await
+}
+
+#[get("/admin/quotes/<quote_id>/audit")]
+async fn get_moderation_audit_handler(
+    authorization: Authorization,
+    quote_id: &str,
+    id: RequestId,
+    rest: &State<Rest>,
+) -> Reply {
+    request_id::scope(
+        id.0,
+        rest.get_moderation_audit(authorization.0.as_deref(), quote_id),
+    )
+    .await
+}
+
+#[get("/admin/stats")]
+async fn get_stats_handler(
+    authorization: Authorization,
+    id: RequestId,
+    rest: &State<Rest>,
+) -> Reply {
+    request_id::scope(id.0, rest.get_stats(authorization.0.as_deref())).await
+}
+
+#[get("/graphql")]
 async fn get_graphql(
-    quotes: &State<Service>,
-    heartbeat: &State<Heartbeat>,
-    request: GraphQLRequest,
-    schema: &State<Schema>,
-) -> GraphQLResponse {
-    request
-        .execute(
-            schema,
-            &graphql_context {
-                quotes: quotes.inner().clone(),
-                heartbeat: heartbeat.inner().clone(),
-            },
-        )
-        .await
+    context: &State<graphql_context>,
+    authorization: Authorization,
+    admin: &State<Admin>,
+    guard: &State<Arc<Guard>>,
+    query: RawQuery,
+    id: RequestId,
+    schema: &State<Arc<Schema>>,
+) -> Result<GraphQLResponse, Reply> {
+    let context = new_graphql_context(&authorization, admin, context);
+    let response = request_id::scope(id.0, async {
+        let request = rest::parse_graphql_query(query.0.as_deref())?;
+        Ok(guard
+            .execute(schema, &context, request.into_request())
+            .await)
+    })
+    .await?;
+
+    Ok(to_graphql_response(response))
 }
 
 #[post("/graphql", data = "<request>")]
 async fn post_graphql(
-    quotes: &State<Service>,
-    heartbeat: &State<Heartbeat>,
-    request: GraphQLRequest,
-    schema: &State<Schema>,
+    context: &State<graphql_context>,
+    authorization: Authorization,
+    admin: &State<Admin>,
+    guard: &State<Arc<Guard>>,
+    request: GraphQLBody,
+    id: RequestId,
+    schema: &State<Arc<Schema>>,
 ) -> GraphQLResponse {
-    request
-        .execute(
-            schema,
-            &graphql_context {
-                quotes: quotes.inner().clone(),
-                heartbeat: heartbeat.inner().clone(),
-            },
-        )
-        .await
+    let context = new_graphql_context(&authorization, admin, context);
+    let response = request_id::scope(id.0, guard.execute(schema, &context, request.0)).await;
+
+    to_graphql_response(response)
+}
+
+/// POST body of a GraphQL request, malformed ones are answered with a GraphQL error.
+struct GraphQLBody(FieldResult<BatchRequest>);
+
+#[rocket::async_trait]
+impl<'r> FromData<'r> for GraphQLBody {
+    type Error = String;
+
+    async fn from_data(req: &'r Request<'_>, data: Data<'r>) -> data::Outcome<'r, Self> {
+        let limit = req.limits().get("json").unwrap_or(Limits::JSON);
+        let body = match data.open(limit).into_bytes().await {
+            Ok(body) if body.is_complete() => body.into_inner(),
+            Ok(_) => {
+                return data::Outcome::Error((Status::PayloadTooLarge, "body is too large".into()))
+            }
+            Err(err) => return data::Outcome::Error((Status::BadRequest, err.to_string())),
+        };
+
+        let content_type = req.content_type().map(ContentType::to_string);
+        data::Outcome::Success(GraphQLBody(BatchRequest::from_body(
+            content_type.as_deref(),
+            &body,
+        )))
+    }
+}
+
+fn to_graphql_response(reply: GraphQLReply) -> GraphQLResponse {
+    let status = if reply.ok {
+        Status::Ok
+    } else {
+        Status::BadRequest
+    };
+
+    match serde_json::to_string(&reply.response) {
+        Ok(body) => GraphQLResponse(status, body),
+        Err(err) => {
+            log::error!("failed to serialize graphql response: {err:#}");
+            GraphQLResponse(Status::InternalServerError, String::new())
+        }
+    }
+}
+
+#[get("/openapi.json")]
+fn openapi_handler() -> RawJson<String> {
+    RawJson(openapi::document())
+}
+
+#[get("/swagger-ui")]
+fn swagger_ui_handler() -> RawHtml<String> {
+    RawHtml(openapi::swagger_ui())
+}
+
+#[get("/subscriptions")]
+fn graphql_subscriptions(
+    ws: WebSocket,
+    protocol: WebSocketProtocol,
+    context: &State<graphql_context>,
+    authorization: Authorization,
+    admin: &State<Admin>,
+    guard: &State<Arc<Guard>>,
+    schema: &State<Arc<Schema>>,
+) -> GraphQLWebSocket<'static> {
+    let config = ConnectionConfig::new(new_graphql_context(&authorization, admin, context));
+    let schema = ArcSchema(schema.inner().clone());
+    let guard = guard.inner().clone();
+
+    let protocol = protocol.select();
+    let channel = ws.channel(move |stream| {
+        Box::pin(async move {
+            match protocol {
+                Some(GRAPHQL_WS) => serve_graphql_ws(stream, schema, config, guard).await,
+                _ => serve_graphql_transport_ws(stream, schema, config, guard).await,
+            }
+            Ok(())
+        })
+    });
+
+    GraphQLWebSocket { channel, protocol }
+}
+
+#[get("/graphiql")]
+fn graphiql() -> RawHtml<String> {
+    juniper_rocket::graphiql_source(GRAPHQL_ENDPOINT, SUBSCRIPTIONS_ENDPOINT)
+}
+
+#[get("/playground")]
+fn playground() -> RawHtml<String> {
+    juniper_rocket::playground_source(GRAPHQL_ENDPOINT, SUBSCRIPTIONS_ENDPOINT)
+}
+
+type GraphQLSchema =
+    ArcSchema<Query, Mutation, Subscription, graphql_context, juniper::DefaultScalarValue>;
+
+const GRAPHQL_TRANSPORT_WS: &str = "graphql-transport-ws";
+const GRAPHQL_WS: &str = "graphql-ws";
+
+/// `Sec-WebSocket-Protocol` header with the subprotocols offered by the client.
+struct WebSocketProtocol(Option<String>);
+
+impl WebSocketProtocol {
+    /// Prefers the new `graphql-transport-ws` protocol over the legacy `graphql-ws` one.
+    fn select(&self) -> Option<&'static str> {
+        let offered: Vec<&str> = self
+            .0
+            .as_deref()
+            .map(|protocols| protocols.split(',').map(str::trim).collect())
+            .unwrap_or_default();
+
+        [GRAPHQL_TRANSPORT_WS, GRAPHQL_WS]
+            .into_iter()
+            .find(|protocol| offered.contains(protocol))
+    }
+}
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for WebSocketProtocol {
+    type Error = Infallible;
+
+    async fn from_request(req: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        Outcome::Success(WebSocketProtocol(
+            req.headers()
+                .get_one("Sec-WebSocket-Protocol")
+                .map(str::to_owned),
+        ))
+    }
+}
+
+/// WebSocket channel answering with the selected subprotocol, browsers drop
+/// the connection when the server doesn't confirm one they have offered.
+struct GraphQLWebSocket<'r> {
+    channel: Channel<'r>,
+    protocol: Option<&'static str>,
+}
+
+impl<'r, 'o: 'r> Responder<'r, 'o> for GraphQLWebSocket<'o> {
+    fn respond_to(self, req: &'r Request<'_>) -> response::Result<'o> {
+        let mut response = self.channel.respond_to(req)?;
+        if let Some(protocol) = self.protocol {
+            response.set_raw_header("Sec-WebSocket-Protocol", protocol);
+        }
+
+        Ok(response)
+    }
+}
+
+async fn serve_graphql_transport_ws(
+    stream: DuplexStream,
+    schema: GraphQLSchema,
+    config: ConnectionConfig<graphql_context>,
+    guard: Arc<Guard>,
+) {
+    let (ws_tx, ws_rx) = stream.split();
+    let root = schema.0.clone();
+    let (s_tx, s_rx) = guard_transport_ws(
+        graphql_transport_ws::Connection::new(schema, config),
+        guard,
+        root,
+    );
+
+    let input = ws_rx
+        .filter_map(|message| future::ready(message.ok().map(|message| Ok(ClientMessage(message)))))
+        .forward(s_tx.sink_map_err(|err| match err {}));
+
+    let output = s_rx
+        .map(|output| {
+            Ok(match output {
+                graphql_transport_ws::Output::Message(message) => to_text_message(&message),
+                graphql_transport_ws::Output::Close { code, message } => {
+                    Message::Close(Some(CloseFrame {
+                        code: CloseCode::from(code),
+                        reason: message.into(),
+                    }))
+                }
+            })
+        })
+        .forward(ws_tx.sink_map_err(drop));
+
+    // Whichever side finishes first closes the connection, errors have nowhere to go.
+    let _ = future::select(input, output).await;
+}
+
+async fn serve_graphql_ws(
+    stream: DuplexStream,
+    schema: GraphQLSchema,
+    config: ConnectionConfig<graphql_context>,
+    guard: Arc<Guard>,
+) {
+    let (ws_tx, ws_rx) = stream.split();
+    let root = schema.0.clone();
+    let (s_tx, s_rx) = guard_graphql_ws(graphql_ws::Connection::new(schema, config), guard, root);
+
+    let input = ws_rx
+        .filter_map(|message| future::ready(message.ok().map(|message| Ok(ClientMessage(message)))))
+        .forward(s_tx.sink_map_err(|err| match err {}));
+
+    let output = s_rx
+        .map(|message| Ok(to_text_message(&message)))
+        .forward(ws_tx.sink_map_err(drop));
+
+    // Whichever side finishes first closes the connection, errors have nowhere to go.
+    let _ = future::select(input, output).await;
+}
+
+fn to_text_message<T: serde::Serialize>(message: &T) -> Message {
+    serde_json::to_string(message)
+        .map(Message::Text)
+        .unwrap_or_else(|err| {
+            Message::Close(Some(CloseFrame {
+                code: CloseCode::Error,
+                reason: format!("failed to serialize response: {err}").into(),
+            }))
+        })
+}
+
+/// Raw WebSocket message received from a subscriptions client.
+struct ClientMessage(Message);
+
+#[derive(Debug)]
+enum ClientMessageError {
+    Serde(serde_json::Error),
+    Unexpected(Message),
+}
+
+impl fmt::Display for ClientMessageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Serde(err) => write!(f, "failed to deserialize message: {err}"),
+            Self::Unexpected(message) => write!(f, "unexpected message: {message:?}"),
+        }
+    }
+}
+
+impl std::error::Error for ClientMessageError {}
+
+impl<S: ScalarValue> TryFrom<ClientMessage> for graphql_transport_ws::Input<S> {
+    type Error = ClientMessageError;
+
+    fn try_from(message: ClientMessage) -> Result<Self, Self::Error> {
+        match message.0 {
+            Message::Text(text) => serde_json::from_str(&text)
+                .map(Self::Message)
+                .map_err(ClientMessageError::Serde),
+            Message::Binary(bytes) => serde_json::from_slice(&bytes)
+                .map(Self::Message)
+                .map_err(ClientMessageError::Serde),
+            Message::Close(_) => Ok(Self::Close),
+            other => Err(ClientMessageError::Unexpected(other)),
+        }
+    }
+}
+
+impl<S: ScalarValue> TryFrom<ClientMessage> for graphql_ws::ClientMessage<S> {
+    type Error = ClientMessageError;
+
+    fn try_from(message: ClientMessage) -> Result<Self, Self::Error> {
+        match message.0 {
+            Message::Text(text) => serde_json::from_str(&text).map_err(ClientMessageError::Serde),
+            Message::Binary(bytes) => {
+                serde_json::from_slice(&bytes).map_err(ClientMessageError::Serde)
+            }
+            Message::Close(_) => Ok(Self::ConnectionTerminate),
+            other => Err(ClientMessageError::Unexpected(other)),
+        }
+    }
 }
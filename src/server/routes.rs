@@ -0,0 +1,83 @@
+use super::graphql::{GRAPHQL_ENDPOINT, SUBSCRIPTIONS_ENDPOINT};
+use super::openapi::{OPENAPI_ENDPOINT, SWAGGER_UI_ENDPOINT};
+
+/// Paths served by every HTTP server and their methods, `{id}` matches any segment.
+/// Only read to tell a wrong method from an unknown path, the frameworks route on their own.
+const ROUTES: [(&str, &[&str]); 32] = [
+    ("/v1/heartbeat", &["GET"]),
+    ("/v1/quotes/random", &["GET"]),
+    ("/v1/quotes", &["GET"]),
+    ("/v1/quotes/{id}", &["GET"]),
+    ("/v1/quotes/{id}/like", &["PATCH"]),
+    ("/v1/quotes/{id}/dislike", &["PATCH"]),
+    ("/v1/quotes/{id}/skip", &["PATCH"]),
+    ("/v1/quotes/{id}/similar", &["GET"]),
+    ("/v1/quotes/{id}/report", &["POST"]),
+    ("/v1/tags", &["GET"]),
+    ("/v1/authors", &["GET"]),
+    ("/v1/daily", &["GET"]),
+    ("/v1/admin/daily", &["PUT"]),
+    ("/v1/admin/reports", &["GET"]),
+    ("/v1/admin/quotes/{id}/moderation", &["PUT"]),
+    ("/v1/admin/quotes/{id}/audit", &["GET"]),
+    ("/v1/admin/stats", &["GET"]),
+    ("/heartbeat", &["GET"]),
+    ("/", &["GET"]),
+    ("/batch", &["GET"]),
+    ("/like", &["PATCH"]),
+    ("/same", &["GET"]),
+    ("/tags", &["GET"]),
+    ("/authors", &["GET"]),
+    ("/daily", &["GET"]),
+    ("/admin/daily", &["PUT"]),
+    (GRAPHQL_ENDPOINT, &["GET", "POST"]),
+    (SUBSCRIPTIONS_ENDPOINT, &["GET"]),
+    ("/graphiql", &["GET"]),
+    ("/playground", &["GET"]),
+    (OPENAPI_ENDPOINT, &["GET"]),
+    (SWAGGER_UI_ENDPOINT, &["GET"]),
+];
+
+/// Methods of the routes matching the path, empty when no route does.
+pub fn allowed_methods(path: &str) -> Vec<&'static str> {
+    let mut methods: Vec<&'static str> = ROUTES
+        .iter()
+        .filter(|(route, _)| matches(route, path))
+        .flat_map(|(_, methods)| methods.iter().copied())
+        .collect();
+    methods.sort_unstable();
+    methods.dedup();
+
+    methods
+}
+
+fn matches(route: &str, path: &str) -> bool {
+    let mut route = route.split('/');
+    let mut path = path.split('/');
+    loop {
+        match (route.next(), path.next()) {
+            (None, None) => return true,
+            (Some("{id}"), Some(segment)) if !segment.is_empty() => {}
+            (Some(expected), Some(segment)) if expected == segment => {}
+            _ => return false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_allowed_methods() {
+        assert_eq!(allowed_methods("/v1/heartbeat"), vec!["GET"]);
+        assert_eq!(allowed_methods("/v1/quotes/abc/like"), vec!["PATCH"]);
+        assert_eq!(allowed_methods("/graphql"), vec!["GET", "POST"]);
+        assert_eq!(allowed_methods("/"), vec!["GET"]);
+        assert_eq!(allowed_methods("/swagger-ui"), vec!["GET"]);
+
+        assert!(allowed_methods("/v1/quotes//like").is_empty());
+        assert!(allowed_methods("/v1/quotes/abc/unknown").is_empty());
+        assert!(allowed_methods("/v1/heartbeat/").is_empty());
+    }
+}
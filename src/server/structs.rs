@@ -1,7 +1,17 @@
+use anyhow::Result;
 use serde::Deserialize;
+use utoipa::IntoParams;
 
-#[derive(Deserialize)]
+use crate::catalogue::structs::{ListParams, Sort};
+use crate::database::errors::Error as DatabaseErrors;
+use crate::quote::structs::{
+    DailyQuoteOverride, DailyQuoteParams, ModerationDecision, QuoteReport, ReviewPage,
+};
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct UserID {
+    /// ID of the user, quotes already shown to them are skipped
     pub user_id: String,
 }
 
@@ -10,3 +20,155 @@ pub struct UserAndQuoteID {
     pub user_id: String,
     pub quote_id: String,
 }
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct BatchQuery {
+    /// ID of the user, quotes already shown to them are skipped
+    pub user_id: String,
+    /// Number of quotes to get
+    #[param(minimum = 1, maximum = 50, default = 10)]
+    pub count: Option<u64>,
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ListQuery {
+    /// Field to sort by: `name`, `quotes` or, for authors, `likes`
+    #[param(default = "quotes")]
+    pub sort: Option<String>,
+    /// Sorting direction, `asc` or `desc`, ascending for names and descending for counters by default
+    pub order: Option<String>,
+    /// Maximum number of items on the page
+    #[param(maximum = 100, default = 20)]
+    pub limit: Option<u64>,
+    /// Number of items to skip
+    #[param(default = 0)]
+    pub offset: Option<u64>,
+}
+
+impl ListQuery {
+    pub fn to_params<S: Sort>(&self) -> Result<ListParams<S>> {
+        ListParams::parse(
+            self.sort.as_deref(),
+            self.order.as_deref(),
+            self.limit,
+            self.offset,
+        )
+    }
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct DailyQuery {
+    /// IANA timezone whose calendar day is used, DAILY_QUOTE_TIMEZONE by default
+    #[param(example = "Europe/Kyiv")]
+    pub timezone: Option<String>,
+    /// Pick the quote of the day only among quotes with this tag
+    pub tag: Option<String>,
+}
+
+impl DailyQuery {
+    pub fn to_params(&self) -> Result<DailyQuoteParams> {
+        DailyQuoteParams::parse(self.timezone.as_deref(), self.tag.as_deref())
+    }
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct DailyOverrideQuery {
+    /// Day to override in the YYYY-MM-DD format, today by default
+    #[param(format = Date)]
+    pub date: Option<String>,
+    /// IANA timezone whose calendar day is used, DAILY_QUOTE_TIMEZONE by default
+    #[param(example = "Europe/Kyiv")]
+    pub timezone: Option<String>,
+    /// Override the quote of the day only among quotes with this tag
+    pub tag: Option<String>,
+    /// ID of the quote to show on that day
+    pub quote_id: String,
+}
+
+impl DailyOverrideQuery {
+    pub fn to_override(&self) -> Result<DailyQuoteOverride> {
+        DailyQuoteOverride::parse(
+            self.date.as_deref(),
+            self.timezone.as_deref(),
+            self.tag.as_deref(),
+            &self.quote_id,
+        )
+    }
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ReportQuery {
+    /// ID of the user reporting the quote
+    pub user_id: String,
+    /// Why the quote is offensive or incorrect
+    #[param(max_length = 500)]
+    pub reason: Option<String>,
+}
+
+impl ReportQuery {
+    pub fn to_report(&self, quote_id: &str) -> Result<QuoteReport> {
+        QuoteReport::parse(&self.user_id, quote_id, self.reason.as_deref())
+    }
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ReviewQuery {
+    /// Maximum number of quotes on the page
+    #[param(maximum = 100, default = 20)]
+    pub limit: Option<u64>,
+    /// Number of quotes to skip
+    #[param(default = 0)]
+    pub offset: Option<u64>,
+}
+
+impl ReviewQuery {
+    pub fn to_page(&self) -> Result<ReviewPage> {
+        ReviewPage::parse(self.limit, self.offset)
+    }
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ModerationQuery {
+    /// New status of the quote: `pending`, `approved` or `hidden`
+    pub status: String,
+    /// Name of the admin recorded in the audit trail, `admin` by default
+    pub moderator: Option<String>,
+    /// Why the decision was made
+    pub note: Option<String>,
+}
+
+impl ModerationQuery {
+    pub fn to_decision(&self, quote_id: &str) -> Result<ModerationDecision> {
+        ModerationDecision::parse(
+            quote_id,
+            &self.status,
+            self.moderator.as_deref(),
+            self.note.as_deref(),
+        )
+    }
+}
+
+/// Tells whether the error means that the requested quote does not exist.
+pub fn is_not_found(err: &anyhow::Error) -> bool {
+    matches!(
+        err.downcast_ref::<DatabaseErrors>(),
+        Some(DatabaseErrors::ErrNotFound)
+    )
+}
+
+/// Field of the request an error message is about, matched by its name with or without underscores.
+pub fn violated_field<'a>(fields: &[&'a str], description: &str) -> Option<&'a str> {
+    fields
+        .iter()
+        .find(|field| {
+            description.contains(*field) || description.contains(&field.replace('_', " "))
+        })
+        .copied()
+}
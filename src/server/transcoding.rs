@@ -0,0 +1,566 @@
+use anyhow::{anyhow, bail, Context, Result};
+use axum::body::Bytes;
+use axum::extract::{Query, RawPathParams};
+use axum::http::{HeaderMap, Method, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{on, MethodFilter};
+use axum::{Json, Router};
+use futures::future::BoxFuture;
+use prost::Message;
+use prost_types::field_descriptor_proto::{Label, Type};
+use prost_types::DescriptorProto;
+use serde::de::DeserializeOwned;
+use serde_json::{json, Map, Value};
+use std::sync::Arc;
+use tonic::metadata::MetadataMap;
+use tonic::{Code, Request, Status};
+use tonic_types::StatusExt;
+
+use super::grpc_errors as errors;
+use super::grpc_handlers::Grpc;
+use super::proto::google::api::{http_rule::Pattern, HttpRule};
+use super::proto::quotes_server::Quotes;
+
+// Subset of `descriptor.proto` down to the method options, `prost_types` drops the
+// `google.api.http` extension as an unknown field.
+#[derive(Clone, PartialEq, Message)]
+struct FileDescriptorSet {
+    #[prost(message, repeated, tag = "1")]
+    file: Vec<FileDescriptorProto>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct FileDescriptorProto {
+    #[prost(string, tag = "2")]
+    package: String,
+    #[prost(message, repeated, tag = "4")]
+    message_type: Vec<DescriptorProto>,
+    #[prost(message, repeated, tag = "6")]
+    service: Vec<ServiceDescriptorProto>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct ServiceDescriptorProto {
+    #[prost(string, tag = "1")]
+    name: String,
+    #[prost(message, repeated, tag = "2")]
+    method: Vec<MethodDescriptorProto>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct MethodDescriptorProto {
+    #[prost(string, tag = "1")]
+    name: String,
+    #[prost(string, tag = "2")]
+    input_type: String,
+    #[prost(message, optional, tag = "4")]
+    options: Option<MethodOptions>,
+    #[prost(bool, tag = "5")]
+    client_streaming: bool,
+    #[prost(bool, tag = "6")]
+    server_streaming: bool,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct MethodOptions {
+    #[prost(message, optional, tag = "72295728")]
+    http: Option<HttpRule>,
+}
+
+/// Unary method called with the JSON form of its request.
+type Handler = fn(Arc<Grpc>, Request<Value>) -> BoxFuture<'static, Result<Value, Status>>;
+
+macro_rules! unary {
+    ($method:ident) => {
+        |grpc: Arc<Grpc>, request: Request<Value>| -> BoxFuture<'static, Result<Value, Status>> {
+            Box::pin(async move {
+                let request = decode(request)
+                    .map_err(|err| errors::invalid_argument(&[], err, "decode request"))?;
+                let response = grpc.$method(request).await?.into_inner();
+                serde_json::to_value(response)
+                    .map_err(|err| errors::from_service(err.into(), "encode response"))
+            })
+        }
+    };
+}
+
+fn handler(method: &str) -> Option<Handler> {
+    match method {
+        "Heartbeat" => Some(unary!(heartbeat)),
+        "GetQuoteHandler" => Some(unary!(get_quote_handler)),
+        "GetQuotesHandler" => Some(unary!(get_quotes_handler)),
+        "GetSameQuoteHandler" => Some(unary!(get_same_quote_handler)),
+        "LikeQuoteHandler" => Some(unary!(like_quote_handler)),
+        "DislikeQuote" => Some(unary!(dislike_quote)),
+        "SkipQuote" => Some(unary!(skip_quote)),
+        "GetTagsHandler" => Some(unary!(get_tags_handler)),
+        "GetAuthorsHandler" => Some(unary!(get_authors_handler)),
+        "GetQuoteOfTheDayHandler" => Some(unary!(get_quote_of_the_day_handler)),
+        "SetQuoteOfTheDayHandler" => Some(unary!(set_quote_of_the_day_handler)),
+        "ReportQuote" => Some(unary!(report_quote)),
+        "ReviewQueue" => Some(unary!(review_queue)),
+        "ModerateQuote" => Some(unary!(moderate_quote)),
+        "ModerationAudit" => Some(unary!(moderation_audit)),
+        "Stats" => Some(unary!(stats)),
+        _ => None,
+    }
+}
+
+fn decode<T: DeserializeOwned>(request: Request<Value>) -> Result<Request<T>> {
+    let (metadata, extensions, message) = request.into_parts();
+    let message = serde_json::from_value(message).context("failed to decode request")?;
+
+    Ok(Request::from_parts(metadata, extensions, message))
+}
+
+/// Field of a request message which can be set from the path or the query string.
+#[derive(Debug, Clone)]
+struct Field {
+    name: String,
+    json_name: String,
+    kind: Type,
+    repeated: bool,
+}
+
+impl Field {
+    /// JSON value of a path or query parameter.
+    fn parse(&self, value: &str) -> Result<Value> {
+        let value = match self.kind {
+            Type::String | Type::Bytes => Value::from(value),
+            Type::Bool => Value::from(
+                value
+                    .parse::<bool>()
+                    .with_context(|| format!("{} must be a boolean", self.name))?,
+            ),
+            Type::Double | Type::Float => Value::from(
+                value
+                    .parse::<f64>()
+                    .with_context(|| format!("{} must be a number", self.name))?,
+            ),
+            Type::Int32
+            | Type::Int64
+            | Type::Sint32
+            | Type::Sint64
+            | Type::Sfixed32
+            | Type::Sfixed64
+            | Type::Enum => Value::from(
+                value
+                    .parse::<i64>()
+                    .with_context(|| format!("{} must be an integer", self.name))?,
+            ),
+            Type::Uint32 | Type::Uint64 | Type::Fixed32 | Type::Fixed64 => Value::from(
+                value
+                    .parse::<u64>()
+                    .with_context(|| format!("{} must be a positive integer", self.name))?,
+            ),
+            Type::Message | Type::Group => bail!("{} can not be set from the url", self.name),
+        };
+
+        Ok(value)
+    }
+}
+
+/// HTTP binding of a unary method, from its `google.api.http` rule.
+#[derive(Debug)]
+struct Binding {
+    method: String,
+    verb: Method,
+    path: String,
+    body: String,
+    response_body: String,
+    fields: Vec<Field>,
+}
+
+impl Binding {
+    fn field(&self, name: &str) -> Result<&Field> {
+        self.fields
+            .iter()
+            .find(|field| field.name == name || field.json_name == name)
+            .ok_or_else(|| anyhow!("unknown field {name}"))
+    }
+
+    /// JSON form of the request message: the body, then the query string unless the body
+    /// holds every field, then the path parameters.
+    fn to_message(
+        &self,
+        params: &[(&str, &str)],
+        query: &[(String, String)],
+        body: &[u8],
+    ) -> Result<Value, Box<Status>> {
+        let invalid =
+            |field: &str, err| Box::new(errors::invalid_argument(&[field], err, "decode request"));
+
+        let mut message = Map::new();
+        if !self.body.is_empty() {
+            let value: Value = if body.is_empty() {
+                json!({})
+            } else {
+                serde_json::from_slice(body).map_err(|err| {
+                    Box::new(errors::invalid_argument(&[], err.into(), "decode request"))
+                })?
+            };
+
+            if self.body == "*" {
+                let Value::Object(fields) = value else {
+                    let err = anyhow!("body must be a JSON object");
+                    return Err(Box::new(errors::invalid_argument(
+                        &[],
+                        err,
+                        "decode request",
+                    )));
+                };
+                // the proto field names are accepted as well as the JSON ones
+                message = fields
+                    .into_iter()
+                    .map(|(name, value)| match self.field(&name) {
+                        Ok(field) => (field.json_name.clone(), value),
+                        Err(_) => (name, value),
+                    })
+                    .collect();
+            } else {
+                let field = self
+                    .field(&self.body)
+                    .map_err(|err| invalid(&self.body, err))?;
+                message.insert(field.json_name.clone(), value);
+            }
+        }
+
+        if self.body != "*" {
+            for (name, value) in query {
+                let field = self.field(name).map_err(|err| invalid(name, err))?;
+                let value = field.parse(value).map_err(|err| invalid(name, err))?;
+                if field.repeated {
+                    let values = message
+                        .entry(field.json_name.clone())
+                        .or_insert_with(|| Value::Array(Vec::new()));
+                    if let Value::Array(values) = values {
+                        values.push(value);
+                    }
+                } else {
+                    message.insert(field.json_name.clone(), value);
+                }
+            }
+        }
+
+        for (name, value) in params {
+            let field = self.field(name).map_err(|err| invalid(name, err))?;
+            let value = field.parse(value).map_err(|err| invalid(name, err))?;
+            message.insert(field.json_name.clone(), value);
+        }
+
+        Ok(Value::Object(message))
+    }
+
+    async fn transcode(
+        &self,
+        grpc: Arc<Grpc>,
+        params: RawPathParams,
+        query: Vec<(String, String)>,
+        headers: HeaderMap,
+        body: Bytes,
+    ) -> Response {
+        let params: Vec<(&str, &str)> = params.iter().collect();
+        let message = match self.to_message(&params, &query, &body) {
+            Ok(message) => message,
+            Err(status) => return to_error_response(*status),
+        };
+
+        // the method was checked to have a handler when the binding was built
+        let handler = handler(&self.method).unwrap();
+        let request = Request::from_parts(
+            MetadataMap::from_headers(headers),
+            Default::default(),
+            message,
+        );
+
+        match handler(grpc, request).await {
+            Ok(response) if self.response_body.is_empty() => Json(response).into_response(),
+            Ok(response) => Json(response[&self.response_body].clone()).into_response(),
+            Err(status) => to_error_response(status),
+        }
+    }
+}
+
+/// Bindings of the `quotes.Quotes` methods annotated with a `google.api.http` rule.
+fn bindings(descriptor_set: &[u8]) -> Result<Vec<Binding>> {
+    let descriptor_set =
+        FileDescriptorSet::decode(descriptor_set).context("failed to decode file descriptors")?;
+
+    let messages: Vec<(String, &DescriptorProto)> = descriptor_set
+        .file
+        .iter()
+        .flat_map(|file| {
+            file.message_type
+                .iter()
+                .map(|message| (format!(".{}.{}", file.package, message.name()), message))
+        })
+        .collect();
+
+    let mut bindings = Vec::new();
+    for file in descriptor_set
+        .file
+        .iter()
+        .filter(|file| file.package == "quotes")
+    {
+        for method in file.service.iter().flat_map(|service| &service.method) {
+            let Some(rule) = method
+                .options
+                .as_ref()
+                .and_then(|options| options.http.as_ref())
+            else {
+                continue;
+            };
+            if method.client_streaming || method.server_streaming {
+                bail!("streaming method {} can not be transcoded", method.name);
+            }
+            if handler(&method.name).is_none() {
+                bail!("method {} has no transcoding handler", method.name);
+            }
+
+            let input = messages
+                .iter()
+                .find(|(name, _)| *name == method.input_type)
+                .map(|(_, message)| *message)
+                .ok_or_else(|| anyhow!("message {} not found", method.input_type))?;
+            let fields = fields(input);
+
+            for rule in std::iter::once(rule).chain(&rule.additional_bindings) {
+                let binding = to_binding(&method.name, rule, fields.clone())
+                    .with_context(|| format!("invalid http rule of {}", method.name))?;
+                bindings.push(binding);
+            }
+        }
+    }
+
+    Ok(bindings)
+}
+
+fn fields(message: &DescriptorProto) -> Vec<Field> {
+    message
+        .field
+        .iter()
+        .map(|field| Field {
+            name: field.name().to_string(),
+            json_name: field.json_name().to_string(),
+            kind: field.r#type(),
+            repeated: field.label() == Label::Repeated,
+        })
+        .collect()
+}
+
+fn to_binding(method: &str, rule: &HttpRule, fields: Vec<Field>) -> Result<Binding> {
+    let (verb, template) = match &rule.pattern {
+        Some(Pattern::Get(path)) => (Method::GET, path),
+        Some(Pattern::Put(path)) => (Method::PUT, path),
+        Some(Pattern::Post(path)) => (Method::POST, path),
+        Some(Pattern::Delete(path)) => (Method::DELETE, path),
+        Some(Pattern::Patch(path)) => (Method::PATCH, path),
+        Some(Pattern::Custom(custom)) => (
+            Method::from_bytes(custom.kind.as_bytes()).context("failed to parse http method")?,
+            &custom.path,
+        ),
+        None => bail!("http rule has no pattern"),
+    };
+
+    let mut binding = Binding {
+        method: method.to_string(),
+        verb,
+        path: String::new(),
+        body: rule.body.clone(),
+        response_body: String::new(),
+        fields,
+    };
+
+    // templates with variables matching several segments or a verb suffix are not supported
+    if !template.starts_with('/') || template.contains(['*', '=', ':']) {
+        bail!("unsupported path template {template}");
+    }
+    for segment in template.split('/').filter(|segment| !segment.is_empty()) {
+        if let Some(name) = segment.strip_prefix('{').and_then(|s| s.strip_suffix('}')) {
+            binding.field(name)?;
+        }
+    }
+    binding.path = template.clone();
+
+    if !binding.body.is_empty() && binding.body != "*" {
+        binding.field(&binding.body)?;
+    }
+    if !rule.response_body.is_empty() {
+        binding.response_body = snake_to_camel(&rule.response_body);
+    }
+
+    Ok(binding)
+}
+
+fn snake_to_camel(name: &str) -> String {
+    let mut parts = name.split('_');
+    let first = parts.next().unwrap_or_default().to_string();
+    parts.fold(first, |mut camel, part| {
+        let mut chars = part.chars();
+        if let Some(char) = chars.next() {
+            camel.extend(char.to_uppercase());
+            camel.push_str(chars.as_str());
+        }
+        camel
+    })
+}
+
+/// Routes transcoding HTTP/JSON requests to the unary methods of the service, following
+/// their `google.api.http` rules.
+pub fn router(descriptor_set: &[u8], grpc: Arc<Grpc>) -> Result<Router> {
+    let mut router = Router::new();
+    for binding in bindings(descriptor_set)? {
+        let filter = MethodFilter::try_from(binding.verb.clone())
+            .map_err(|err| anyhow!("unsupported http method: {err}"))?;
+        let path = binding.path.clone();
+        let binding = Arc::new(binding);
+        let grpc = grpc.clone();
+
+        router = router.route(
+            &path,
+            on(
+                filter,
+                move |params: RawPathParams,
+                      Query(query): Query<Vec<(String, String)>>,
+                      headers: HeaderMap,
+                      body: Bytes| async move {
+                    binding.transcode(grpc, params, query, headers, body).await
+                },
+            ),
+        );
+    }
+
+    Ok(router)
+}
+
+/// HTTP status of a gRPC code, as mapped by the gRPC gateways.
+fn to_http_status(code: Code) -> StatusCode {
+    match code {
+        Code::Ok => StatusCode::OK,
+        Code::Cancelled => StatusCode::from_u16(499).unwrap(),
+        Code::InvalidArgument | Code::FailedPrecondition | Code::OutOfRange => {
+            StatusCode::BAD_REQUEST
+        }
+        Code::DeadlineExceeded => StatusCode::GATEWAY_TIMEOUT,
+        Code::NotFound => StatusCode::NOT_FOUND,
+        Code::AlreadyExists | Code::Aborted => StatusCode::CONFLICT,
+        Code::PermissionDenied => StatusCode::FORBIDDEN,
+        Code::Unauthenticated => StatusCode::UNAUTHORIZED,
+        Code::ResourceExhausted => StatusCode::TOO_MANY_REQUESTS,
+        Code::Unimplemented => StatusCode::NOT_IMPLEMENTED,
+        Code::Unavailable => StatusCode::SERVICE_UNAVAILABLE,
+        Code::Unknown | Code::Internal | Code::DataLoss => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+/// JSON form of a `google.rpc.Status` with its details.
+fn to_error_response(status: Status) -> Response {
+    let details = status.get_error_details();
+    let mut body = Vec::new();
+    if let Some(error_info) = details.error_info() {
+        body.push(json!({
+            "@type": "type.googleapis.com/google.rpc.ErrorInfo",
+            "reason": error_info.reason,
+            "domain": error_info.domain,
+            "metadata": error_info.metadata,
+        }));
+    }
+    if let Some(bad_request) = details.bad_request() {
+        let violations: Vec<Value> = bad_request
+            .field_violations
+            .iter()
+            .map(|violation| {
+                json!({ "field": violation.field, "description": violation.description })
+            })
+            .collect();
+        body.push(json!({
+            "@type": "type.googleapis.com/google.rpc.BadRequest",
+            "fieldViolations": violations,
+        }));
+    }
+
+    let body = json!({
+        "code": status.code() as i32,
+        "message": status.message(),
+        "details": body,
+    });
+    (to_http_status(status.code()), Json(body)).into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::proto::FILE_DESCRIPTOR_SET;
+    use super::*;
+
+    fn binding(method: &str) -> Binding {
+        bindings(FILE_DESCRIPTOR_SET)
+            .unwrap()
+            .into_iter()
+            .find(|binding| binding.method == method)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_bindings_follow_http_rules() {
+        let bindings = bindings(FILE_DESCRIPTOR_SET).unwrap();
+        assert_eq!(bindings.len(), 16);
+
+        let same = binding("GetSameQuoteHandler");
+        assert_eq!(same.verb, Method::GET);
+        assert_eq!(same.path, "/v1/quotes/{quote_id}/similar");
+
+        let set_daily = binding("SetQuoteOfTheDayHandler");
+        assert_eq!(set_daily.verb, Method::PUT);
+        assert_eq!(set_daily.body, "*");
+
+        let moderate = binding("ModerateQuote");
+        assert_eq!(moderate.verb, Method::PUT);
+        assert_eq!(moderate.path, "/v1/admin/quotes/{quote_id}/moderation");
+    }
+
+    #[test]
+    fn test_to_message_converts_parameters() {
+        let binding = binding("GetQuotesHandler");
+
+        let message = binding
+            .to_message(
+                &[("user_id", "user")],
+                &[("count".to_string(), "3".to_string())],
+                b"",
+            )
+            .unwrap();
+        assert_eq!(message, json!({ "userId": "user", "count": 3 }));
+
+        let status = binding
+            .to_message(&[], &[("count".to_string(), "three".to_string())], b"")
+            .unwrap_err();
+        assert_eq!(status.code(), Code::InvalidArgument);
+        assert_eq!(
+            status
+                .get_error_details()
+                .bad_request()
+                .unwrap()
+                .field_violations[0]
+                .field,
+            "count"
+        );
+
+        let status = binding
+            .to_message(&[], &[("size".to_string(), "3".to_string())], b"")
+            .unwrap_err();
+        assert_eq!(status.message(), "unknown field size");
+    }
+
+    #[test]
+    fn test_to_message_reads_the_body() {
+        let binding = binding("SetQuoteOfTheDayHandler");
+
+        let message = binding
+            .to_message(&[], &[], br#"{"quote_id": "quote", "date": "2025-01-01"}"#)
+            .unwrap();
+        assert_eq!(message, json!({ "quoteId": "quote", "date": "2025-01-01" }));
+
+        let status = binding.to_message(&[], &[], b"[]").unwrap_err();
+        assert_eq!(status.code(), Code::InvalidArgument);
+    }
+}
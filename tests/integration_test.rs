@@ -1,11 +1,24 @@
 use enclose::enclose;
 use fake::{uuid, Fake};
-use std::{env, time::Duration};
+use prost::Message;
+use sha2::{Digest, Sha256};
+use std::{
+    collections::{BTreeSet, HashSet},
+    env,
+    time::Duration,
+};
 use testcontainers::{runners::AsyncRunner, ImageExt};
 use testcontainers_modules::postgres;
 use tokio::time::sleep;
+use tonic_reflection::pb::v1::{
+    server_reflection_client::ServerReflectionClient, server_reflection_request::MessageRequest,
+    server_reflection_response::MessageResponse, ServerReflectionRequest,
+};
 
-use quotes_rs::{app, test_tools::Tools};
+use quotes_rs::{
+    app,
+    test_tools::{Tools, ADMIN_TOKEN, CORS_ORIGIN},
+};
 
 #[tokio::test]
 async fn test_integration() {
@@ -57,6 +70,18 @@ async fn test_integration() {
     get_quote(&cfg.server_config.addr, &tools, &client, &user_id).await;
     like_quote(&cfg.server_config.addr, &tools, &client, &user_id).await;
     get_same_quote(&cfg.server_config.addr, &tools, &client, &user_id).await;
+    get_quotes(&cfg.server_config.addr, &tools, &client).await;
+    get_authors(&cfg.server_config.addr, &tools, &client).await;
+    quote_of_the_day(&cfg.server_config.addr, &tools, &client).await;
+    moderation(&cfg.server_config.addr, &tools, &client).await;
+    feedback(&cfg.server_config.addr, &tools, &client).await;
+    graphql_schema(&cfg.server_config.addr, &tools, &client, &user_id).await;
+    graphql_limits(&cfg.server_config.addr, &client).await;
+    canonical_tags(&tools).await;
+    duplicate_quote(&tools).await;
+    concurrent_duplicates(&tools).await;
+    provider_ids(&tools).await;
+    transport_parity(&tools, &client).await;
 
     server.abort();
     db_container
@@ -138,3 +163,1151 @@ async fn get_same_quote(addr: &str, tools: &Tools, client: &reqwest::Client, use
 
     tools.compare_quotes(body.as_str(), same_quote);
 }
+
+async fn get_quotes(addr: &str, tools: &Tools, client: &reqwest::Client) {
+    let user_id: String = uuid::UUIDv4.fake();
+
+    let resp = client
+        .get(format!("http://{addr}/batch"))
+        .query(&[("user_id", user_id.as_str()), ("count", "3")])
+        .send()
+        .await
+        .expect("failed to receive quotes from server");
+    assert_eq!(resp.status(), 200);
+
+    let body = resp
+        .text()
+        .await
+        .expect("failed to receive quotes from server");
+
+    let body: serde_json::Value = serde_json::from_str(&body).expect("failed to parse quotes");
+    let ids: HashSet<&str> = body
+        .as_array()
+        .expect("quotes are not a list")
+        .iter()
+        .map(|quote| quote["id"].as_str().expect("quote has no id"))
+        .collect();
+    assert_eq!(ids.len(), 3);
+    assert!(ids.contains(tools.get_main_quote().id.as_str()));
+
+    for id in ids {
+        tools
+            .get_quote(id)
+            .await
+            .expect("failed to get quote from database");
+    }
+
+    let resp = client
+        .get(format!("http://{addr}/batch"))
+        .query(&[("user_id", user_id.as_str()), ("count", "0")])
+        .send()
+        .await
+        .expect("failed to receive quotes from server");
+    assert_eq!(resp.status(), 400);
+}
+
+async fn get_authors(addr: &str, tools: &Tools, client: &reqwest::Client) {
+    let quote = tools.get_main_quote();
+
+    let resp = client
+        .get(format!("http://{addr}/authors"))
+        .query(&[("sort", "name"), ("limit", "100")])
+        .send()
+        .await
+        .expect("failed to receive authors from server");
+    assert_eq!(resp.status(), 200);
+
+    let body = resp
+        .text()
+        .await
+        .expect("failed to receive authors from server");
+
+    let body: serde_json::Value = serde_json::from_str(&body).expect("failed to parse authors");
+    let author = body["items"]
+        .as_array()
+        .expect("authors are not a list")
+        .iter()
+        .find(|author| author["name"] == quote.author.as_str())
+        .expect("main quote author is missing");
+    assert_eq!(author["quotes"], 2);
+    assert_eq!(author["likes"], 1);
+}
+
+async fn quote_of_the_day(addr: &str, tools: &Tools, client: &reqwest::Client) {
+    let get_daily = || async {
+        let resp = client
+            .get(format!("http://{addr}/daily"))
+            .query(&[("timezone", "Europe/Kyiv")])
+            .send()
+            .await
+            .expect("failed to receive quote of the day from server");
+        assert_eq!(resp.status(), 200);
+
+        let body = resp
+            .text()
+            .await
+            .expect("failed to receive quote of the day from server");
+        let body: serde_json::Value =
+            serde_json::from_str(&body).expect("failed to parse quote of the day");
+        body["id"].as_str().expect("quote has no id").to_string()
+    };
+
+    let picked = get_daily().await;
+    assert_eq!(get_daily().await, picked);
+
+    let quote = tools.get_main_quote();
+    let set_daily = |token: &'static str| {
+        client
+            .put(format!("http://{addr}/admin/daily"))
+            .query(&[("timezone", "Europe/Kyiv"), ("quote_id", quote.id.as_str())])
+            .bearer_auth(token)
+            .send()
+    };
+
+    let resp = set_daily("wrong-token")
+        .await
+        .expect("failed to set quote of the day");
+    assert_eq!(resp.status(), 401);
+
+    let resp = set_daily(ADMIN_TOKEN)
+        .await
+        .expect("failed to set quote of the day");
+    assert_eq!(resp.status(), 200);
+    assert_eq!(get_daily().await, quote.id);
+}
+
+async fn moderation(addr: &str, tools: &Tools, client: &reqwest::Client) {
+    let quote = tools.get_random_quote();
+    tools
+        .save_quote(quote.clone())
+        .await
+        .expect("failed to save quote");
+
+    // the threshold of the test config is 3 reports
+    for _ in 0..3 {
+        let resp = client
+            .post(format!("http://{addr}/v1/quotes/{}/report", quote.id))
+            .query(&[
+                ("user_id", uuid::UUIDv4.fake::<String>()),
+                ("reason", "offensive".to_string()),
+            ])
+            .send()
+            .await
+            .expect("failed to report quote");
+        assert_eq!(resp.status(), 200);
+    }
+    let reported = tools
+        .get_quote(&quote.id)
+        .await
+        .expect("failed to get quote from database");
+    assert_eq!(reported.status, "pending");
+
+    let admin_request = |method: reqwest::Method, path: String| {
+        client
+            .request(method, format!("http://{addr}{path}"))
+            .bearer_auth(ADMIN_TOKEN)
+            .send()
+    };
+    let json = |resp: reqwest::Response| async move {
+        assert_eq!(resp.status(), 200);
+        let body = resp.text().await.expect("failed to receive response");
+        serde_json::from_str::<serde_json::Value>(&body).expect("failed to parse response")
+    };
+
+    let queue = json(
+        admin_request(reqwest::Method::GET, "/v1/admin/reports".to_string())
+            .await
+            .expect("failed to get review queue"),
+    )
+    .await;
+    let item = queue
+        .as_array()
+        .expect("review queue is not a list")
+        .iter()
+        .find(|item| item["quote"]["id"] == quote.id.as_str())
+        .expect("reported quote is not in the review queue");
+    assert_eq!(item["status"], "pending");
+    assert_eq!(item["reports"].as_array().unwrap().len(), 3);
+
+    let entry = json(
+        admin_request(
+            reqwest::Method::PUT,
+            format!(
+                "/v1/admin/quotes/{}/moderation?status=hidden&note=offensive",
+                quote.id
+            ),
+        )
+        .await
+        .expect("failed to moderate quote"),
+    )
+    .await;
+    assert_eq!(entry["previous_status"], "pending");
+    assert_eq!(entry["moderator"], "admin");
+
+    let audit = json(
+        admin_request(
+            reqwest::Method::GET,
+            format!("/v1/admin/quotes/{}/audit", quote.id),
+        )
+        .await
+        .expect("failed to get moderation audit"),
+    )
+    .await;
+    let statuses: Vec<_> = audit
+        .as_array()
+        .expect("audit is not a list")
+        .iter()
+        .map(|entry| (entry["moderator"].clone(), entry["status"].clone()))
+        .collect();
+    assert_eq!(
+        statuses,
+        vec![
+            (serde_json::json!("reports"), serde_json::json!("pending")),
+            (serde_json::json!("admin"), serde_json::json!("hidden")),
+        ]
+    );
+
+    // the decision resolved the reports and the quote is not shown anymore
+    let queue = json(
+        admin_request(reqwest::Method::GET, "/v1/admin/reports".to_string())
+            .await
+            .expect("failed to get review queue"),
+    )
+    .await;
+    assert!(!queue.to_string().contains(&quote.id));
+    let resp = client
+        .get(format!("http://{addr}/v1/quotes"))
+        .query(&[
+            ("user_id", uuid::UUIDv4.fake::<String>()),
+            ("count", "50".to_string()),
+        ])
+        .send()
+        .await
+        .expect("failed to receive quotes from server");
+    assert!(!resp
+        .text()
+        .await
+        .expect("failed to receive quotes from server")
+        .contains(&quote.id));
+    let resp = client
+        .get(format!("http://{addr}/v1/quotes/{}", quote.id))
+        .send()
+        .await
+        .expect("failed to receive quote from server");
+    assert_eq!(resp.status(), 404);
+    let body = graphql(
+        addr,
+        client,
+        &format!("{{ quote(id: \"{}\") {{ id }} }}", quote.id),
+    )
+    .await;
+    assert_eq!(body["errors"][0]["extensions"]["code"], "NOT_FOUND");
+
+    let body = graphql(addr, client, "{ reviewQueue { quote { id } } }").await;
+    assert_eq!(body["errors"][0]["extensions"]["code"], "UNAUTHORIZED");
+}
+
+async fn feedback(addr: &str, tools: &Tools, client: &reqwest::Client) {
+    let user_id: String = uuid::UUIDv4.fake();
+    let quote = tools.get_main_quote();
+
+    // only the viewed quotes can be disliked or skipped
+    let resp = client
+        .get(format!("http://{addr}/same"))
+        .query(&[("user_id", user_id.as_str()), ("quote_id", &quote.id)])
+        .send()
+        .await
+        .expect("failed to receive quote from server");
+    assert_eq!(resp.status(), 200);
+    let body: serde_json::Value = serde_json::from_str(
+        &resp
+            .text()
+            .await
+            .expect("failed to receive quote from server"),
+    )
+    .expect("failed to parse quote");
+    let viewed_id = body["id"].as_str().expect("quote has no id");
+
+    let give_feedback = |quote_id: &str, feedback: &str| {
+        client
+            .patch(format!("http://{addr}/v1/quotes/{quote_id}/{feedback}"))
+            .query(&[("user_id", user_id.as_str())])
+            .send()
+    };
+
+    for feedback in ["dislike", "skip"] {
+        let resp = give_feedback(viewed_id, feedback)
+            .await
+            .expect("failed to give feedback");
+        assert_eq!(resp.status(), 200);
+
+        // the last feedback replaces the previous one
+        let given = tools
+            .get_feedback(&user_id)
+            .await
+            .expect("failed to get feedback from database");
+        assert_eq!(given.len(), 1);
+        assert_eq!(given[0].0, feedback);
+        assert_eq!(given[0].1.id, viewed_id);
+    }
+
+    let resp = give_feedback(&uuid::UUIDv4.fake::<String>(), "dislike")
+        .await
+        .expect("failed to give feedback");
+    assert_eq!(resp.status(), 404);
+}
+
+async fn graphql_schema(addr: &str, tools: &Tools, client: &reqwest::Client, user_id: &str) {
+    let quote = tools.get_main_quote();
+
+    let body = graphql(
+        addr,
+        client,
+        &format!(
+            r#"{{ version quote(id: "{}") {{ id text likes
+                author {{ name quoteCount quotes {{ id }} }}
+                tags {{ name quoteCount quotes {{ id }} }} }} }}"#,
+            quote.id
+        ),
+    )
+    .await;
+    assert_eq!(body["data"]["version"], "2.0");
+
+    let received = &body["data"]["quote"];
+    assert_eq!(received["id"], quote.id.as_str());
+    assert_eq!(received["text"], quote.quote.as_str());
+    assert_eq!(received["author"]["name"], quote.author.as_str());
+    assert!(received["author"]["quoteCount"].as_i64().unwrap() >= 1);
+    assert!(received["author"]["quotes"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .any(|author_quote| author_quote["id"] == quote.id.as_str()));
+    assert_eq!(received["tags"].as_array().unwrap().len(), quote.tags.len());
+
+    let body = graphql(
+        addr,
+        client,
+        &format!(r#"{{ history(userId: "{user_id}", limit: 50) {{ id views liked(userId: "{user_id}") }} }}"#),
+    )
+    .await;
+    let history = body["data"]["history"].as_array().unwrap();
+    let main_quote = history
+        .iter()
+        .find(|history_quote| history_quote["id"] == quote.id.as_str())
+        .expect("liked quote is missing from history");
+    assert_eq!(main_quote["liked"], true);
+    assert!(main_quote["views"].as_i64().unwrap() >= 1);
+    assert!(history
+        .iter()
+        .all(|history_quote| history_quote["views"].as_i64().unwrap() >= 1));
+
+    let missing_id: String = uuid::UUIDv4.fake();
+    let body = graphql(
+        addr,
+        client,
+        &format!(r#"{{ quote(id: "{missing_id}") {{ id }} }}"#),
+    )
+    .await;
+    assert_eq!(body["errors"][0]["extensions"]["code"], "NOT_FOUND");
+
+    let body = graphql(
+        addr,
+        client,
+        r#"mutation { setQuoteOfTheDay(quoteId: "any") { id } }"#,
+    )
+    .await;
+    assert_eq!(body["errors"][0]["extensions"]["code"], "UNAUTHORIZED");
+}
+
+async fn graphql_limits(addr: &str, client: &reqwest::Client) {
+    let nested = "author { quotes { ".repeat(5);
+    let closing = "} } ".repeat(5);
+    let (status, body) = graphql_request(
+        addr,
+        client,
+        serde_json::json!({
+            "query": format!(r#"{{ quote(id: "any") {{ {nested} text {closing} }} }}"#)
+        }),
+    )
+    .await;
+    assert_eq!(status, 400);
+    assert_eq!(body["errors"][0]["extensions"]["code"], "QUERY_TOO_DEEP");
+
+    let (status, body) = graphql_request(
+        addr,
+        client,
+        serde_json::json!({
+            "query": "{ tags(limit: 100) { items { quotes(limit: 50) { views } } } }"
+        }),
+    )
+    .await;
+    assert_eq!(status, 400);
+    assert_eq!(body["errors"][0]["extensions"]["code"], "QUERY_TOO_COMPLEX");
+
+    // the test config accepts 10 operations per batch
+    let batch = vec![serde_json::json!({ "query": "{ version }" }); 11];
+    let (status, body) = graphql_request(addr, client, serde_json::json!(batch)).await;
+    assert_eq!(status, 400);
+    assert_eq!(body["errors"][0]["extensions"]["code"], "BATCH_TOO_LARGE");
+
+    let (status, body) = graphql_request(addr, client, serde_json::json!(batch[1..])).await;
+    assert_eq!(status, 200);
+    assert_eq!(body.as_array().map(Vec::len), Some(10));
+
+    let query = "query persisted { version }";
+    let persisted = serde_json::json!({
+        "persistedQuery": { "version": 1, "sha256Hash": format!("{:x}", Sha256::digest(query)) }
+    });
+
+    let (status, body) =
+        graphql_request(addr, client, serde_json::json!({ "extensions": persisted })).await;
+    assert_eq!(status, 400);
+    assert_eq!(
+        body["errors"][0]["extensions"]["code"],
+        "PERSISTED_QUERY_NOT_FOUND"
+    );
+
+    let (status, _) = graphql_request(
+        addr,
+        client,
+        serde_json::json!({ "query": query, "extensions": persisted }),
+    )
+    .await;
+    assert_eq!(status, 200);
+
+    let (status, body) =
+        graphql_request(addr, client, serde_json::json!({ "extensions": persisted })).await;
+    assert_eq!(status, 200);
+    assert_eq!(body["data"]["version"], "2.0");
+}
+
+async fn canonical_tags(tools: &Tools) {
+    let mut quote = tools.get_random_quote();
+    quote.tags = vec![
+        "Motivation".to_string(),
+        "inspirational".to_string(),
+        " Famous  Quotes ".to_string(),
+    ];
+
+    tools
+        .save_quote(quote.clone())
+        .await
+        .expect("failed to save quote with aliased tags");
+
+    let database_quote = tools
+        .get_quote(&quote.id)
+        .await
+        .expect("failed to get quote from database");
+    assert_eq!(database_quote.tags, vec!["motivational", "famous-quotes"]);
+}
+
+async fn duplicate_quote(tools: &Tools) {
+    let quote = tools.get_main_quote();
+    let mut duplicate = tools.get_random_quote();
+    duplicate.quote = format!("\u{201c}{}\u{201d}", quote.quote.to_uppercase());
+
+    tools
+        .save_quote(duplicate.clone())
+        .await
+        .expect("failed to save duplicate quote");
+
+    assert!(tools.get_quote(&duplicate.id).await.is_err());
+
+    let database_quote = tools
+        .get_quote(&quote.id)
+        .await
+        .expect("failed to get quote from database");
+    assert_eq!(database_quote.quote, quote.quote);
+    assert_eq!(database_quote.likes, 1);
+}
+
+async fn concurrent_duplicates(tools: &Tools) {
+    // the later rounds race on the connections the earlier ones opened
+    for _ in 0..5 {
+        // the author and tags are stored already, nothing holds the copies back before the insert
+        let quote = tools.get_same_quote();
+        let copies: Vec<_> = (0..8)
+            .map(|_| {
+                let mut copy = quote.clone();
+                copy.id = uuid::UUIDv4.fake();
+                copy
+            })
+            .collect();
+
+        let saves =
+            futures::future::join_all(copies.iter().map(|copy| tools.save_quote(copy.clone())));
+        for saved in saves.await {
+            saved.expect("failed to save concurrent duplicate");
+        }
+
+        let mut stored = 0;
+        for copy in &copies {
+            if tools.get_quote(&copy.id).await.is_ok() {
+                stored += 1;
+            }
+        }
+        assert_eq!(stored, 1);
+    }
+}
+
+async fn provider_ids(tools: &Tools) {
+    let mut first = tools.get_random_quote();
+    first.source = "dummyjson".to_string();
+    first.external_id = Some("42".to_string());
+
+    let mut updated = tools.get_random_quote();
+    updated.source = first.source.clone();
+    updated.external_id = first.external_id.clone();
+
+    let mut other_provider = tools.get_random_quote();
+    other_provider.external_id = first.external_id.clone();
+
+    for quote in [&first, &updated, &other_provider] {
+        tools
+            .save_quote(quote.clone())
+            .await
+            .expect("failed to save provider quote");
+    }
+
+    assert!(tools.get_quote(&updated.id).await.is_err());
+
+    let database_quote = tools
+        .get_quote(&first.id)
+        .await
+        .expect("failed to get quote from database");
+    assert_eq!(database_quote.quote, updated.quote);
+
+    let database_quote = tools
+        .get_quote(&other_provider.id)
+        .await
+        .expect("failed to get quote from database");
+    assert_eq!(database_quote.source, "manual");
+}
+
+async fn transport_parity(tools: &Tools, client: &reqwest::Client) {
+    let transports = [
+        ("actix", "0.0.0.0:1142"),
+        ("rocket", "0.0.0.0:1143"),
+        ("axum", "0.0.0.0:1144"),
+        ("grpc", "0.0.0.0:1145"),
+    ];
+
+    let servers: Vec<_> = transports
+        .iter()
+        .map(|(service_type, addr)| {
+            let mut cfg = tools.get_config();
+            cfg.server_config.service_type = service_type.to_string();
+            cfg.server_config.addr = addr.to_string();
+            tokio::spawn(async move { app::start(cfg).await })
+        })
+        .collect();
+    sleep(Duration::from_secs(2)).await; // wait until the servers are ready
+
+    let grpc_operations = grpc_operations("0.0.0.0:1145").await;
+    assert!(grpc_operations.contains("getquotehandler"));
+    grpc_browser_clients("0.0.0.0:1145", client).await;
+
+    let mut graphql_schema = None;
+    let mut rest_replies = None;
+    for (service_type, addr) in &transports[..3] {
+        versioned_routes(service_type, addr, client).await;
+        cors_policy(service_type, addr, client).await;
+        conditional_requests(service_type, addr, client).await;
+
+        let replies = rest_scenario(addr, client).await;
+        assert_eq!(
+            rest_replies.get_or_insert_with(|| replies.clone()),
+            &replies,
+            "{service_type} answers differently"
+        );
+
+        for page in ["graphiql", "playground"] {
+            let resp = client
+                .get(format!("http://{addr}/{page}"))
+                .send()
+                .await
+                .expect("failed to receive graphql page from server");
+            assert_eq!(resp.status(), 200, "{service_type} does not serve /{page}");
+        }
+
+        let operations = graphql_operations(addr, client).await;
+        assert!(
+            grpc_operations.is_subset(&operations),
+            "{service_type} graphql misses grpc operations"
+        );
+        assert_eq!(
+            graphql_schema.get_or_insert_with(|| operations.clone()),
+            &operations,
+            "{service_type} graphql operations differ"
+        );
+    }
+
+    servers.iter().for_each(|server| server.abort());
+}
+
+/// Same requests sent to every HTTP server, the status, content type and error body of the
+/// replies must not depend on the framework.
+async fn rest_scenario(addr: &str, client: &reqwest::Client) -> Vec<String> {
+    let user_id: String = uuid::UUIDv4.fake();
+    // only the viewed quotes can be liked
+    let resp = client
+        .get(format!("http://{addr}/v1/quotes/random?user_id={user_id}"))
+        .send()
+        .await
+        .expect("failed to receive quote from server");
+    // requests without an id get a generated one
+    assert!(resp.headers().contains_key("x-request-id"));
+    let quote: serde_json::Value = serde_json::from_str(
+        &resp
+            .text()
+            .await
+            .expect("failed to receive quote from server"),
+    )
+    .expect("failed to parse quote");
+    let quote_id = quote["id"].as_str().expect("quote has no id");
+    let requests = [
+        (reqwest::Method::GET, "/v1/heartbeat".to_string(), None, 200),
+        (
+            reqwest::Method::GET,
+            "/v1/quotes/random".to_string(),
+            None,
+            400,
+        ),
+        (
+            reqwest::Method::GET,
+            format!("/v1/quotes?user_id={user_id}&count=0"),
+            None,
+            400,
+        ),
+        (
+            reqwest::Method::GET,
+            format!("/v1/quotes?user_id={user_id}&count=2"),
+            None,
+            200,
+        ),
+        (
+            reqwest::Method::PATCH,
+            format!("/v1/quotes/{quote_id}/like?user_id={user_id}"),
+            None,
+            200,
+        ),
+        (
+            reqwest::Method::PATCH,
+            format!("/like?quote_id={quote_id}"),
+            None,
+            400,
+        ),
+        (
+            reqwest::Method::PATCH,
+            format!("/v1/quotes/{quote_id}/dislike?user_id={user_id}"),
+            None,
+            200,
+        ),
+        (
+            reqwest::Method::PATCH,
+            format!("/v1/quotes/{quote_id}/skip"),
+            None,
+            400,
+        ),
+        (
+            reqwest::Method::PATCH,
+            format!(
+                "/v1/quotes/{}/skip?user_id={user_id}",
+                uuid::UUIDv4.fake::<String>()
+            ),
+            None,
+            404,
+        ),
+        (
+            reqwest::Method::GET,
+            "/v1/tags?limit=-1".to_string(),
+            None,
+            400,
+        ),
+        (
+            reqwest::Method::GET,
+            "/v1/authors?sort=unknown".to_string(),
+            None,
+            400,
+        ),
+        (
+            reqwest::Method::GET,
+            "/v1/daily?timezone=Mars/Olympus".to_string(),
+            None,
+            400,
+        ),
+        (
+            reqwest::Method::PUT,
+            format!("/v1/admin/daily?quote_id={quote_id}"),
+            None,
+            401,
+        ),
+        (
+            reqwest::Method::PUT,
+            "/v1/admin/daily?date=yesterday".to_string(),
+            Some(ADMIN_TOKEN),
+            400,
+        ),
+        (
+            reqwest::Method::PUT,
+            format!("/v1/admin/daily?quote_id={}", uuid::UUIDv4.fake::<String>()),
+            Some(ADMIN_TOKEN),
+            404,
+        ),
+        (
+            reqwest::Method::POST,
+            format!(
+                "/v1/quotes/{}/report?user_id={user_id}",
+                uuid::UUIDv4.fake::<String>()
+            ),
+            None,
+            404,
+        ),
+        (
+            reqwest::Method::GET,
+            "/v1/admin/reports".to_string(),
+            None,
+            401,
+        ),
+        (
+            reqwest::Method::GET,
+            "/v1/admin/stats".to_string(),
+            None,
+            401,
+        ),
+        (
+            reqwest::Method::GET,
+            "/v1/admin/stats".to_string(),
+            Some(ADMIN_TOKEN),
+            200,
+        ),
+        (
+            reqwest::Method::PUT,
+            format!("/v1/admin/quotes/{quote_id}/moderation?status=deleted"),
+            Some(ADMIN_TOKEN),
+            400,
+        ),
+        (reqwest::Method::GET, "/v1/unknown".to_string(), None, 404),
+        (
+            reqwest::Method::DELETE,
+            "/v1/heartbeat".to_string(),
+            None,
+            405,
+        ),
+        (
+            reqwest::Method::POST,
+            format!("/v1/quotes/{quote_id}/like?user_id={user_id}"),
+            None,
+            405,
+        ),
+        (reqwest::Method::PUT, "/graphql".to_string(), None, 405),
+        (
+            reqwest::Method::GET,
+            "/graphql?query=a&query=b".to_string(),
+            None,
+            400,
+        ),
+    ];
+
+    let mut replies = Vec::new();
+    for (index, (method, path, token, status)) in requests.into_iter().enumerate() {
+        let request_id = format!("scenario-{index}");
+        let mut request = client
+            .request(method.clone(), format!("http://{addr}{path}"))
+            .header("x-request-id", &request_id);
+        if let Some(token) = token {
+            request = request.bearer_auth(token);
+        }
+        let resp = request
+            .send()
+            .await
+            .expect("failed to receive response from server");
+        assert_eq!(
+            resp.status(),
+            status,
+            "unexpected status of {method} {path}"
+        );
+        assert_eq!(resp.headers()["x-request-id"], request_id.as_str());
+
+        let content_type = resp
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .map(|value| value.to_str().unwrap_or_default().to_string());
+        let allow = resp
+            .headers()
+            .get(reqwest::header::ALLOW)
+            .map(|value| value.to_str().unwrap_or_default().to_string());
+        let body = resp
+            .text()
+            .await
+            .expect("failed to receive response from server");
+        // successful bodies differ between the calls, only the errors are compared
+        let body = if status == 200 {
+            String::new()
+        } else {
+            let error: serde_json::Value =
+                serde_json::from_str(&body).expect("failed to parse error");
+            assert_eq!(error["request_id"], request_id.as_str());
+            body
+        };
+        replies.push(format!(
+            "{method} {status} {content_type:?} {allow:?} {body}"
+        ));
+    }
+
+    replies
+}
+
+/// Reads answered with `304` while the quote is unchanged and with a new `ETag` once it's liked.
+async fn conditional_requests(service_type: &str, addr: &str, client: &reqwest::Client) {
+    let user_id: String = uuid::UUIDv4.fake();
+    let quote: serde_json::Value = serde_json::from_str(
+        &client
+            .get(format!("http://{addr}/v1/quotes/random?user_id={user_id}"))
+            .send()
+            .await
+            .expect("failed to receive quote from server")
+            .text()
+            .await
+            .expect("failed to receive quote from server"),
+    )
+    .expect("failed to parse quote");
+    let quote_id = quote["id"].as_str().expect("quote has no id");
+
+    let get = |etag: Option<String>| {
+        let mut request = client.get(format!("http://{addr}/v1/quotes/{quote_id}"));
+        if let Some(etag) = etag {
+            request = request.header("If-None-Match", etag);
+        }
+        request.send()
+    };
+    let etag_of = |resp: &reqwest::Response| {
+        resp.headers()["etag"]
+            .to_str()
+            .expect("etag is not a string")
+            .to_string()
+    };
+
+    let resp = get(None)
+        .await
+        .expect("failed to receive quote from server");
+    assert_eq!(resp.status(), 200, "{service_type} can't get quote by id");
+    assert_eq!(resp.headers()["cache-control"], "public, max-age=60");
+    let etag = etag_of(&resp);
+
+    let resp = get(Some(etag.clone()))
+        .await
+        .expect("failed to receive quote from server");
+    assert_eq!(resp.status(), 304, "{service_type} resends unchanged quote");
+    assert_eq!(etag_of(&resp), etag);
+
+    client
+        .patch(format!(
+            "http://{addr}/v1/quotes/{quote_id}/like?user_id={user_id}"
+        ))
+        .send()
+        .await
+        .expect("failed to like quote");
+    let resp = get(Some(etag.clone()))
+        .await
+        .expect("failed to receive quote from server");
+    assert_eq!(resp.status(), 200, "{service_type} serves stale quote");
+    assert_ne!(etag_of(&resp), etag);
+}
+
+/// Preflight and actual cross-origin requests answered by the configured CORS policy.
+async fn cors_policy(service_type: &str, addr: &str, client: &reqwest::Client) {
+    let preflight = |origin: &'static str, method: &'static str, path: &'static str| {
+        client
+            .request(reqwest::Method::OPTIONS, format!("http://{addr}{path}"))
+            .header("Origin", origin)
+            .header("Access-Control-Request-Method", method)
+            .header(
+                "Access-Control-Request-Headers",
+                "authorization, content-type",
+            )
+            .send()
+    };
+
+    for (method, path) in [("POST", "/graphql"), ("PUT", "/v1/admin/daily")] {
+        let resp = preflight(CORS_ORIGIN, method, path)
+            .await
+            .expect("failed to receive preflight response from server");
+        assert_eq!(resp.status(), 204, "{service_type} refuses {method} {path}");
+        let headers = resp.headers();
+        assert_eq!(headers["access-control-allow-origin"], CORS_ORIGIN);
+        assert!(headers["access-control-allow-methods"]
+            .to_str()
+            .unwrap()
+            .contains(method));
+        assert_eq!(
+            headers["access-control-allow-headers"],
+            "authorization, content-type"
+        );
+        assert_eq!(headers["access-control-max-age"], "300");
+    }
+
+    let resp = preflight("https://unknown.example", "GET", "/v1/heartbeat")
+        .await
+        .expect("failed to receive preflight response from server");
+    assert_eq!(resp.status(), 403, "{service_type} allows unknown origins");
+    assert!(!resp.headers().contains_key("access-control-allow-origin"));
+
+    let resp = preflight(CORS_ORIGIN, "DELETE", "/v1/heartbeat")
+        .await
+        .expect("failed to receive preflight response from server");
+    assert_eq!(resp.status(), 403, "{service_type} allows unknown methods");
+
+    let resp = client
+        .get(format!("http://{addr}/v1/heartbeat"))
+        .header("Origin", CORS_ORIGIN)
+        .send()
+        .await
+        .expect("failed to receive response from server");
+    assert_eq!(resp.status(), 200);
+    assert_eq!(resp.headers()["access-control-allow-origin"], CORS_ORIGIN);
+    assert_eq!(resp.headers()["vary"], "Origin");
+}
+
+/// `/v1` routes documented by the OpenAPI document, and the legacy aliases.
+async fn versioned_routes(service_type: &str, addr: &str, client: &reqwest::Client) {
+    let resp = client
+        .get(format!("http://{addr}/openapi.json"))
+        .send()
+        .await
+        .expect("failed to receive openapi document from server");
+    assert_eq!(resp.status(), 200, "{service_type} does not serve openapi");
+    let document: serde_json::Value = serde_json::from_str(
+        &resp
+            .text()
+            .await
+            .expect("failed to receive openapi document from server"),
+    )
+    .expect("failed to parse openapi document");
+    let paths = document["paths"]
+        .as_object()
+        .expect("openapi document has no paths");
+    assert!(paths.contains_key("/v1/quotes/{id}/similar"));
+
+    let user_id: String = uuid::UUIDv4.fake();
+    let resp = client
+        .get(format!("http://{addr}/v1/quotes/random?user_id={user_id}"))
+        .send()
+        .await
+        .expect("failed to receive quote from server");
+    assert_eq!(resp.status(), 200, "{service_type} does not serve /v1");
+    let quote: serde_json::Value = serde_json::from_str(
+        &resp
+            .text()
+            .await
+            .expect("failed to receive quote from server"),
+    )
+    .expect("failed to parse quote");
+    let quote_id = quote["id"].as_str().expect("quote has no id");
+
+    for (method, path) in [
+        (
+            reqwest::Method::PATCH,
+            format!("/v1/quotes/{quote_id}/like?user_id={user_id}"),
+        ),
+        (
+            reqwest::Method::GET,
+            format!("/v1/quotes/{quote_id}/similar?user_id={user_id}"),
+        ),
+        (
+            reqwest::Method::GET,
+            format!("/same?user_id={user_id}&quote_id={quote_id}"),
+        ),
+        (reqwest::Method::GET, "/swagger-ui".to_string()),
+    ] {
+        let resp = client
+            .request(method, format!("http://{addr}{path}"))
+            .send()
+            .await
+            .expect("failed to receive response from server");
+        assert_eq!(resp.status(), 200, "{service_type} does not serve {path}");
+    }
+}
+
+/// gRPC-Web calls and HTTP/JSON transcoding served next to gRPC.
+async fn grpc_browser_clients(addr: &str, client: &reqwest::Client) {
+    // empty message framed with its 5 bytes header
+    let resp = client
+        .post(format!("http://{addr}/quotes.Quotes/Heartbeat"))
+        .header(reqwest::header::CONTENT_TYPE, "application/grpc-web+proto")
+        .body(vec![0u8; 5])
+        .send()
+        .await
+        .expect("failed to receive grpc-web response from server");
+    assert_eq!(resp.status(), 200);
+    let body = resp
+        .bytes()
+        .await
+        .expect("failed to receive grpc-web response from server");
+    assert!(String::from_utf8_lossy(&body).contains("grpc-status:0"));
+
+    // browsers of the configured origins only
+    for (origin, allowed) in [(CORS_ORIGIN, true), ("https://unknown.example", false)] {
+        let resp = client
+            .request(
+                reqwest::Method::OPTIONS,
+                format!("http://{addr}/quotes.Quotes/Heartbeat"),
+            )
+            .header("Origin", origin)
+            .header("Access-Control-Request-Method", "POST")
+            .header("Access-Control-Request-Headers", "content-type, x-grpc-web")
+            .send()
+            .await
+            .expect("failed to receive preflight response from server");
+        let allow_origin = resp.headers().get("access-control-allow-origin");
+        assert_eq!(allow_origin.is_some_and(|value| value == origin), allowed);
+    }
+
+    let resp = client
+        .get(format!(
+            "http://{addr}/v1/quotes/random?user_id={}",
+            uuid::UUIDv4.fake::<String>()
+        ))
+        .send()
+        .await
+        .expect("failed to receive transcoded response from server");
+    assert_eq!(resp.status(), 200);
+    let quote: serde_json::Value = serde_json::from_str(
+        &resp
+            .text()
+            .await
+            .expect("failed to receive transcoded response from server"),
+    )
+    .expect("failed to parse transcoded response");
+    assert!(quote["id"].is_string());
+    assert!(quote["tags"].is_array());
+
+    let resp = client
+        .get(format!("http://{addr}/v1/tags?limit=-1"))
+        .send()
+        .await
+        .expect("failed to receive transcoded response from server");
+    assert_eq!(resp.status(), 400);
+    let error: serde_json::Value = serde_json::from_str(
+        &resp
+            .text()
+            .await
+            .expect("failed to receive transcoded error from server"),
+    )
+    .expect("failed to parse transcoded error");
+    assert_eq!(error["code"], 3);
+    assert_eq!(error["details"][1]["fieldViolations"][0]["field"], "limit");
+}
+
+/// Names of the root fields, deprecated ones included, lowercased without underscores.
+async fn graphql_operations(addr: &str, client: &reqwest::Client) -> BTreeSet<String> {
+    let query = "{ __schema { \
+        queryType { fields(includeDeprecated: true) { name } } \
+        mutationType { fields(includeDeprecated: true) { name } } \
+        subscriptionType { fields(includeDeprecated: true) { name } } } }";
+
+    let body = graphql(addr, client, query).await;
+    ["queryType", "mutationType", "subscriptionType"]
+        .iter()
+        .flat_map(|name| {
+            body["data"]["__schema"][name]["fields"]
+                .as_array()
+                .expect("graphql type has no fields")
+                .clone()
+        })
+        .map(|field| {
+            field["name"]
+                .as_str()
+                .unwrap()
+                .replace('_', "")
+                .to_lowercase()
+        })
+        .collect()
+}
+
+async fn graphql(addr: &str, client: &reqwest::Client, query: &str) -> serde_json::Value {
+    let (status, body) = graphql_request(addr, client, serde_json::json!({ "query": query })).await;
+    assert_eq!(status, 200);
+
+    body
+}
+
+async fn graphql_request(
+    addr: &str,
+    client: &reqwest::Client,
+    request: serde_json::Value,
+) -> (reqwest::StatusCode, serde_json::Value) {
+    let resp = client
+        .post(format!("http://{addr}/graphql"))
+        .header(reqwest::header::CONTENT_TYPE, "application/json")
+        .body(request.to_string())
+        .send()
+        .await
+        .expect("failed to receive graphql response from server");
+    let status = resp.status();
+
+    let body = resp
+        .text()
+        .await
+        .expect("failed to receive graphql response from server");
+    (
+        status,
+        serde_json::from_str(&body).expect("failed to parse graphql response"),
+    )
+}
+
+/// Names of the unary grpc methods as served by the reflection service, lowercased.
+async fn grpc_operations(addr: &str) -> BTreeSet<String> {
+    let channel = tonic::transport::Endpoint::from_shared(format!("http://{addr}"))
+        .expect("failed to parse grpc address")
+        .connect()
+        .await
+        .expect("failed to connect to grpc server");
+    let mut client = ServerReflectionClient::new(channel);
+
+    let request = ServerReflectionRequest {
+        host: String::new(),
+        message_request: Some(MessageRequest::FileContainingSymbol(
+            "quotes.Quotes".to_string(),
+        )),
+    };
+    let mut request = tonic::Request::new(tokio_stream::once(request));
+    request
+        .metadata_mut()
+        .insert("x-request-id", "parity-check".parse().unwrap());
+    let response = client
+        .server_reflection_info(request)
+        .await
+        .expect("failed to call reflection service");
+    assert_eq!(
+        response.metadata().get("x-request-id").unwrap(),
+        "parity-check"
+    );
+
+    let response = response
+        .into_inner()
+        .message()
+        .await
+        .expect("failed to receive reflection response")
+        .expect("reflection stream is empty");
+
+    let Some(MessageResponse::FileDescriptorResponse(descriptors)) = response.message_response
+    else {
+        panic!("unexpected reflection response");
+    };
+
+    descriptors
+        .file_descriptor_proto
+        .iter()
+        .map(|bytes| {
+            prost_types::FileDescriptorProto::decode(bytes.as_slice())
+                .expect("failed to decode file descriptor")
+        })
+        .flat_map(|file| file.service)
+        .filter(|service| service.name() == "Quotes")
+        .flat_map(|service| service.method)
+        .filter(|method| !method.client_streaming() && !method.server_streaming())
+        .map(|method| method.name().to_lowercase())
+        .collect()
+}